@@ -1,4 +1,6 @@
 use clap::Parser;
+#[cfg(feature = "pipeline")]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::utils::verifier;
@@ -21,6 +23,7 @@ pub enum Args {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Parser)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct ScreenshotArgs {
     #[arg(short, long, value_parser = verifier::path)]
     pub to: String,
@@ -54,6 +57,7 @@ impl ScreenshotArgs {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Parser)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct CropArgs {
     #[arg(long, value_parser = verifier::file)]
     pub source: String,
@@ -77,6 +81,7 @@ pub struct CropArgs {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Parser)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct ClipboardArgs {
     #[arg(long, value_parser = verifier::file)]
     pub source: String,
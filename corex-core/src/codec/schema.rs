@@ -1,4 +1,6 @@
 use clap::{Parser, Subcommand};
+#[cfg(feature = "pipeline")]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::utils::verifier;
@@ -55,6 +57,7 @@ pub enum HashAlgorithm {
 
 /// base64 编解码共用参数
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct Base64Args {
     /// 文本输入（与 --file 二选一）
     #[arg(long)]
@@ -67,6 +70,7 @@ pub struct Base64Args {
 
 /// md5 摘要参数
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct Md5Args {
     #[arg(long)]
     pub input: Option<String>,
@@ -100,11 +100,7 @@ fn hash(args: &HashArgs) -> Result<Output> {
     match &args.algorithm {
         HashAlgorithm::Md5(a) => {
             let bytes = read_bytes(a.input.as_deref(), a.file.as_deref())?;
-            let digest = Md5::digest(&bytes);
-            let text = digest
-                .iter()
-                .map(|byte| format!("{:02x}", byte))
-                .collect::<String>();
+            let text = md5_hex(&bytes);
             if let Some(path) = a.output.as_deref() {
                 validate_write_path(path)?;
             }
@@ -117,6 +113,14 @@ fn hash(args: &HashArgs) -> Result<Output> {
     }
 }
 
+/// md5 摘要（小写 hex）
+pub(crate) fn md5_hex(bytes: &[u8]) -> String {
+    Md5::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
 fn decode_text_for_ipc(bytes: &[u8]) -> Result<String> {
     match std::str::from_utf8(bytes) {
         Ok(text) => Ok(text.to_string()),
@@ -25,6 +25,8 @@ use crate::pipeline;
 use crate::scan;
 #[cfg(feature = "schedule")]
 use crate::schedule;
+#[cfg(feature = "secret")]
+use crate::secret;
 #[cfg(feature = "watch")]
 use crate::watch;
 #[cfg(feature = "capture")]
@@ -98,6 +100,10 @@ pub enum Commands {
     /// 执行 Pipeline
     #[cfg(feature = "pipeline")]
     Pipeline(pipeline::config::PipelineArgs),
+    /// 输出 pipelines.yaml 的 JSON Schema
+    #[cfg(feature = "pipeline")]
+    #[command(subcommand)]
+    Schema(pipeline::jsonschema::Args),
     /// 任务调度器
     #[cfg(feature = "schedule")]
     #[command(subcommand)]
@@ -106,6 +112,10 @@ pub enum Commands {
     #[cfg(feature = "watch")]
     #[command(subcommand)]
     Watch(watch::schema::Args),
+    /// 本地加密密钥（`${secret.*}`）
+    #[cfg(feature = "secret")]
+    #[command(subcommand)]
+    Secret(secret::schema::Args),
 }
 
 /// 分发命令到对应处理器
@@ -127,6 +137,8 @@ pub fn dispatch(args: Args) -> Result<()> {
         Commands::Schedule(a) => schedule::run(&a),
         #[cfg(feature = "watch")]
         Commands::Watch(a) => watch::run(&a),
+        #[cfg(feature = "secret")]
+        Commands::Secret(a) => secret::run(&a),
         #[cfg(feature = "generate")]
         Commands::Generate(a) => generate::run(&a),
         #[cfg(feature = "engine")]
@@ -141,5 +153,7 @@ pub fn dispatch(args: Args) -> Result<()> {
         Commands::Compression(a) => compression::run(&a),
         #[cfg(feature = "pipeline")]
         Commands::Pipeline(a) => pipeline::run(&a),
+        #[cfg(feature = "pipeline")]
+        Commands::Schema(a) => pipeline::jsonschema::run(&a),
     }
 }
@@ -6,4 +6,4 @@ mod zip;
 pub use collect::collect_files;
 pub use seven_z::{compress_seven_z, decompress_seven_z};
 pub use tar_gz::{compress_tar_gz, decompress_tar_gz};
-pub use zip::{compress_zip, decompress_zip};
+pub use zip::{ZipAppender, compress_zip, decompress_zip};
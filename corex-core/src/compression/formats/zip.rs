@@ -1,6 +1,6 @@
 use std::fs::{self, File};
 use std::io::copy as io_copy;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 use anyhow::{Context, Result, bail};
@@ -52,34 +52,12 @@ pub fn compress_zip(args: &ZipFormatArgs) -> Result<()> {
         let rel_path = path
             .strip_prefix(from)
             .map_err(|_| anyhow::anyhow!("路径计算失败: {}", path.display()))?;
-        let zip_path = rel_path
-            .components()
-            .map(|c| c.as_os_str().to_string_lossy().into_owned())
-            .collect::<Vec<_>>()
-            .join("/");
+        let zip_path = zip_entry_name(rel_path);
 
         let file_size = path.metadata().map(|m| m.len()).unwrap_or(0);
         total_bytes += file_size;
 
-        let mut options: FileOptions<'_, ()> = FileOptions::default()
-            .compression_method(zip_method(&args.method))
-            .compression_level(Some(args.level as i64));
-
-        if args.encryption != ZipEncryption::None {
-            let password = args
-                .io
-                .password
-                .as_deref()
-                .context("encryption 需要 password")?;
-            let mode = match args.encryption {
-                ZipEncryption::Aes128 => AesMode::Aes128,
-                ZipEncryption::Aes256 => AesMode::Aes256,
-                ZipEncryption::None => unreachable!(),
-            };
-            options = options.with_aes_encryption(mode, password);
-        }
-
-        zip.start_file(&zip_path, options)?;
+        zip.start_file(&zip_path, entry_options(args)?)?;
         let mut file = File::open(path).with_context(|| format!("打开文件: {}", path.display()))?;
         io_copy(&mut file, &mut zip).with_context(|| format!("压缩文件: {}", path.display()))?;
         pb.inc(1);
@@ -97,6 +75,74 @@ pub fn compress_zip(args: &ZipFormatArgs) -> Result<()> {
     Ok(())
 }
 
+/// 逐文件追加写入 ZIP（流式 sink 使用）
+pub struct ZipAppender {
+    zip: ZipWriter<File>,
+    args: ZipFormatArgs,
+}
+
+impl ZipAppender {
+    pub fn create(args: &ZipFormatArgs) -> Result<Self> {
+        validate_zip_compress(args)?;
+        let to = Path::new(&args.to);
+        if let Some(parent) = to.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("创建输出目录: {}", parent.display()))?;
+        }
+        let output_file =
+            File::create(to).with_context(|| format!("创建输出文件: {}", to.display()))?;
+        Ok(Self {
+            zip: ZipWriter::new(output_file),
+            args: args.clone(),
+        })
+    }
+
+    /// 以相对路径 `rel` 写入文件 `path`
+    pub fn append(&mut self, path: &Path, rel: &Path) -> Result<()> {
+        self.zip
+            .start_file(zip_entry_name(rel), entry_options(&self.args)?)?;
+        let mut file = File::open(path).with_context(|| format!("打开文件: {}", path.display()))?;
+        io_copy(&mut file, &mut self.zip)
+            .with_context(|| format!("压缩文件: {}", path.display()))?;
+        Ok(())
+    }
+
+    /// 写入中央目录，返回归档路径
+    pub fn finish(self) -> Result<PathBuf> {
+        self.zip.finish()?;
+        Ok(PathBuf::from(&self.args.to))
+    }
+}
+
+fn zip_entry_name(rel: &Path) -> String {
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn entry_options(args: &ZipFormatArgs) -> Result<FileOptions<'_, ()>> {
+    let options: FileOptions<'_, ()> = FileOptions::default()
+        .compression_method(zip_method(&args.method))
+        .compression_level(Some(args.level as i64));
+    if args.encryption == ZipEncryption::None {
+        return Ok(options);
+    }
+    let password = args
+        .io
+        .password
+        .as_deref()
+        .context("encryption 需要 password")?;
+    let mode = match args.encryption {
+        ZipEncryption::Aes128 => AesMode::Aes128,
+        ZipEncryption::Aes256 => AesMode::Aes256,
+        ZipEncryption::None => unreachable!(),
+    };
+    Ok(options.with_aes_encryption(mode, password))
+}
+
 pub fn decompress_zip(args: &ZipDecompressArgs) -> Result<()> {
     let from = Path::new(&args.from);
     let to = Path::new(&args.to);
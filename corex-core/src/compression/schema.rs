@@ -1,4 +1,6 @@
 use clap::{Parser, Subcommand, ValueEnum};
+#[cfg(feature = "pipeline")]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::utils::verifier;
@@ -46,6 +48,7 @@ pub enum DecompressFormat {
 
 /// 归档 IO 共用字段（压缩/解压）
 #[derive(Debug, Parser, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct ArchiveIoArgs {
     #[arg(long, value_delimiter = ',')]
     #[serde(default)]
@@ -86,6 +89,7 @@ fn default_seven_level() -> u32 {
 
 /// ZIP 压缩参数
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct ZipFormatArgs {
     #[arg(short, long, value_parser = verifier::path)]
     pub from: String,
@@ -113,6 +117,7 @@ pub struct ZipFormatArgs {
 
 /// ZIP 解压参数
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct ZipDecompressArgs {
     #[arg(short, long, value_parser = verifier::path)]
     pub from: String,
@@ -129,6 +134,7 @@ pub struct ZipDecompressArgs {
 
 /// tar.gz 压缩参数
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct TarGzFormatArgs {
     #[arg(short, long, value_parser = verifier::path)]
     pub from: String,
@@ -151,6 +157,7 @@ pub struct TarGzFormatArgs {
 
 /// tar.gz 解压参数
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct TarGzDecompressArgs {
     #[arg(short, long, value_parser = verifier::path)]
     pub from: String,
@@ -167,6 +174,7 @@ pub struct TarGzDecompressArgs {
 
 /// 7z 压缩参数
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct SevenZFormatArgs {
     #[arg(short, long, value_parser = verifier::path)]
     pub from: String,
@@ -192,6 +200,7 @@ pub struct SevenZFormatArgs {
 
 /// 7z 解压参数
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct SevenZDecompressArgs {
     #[arg(short, long, value_parser = verifier::path)]
     pub from: String,
@@ -207,6 +216,7 @@ pub struct SevenZDecompressArgs {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq, ValueEnum)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum ZipMethod {
     #[default]
@@ -217,6 +227,7 @@ pub enum ZipMethod {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq, ValueEnum)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum ZipEncryption {
     #[default]
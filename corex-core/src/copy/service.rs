@@ -10,7 +10,7 @@ use indicatif::ProgressBar;
 use walkdir::WalkDir;
 
 use crate::copy::schema::Args;
-use crate::utils::{file, notify, progress, Filter};
+use crate::utils::{cancel, file, notify, observe, progress, Filter};
 
 #[derive(Debug, Clone)]
 pub struct Output {
@@ -37,28 +37,58 @@ pub fn execute(args: &Args) -> Result<Output> {
     Ok(Output { path })
 }
 
+/// 逐文件复制（流式 stage 使用）：每写入一个文件即回调目标路径，回调返回错误时停止遍历。
+pub fn copy_each(args: &Args, mut on_file: impl FnMut(PathBuf) -> Result<()>) -> Result<u64> {
+    let from = Path::new(&args.from);
+    let to = Path::new(&args.to);
+
+    if from.is_file() {
+        let target = single_target(from, to)?;
+        fs::copy(from, &target)
+            .with_context(|| format!("复制文件失败: {:?} -> {:?}", from, target))?;
+        on_file(target)?;
+        return Ok(1);
+    }
+    if !from.is_dir() {
+        anyhow::bail!("源路径不存在或不是有效的文件/目录: {}", args.from);
+    }
+
+    let filter = Filter::new(&args.includes, &args.excludes);
+    let hidden = Arc::new(ProgressBar::hidden());
+    copy_dir(from, to, args.empty, &filter, hidden, &mut on_file)
+}
+
+/// 单文件复制的目标路径：`to` 为目录时保持原名，否则确保父目录存在。
+fn single_target(from: &Path, to: &Path) -> Result<PathBuf> {
+    if to.is_dir() {
+        return Ok(to.join(from.file_name().unwrap_or_default()));
+    }
+    if let Some(parent) = to.parent()
+        && !parent.exists()
+    {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("创建目标目录失败: {}", parent.display()))?;
+    }
+    Ok(to.to_path_buf())
+}
+
 /// 复制单个文件；若 `to` 为目录则写入该目录并保持原名。
 fn copy_single_file(from: &Path, to: &Path) -> Result<PathBuf> {
-    let target = if to.is_dir() {
-        to.join(from.file_name().unwrap_or_default())
-    } else {
-        if let Some(parent) = to.parent()
-            && !parent.exists()
-        {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("创建目标目录失败: {}", parent.display()))?;
-        }
-        to.to_path_buf()
-    };
+    let target = single_target(from, to)?;
 
     let size = fs::metadata(from)?.len();
 
-    println!("📄 复制文件: {} → {}", from.display(), target.display());
+    let human = !crate::runtime::is_quiet() && !crate::runtime::is_json_output();
+    if human {
+        println!("📄 复制文件: {} → {}", from.display(), target.display());
+    }
 
     fs::copy(from, &target).with_context(|| format!("复制文件失败: {:?} -> {:?}", from, target))?;
 
     let _ = notify::success("复制成功", "文件复制操作已成功完成");
-    println!("✅ 完成，大小: {}", file::size(size));
+    if human {
+        println!("✅ 完成，大小: {}", file::size(size));
+    }
     Ok(target)
 }
 
@@ -72,10 +102,12 @@ fn copy_directory(from: &Path, to: &Path, args: &Args) -> Result<PathBuf> {
         anyhow::bail!("没有文件需要复制");
     }
 
-    println!("📊 找到 {} 个文件，总大小: {}", count, file::size(size));
+    if !crate::runtime::is_quiet() && !crate::runtime::is_json_output() {
+        println!("📊 找到 {} 个文件，总大小: {}", count, file::size(size));
+    }
 
     let pb = progress::progress(count);
-    let status = copy_dir(from, to, args.empty, &filter, pb);
+    let status = copy_dir(from, to, args.empty, &filter, pb, &mut |_| Ok(()));
 
     match &status {
         Ok(_) => {
@@ -124,13 +156,15 @@ fn finish(progress: &ProgressBar, stats: &CopyStats, start_time: Instant) {
     ));
 }
 
+/// 递归复制目录，每写入一个文件即回调目标路径，返回复制的文件数。
 fn copy_dir(
     from: &Path,
     to: &Path,
     empty: bool,
     filter: &Filter,
     progress: Arc<ProgressBar>,
-) -> Result<()> {
+    on_file: &mut dyn FnMut(PathBuf) -> Result<()>,
+) -> Result<u64> {
     ensure_dir(to)?;
 
     if empty {
@@ -144,6 +178,7 @@ fn copy_dir(
     let entries = WalkDir::new(from).into_iter().filter_map(Result::ok);
 
     for entry in entries {
+        cancel::check()?;
         let source = entry.path();
         let relative = source.strip_prefix(from).context("路径解析失败")?;
         let target = to.join(relative);
@@ -156,11 +191,12 @@ fn copy_dir(
             ensure_dir(&target)?;
         } else if source.is_file() {
             copy_file(source, &target, &mut stats, &progress, start)?;
+            on_file(target)?;
         }
     }
 
     finish(&progress, &stats, start);
-    Ok(())
+    Ok(stats.files)
 }
 
 /// 复制单个文件
@@ -183,6 +219,7 @@ fn copy_file(
 
     stats.add(size);
     progress.set_position(stats.files);
+    observe::report(stats.files, Some(stats.bytes));
 
     Ok(())
 }
@@ -236,6 +273,7 @@ fn calc_size(from: &Path, filter: &Filter) -> Result<(u64, u64)> {
     let entries = WalkDir::new(from).into_iter().filter_map(|e| e.ok());
 
     for entry in entries {
+        cancel::check()?;
         let path = entry.path();
 
         if let Ok(relative) = path.strip_prefix(from)
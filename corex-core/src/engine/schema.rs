@@ -1,4 +1,6 @@
 use clap::Parser;
+#[cfg(feature = "pipeline")]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// engine 子命令
@@ -13,6 +15,7 @@ fn default_one() -> String {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct SuggestionArgs {
     /// 页面类型（如 page.home）
     #[arg(long)]
@@ -1,8 +1,11 @@
 use clap::{ArgAction, Parser, ValueEnum};
+#[cfg(feature = "pipeline")]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// stdout 捕获模式
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, ValueEnum)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum CaptureMode {
     /// 解析 stdout 最后一行 JSON（须含 path + data）
@@ -21,6 +24,7 @@ pub enum Args {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct RunArgs {
     /// 脚本或可执行文件路径
     #[arg(short, long)]
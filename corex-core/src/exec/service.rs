@@ -1,16 +1,19 @@
 use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Child, Command, ExitStatus, Stdio};
 use std::thread;
+use std::time::Duration;
 
 use anyhow::{Context, Result, bail};
 use serde::Deserialize;
 use serde_json::{Map, Value};
 
 use crate::exec::schema::{Args, CaptureMode, RunArgs};
+use crate::utils::{cancel, redact};
 
 const STDERR_TAIL_MAX: usize = 2048;
+const WAIT_POLL: Duration = Duration::from_millis(50);
 
 #[derive(Debug, Clone)]
 pub struct Output {
@@ -80,6 +83,12 @@ fn run_script(args: &RunArgs) -> Result<Output> {
 
     let live = !crate::runtime::is_quiet() && !crate::runtime::is_json_output();
     cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    // 可取消时脚本独占进程组，超时 / 取消时连同其派生的子进程一并终止
+    #[cfg(unix)]
+    if cancel::current().is_some() {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
 
     let mut child = cmd
         .spawn()
@@ -97,9 +106,7 @@ fn run_script(args: &RunArgs) -> Result<Output> {
     let stdout_thread = thread::spawn(move || stream_pipe(stdout_pipe, live, false));
     let stderr_thread = thread::spawn(move || stream_pipe(stderr_pipe, live, true));
 
-    let status = child
-        .wait()
-        .with_context(|| format!("等待脚本结束失败: {}", args.script))?;
+    let status = wait_child(&mut child, &args.script)?;
     let exit_code = status.code().unwrap_or(-1);
 
     let stdout = stdout_thread
@@ -139,31 +146,84 @@ fn run_script(args: &RunArgs) -> Result<Output> {
     }
 }
 
-/// 边读边回显，同时收集完整输出供 capture 解析。
+/// 等待子进程结束；当前线程绑定了取消令牌时轮询，取消后终止子进程及其派生进程。
+fn wait_child(child: &mut Child, script: &str) -> Result<ExitStatus> {
+    let Some(token) = cancel::current() else {
+        return child
+            .wait()
+            .with_context(|| format!("等待脚本结束失败: {script}"));
+    };
+    loop {
+        if let Some(status) = child
+            .try_wait()
+            .with_context(|| format!("等待脚本结束失败: {script}"))?
+        {
+            return Ok(status);
+        }
+        if token.is_cancelled() {
+            kill_tree(child);
+            let _ = child.wait();
+            bail!("脚本已取消并终止: {script}");
+        }
+        thread::sleep(WAIT_POLL);
+    }
+}
+
+/// 终止脚本及其派生的全部子进程（unix 为整个进程组，Windows 为进程树）
+fn kill_tree(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        // SAFETY: 向脚本所在进程组发送 SIGKILL，进程组在 spawn 时以 process_group(0) 创建
+        unsafe {
+            libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+        }
+    }
+    #[cfg(windows)]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &child.id().to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+    let _ = child.kill();
+}
+
+/// 边读边回显（按行脱敏密钥值），同时收集完整输出供 capture 解析；返回值已脱敏。
 fn stream_pipe(mut pipe: impl Read, live: bool, is_stderr: bool) -> String {
     let mut bytes = Vec::new();
     let mut chunk = [0u8; 4096];
+    // 已回显到的位置：只回显完整行，避免密钥值被分块截断后漏过脱敏
+    let mut echoed = 0;
     loop {
         match pipe.read(&mut chunk) {
             Ok(0) => break,
             Ok(n) => {
-                let piece = &chunk[..n];
-                bytes.extend_from_slice(piece);
-                if live {
-                    let text = String::from_utf8_lossy(piece);
-                    if is_stderr {
-                        eprint!("{text}");
-                        let _ = std::io::stderr().flush();
-                    } else {
-                        print!("{text}");
-                        let _ = std::io::stdout().flush();
-                    }
+                bytes.extend_from_slice(&chunk[..n]);
+                if live && let Some(end) = bytes[echoed..].iter().rposition(|b| *b == b'\n') {
+                    let end = echoed + end + 1;
+                    echo(&bytes[echoed..end], is_stderr);
+                    echoed = end;
                 }
             }
             Err(_) => break,
         }
     }
-    String::from_utf8_lossy(&bytes).into_owned()
+    if live && echoed < bytes.len() {
+        echo(&bytes[echoed..], is_stderr);
+    }
+    redact::mask(&String::from_utf8_lossy(&bytes))
+}
+
+fn echo(piece: &[u8], is_stderr: bool) {
+    let text = redact::mask(&String::from_utf8_lossy(piece));
+    if is_stderr {
+        eprint!("{text}");
+        let _ = std::io::stderr().flush();
+    } else {
+        print!("{text}");
+        let _ = std::io::stdout().flush();
+    }
 }
 
 fn build_command(script: &Path, args: &[String]) -> Result<Command> {
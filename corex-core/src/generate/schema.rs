@@ -1,4 +1,6 @@
 use clap::{ArgAction, Parser};
+#[cfg(feature = "pipeline")]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::utils::verifier;
@@ -20,9 +22,11 @@ pub enum Args {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct CvidArgs {}
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct UuidArgs {
     #[arg(short, long, default_value = "1", help = "生成 UUID 的数量")]
     pub count: usize,
@@ -40,6 +44,7 @@ pub struct UuidArgs {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct PathArgs {
     #[arg(short, long, value_parser = verifier::path)]
     pub from: String,
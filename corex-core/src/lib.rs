@@ -11,6 +11,8 @@ pub mod command;
 pub mod pipeline;
 #[cfg(feature = "schedule")]
 pub mod schedule;
+#[cfg(feature = "secret")]
+pub mod secret;
 #[cfg(feature = "watch")]
 pub mod watch;
 pub mod utils;
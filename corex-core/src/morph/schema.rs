@@ -1,4 +1,6 @@
 use clap::Parser;
+#[cfg(feature = "pipeline")]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::utils::verifier;
@@ -42,12 +44,14 @@ pub enum Args {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct MetaArgs {
     #[arg(long, value_parser = verifier::path)]
     pub path: String,
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct RenderArgs {
     #[arg(long, value_parser = verifier::path)]
     pub path: String,
@@ -59,6 +63,7 @@ pub struct RenderArgs {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct ThumbnailsArgs {
     #[arg(long, value_parser = verifier::path)]
     pub path: String,
@@ -67,6 +72,7 @@ pub struct ThumbnailsArgs {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct MatchArgs {
     #[arg(long, value_parser = verifier::path)]
     pub path: String,
@@ -75,6 +81,7 @@ pub struct MatchArgs {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct ExportArgs {
     #[arg(long, value_parser = verifier::path)]
     pub src: String,
@@ -83,6 +90,7 @@ pub struct ExportArgs {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct MergeArgs {
     #[arg(long, value_delimiter = ',')]
     pub paths: Vec<String>,
@@ -91,6 +99,7 @@ pub struct MergeArgs {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct StackArgs {
     #[arg(long, value_parser = verifier::path)]
     pub path: String,
@@ -101,6 +110,7 @@ pub struct StackArgs {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct SplitArgs {
     #[arg(long, value_parser = verifier::path)]
     pub path: String,
@@ -143,6 +153,7 @@ impl SplitArgs {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct ImagesArgs {
     #[arg(long, value_parser = verifier::path)]
     pub path: String,
@@ -155,6 +166,7 @@ pub struct ImagesArgs {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct DocumentArgs {
     #[arg(long, value_parser = verifier::path)]
     pub path: String,
@@ -165,6 +177,7 @@ pub struct DocumentArgs {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct ReorderArgs {
     #[arg(long, value_parser = verifier::path)]
     pub path: String,
@@ -176,6 +189,7 @@ pub struct ReorderArgs {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct RotateArgs {
     #[arg(long, value_parser = verifier::path)]
     pub path: String,
@@ -190,6 +204,7 @@ pub struct RotateArgs {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct RemoveArgs {
     #[arg(long, value_parser = verifier::path)]
     pub path: String,
@@ -201,6 +216,7 @@ pub struct RemoveArgs {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct ExtractArgs {
     #[arg(long, value_parser = verifier::path)]
     pub path: String,
@@ -0,0 +1,218 @@
+//! 步骤增量缓存：按 inputs / outputs 指纹跳过未变化的步骤
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::codec::service::md5_hex;
+use crate::invoke::Artifact;
+
+use super::config::StepConfig;
+use super::context::PipelineContext;
+
+/// 指纹方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Fingerprint {
+    /// 文件大小 + 修改时间（默认）
+    #[default]
+    Mtime,
+    /// 文件内容哈希
+    Content,
+}
+
+impl Fingerprint {
+    pub fn is_mtime(&self) -> bool {
+        *self == Fingerprint::Mtime
+    }
+}
+
+/// 缓存记录（`<cache>/<pipeline>/<step>/state.json`）
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheState {
+    inputs: String,
+    outputs: String,
+    artifact: Artifact,
+    items: u64,
+}
+
+/// 单个步骤的缓存句柄
+pub struct StepCache {
+    path: PathBuf,
+    outputs: Vec<String>,
+    mode: Fingerprint,
+    inputs_key: String,
+}
+
+impl StepCache {
+    /// 计算当前 inputs 指纹；步骤未声明 inputs 时返回 None（不缓存）
+    pub fn open(
+        pipeline_id: &str,
+        step: &StepConfig,
+        ctx: &PipelineContext,
+    ) -> Result<Option<Self>> {
+        if step.inputs.is_empty() {
+            return Ok(None);
+        }
+        let inputs: Vec<String> = step.inputs.iter().map(|p| ctx.parse(p)).collect();
+        // 步骤定义（解析后的 params 等）变化同样使缓存失效
+        let recipe = serde_json::json!({
+            "module": step.module,
+            "action": step.action,
+            "format": step.format,
+            "algorithm": step.algorithm,
+            "params": ctx.parse_value(&step.params),
+            "outputs": step.outputs,
+        });
+        let inputs_key =
+            md5_hex(format!("{}\n{}", recipe, fingerprint(&inputs, step.fingerprint)?).as_bytes());
+        Ok(Some(Self {
+            path: cache_root()?
+                .join(sanitize(pipeline_id))
+                .join(sanitize(&step.id))
+                .join("state.json"),
+            outputs: step.outputs.iter().map(|p| ctx.parse(p)).collect(),
+            mode: step.fingerprint,
+            inputs_key,
+        }))
+    }
+
+    /// inputs 与 outputs 均未变化时返回上次的产物
+    pub fn hit(&self) -> Option<(Artifact, u64)> {
+        let content = fs::read_to_string(&self.path).ok()?;
+        let state: CacheState = serde_json::from_str(&content).ok()?;
+        if state.inputs != self.inputs_key {
+            return None;
+        }
+        let outputs = fingerprint(&self.outputs, self.mode).ok()?;
+        (state.outputs == outputs).then_some((state.artifact, state.items))
+    }
+
+    /// 步骤成功后写入缓存
+    pub fn store(&self, artifact: &Artifact, items: u64) -> Result<()> {
+        let state = CacheState {
+            inputs: self.inputs_key.clone(),
+            outputs: fingerprint(&self.outputs, self.mode)?,
+            artifact: artifact.clone(),
+            items,
+        };
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("创建缓存目录失败: {}", parent.display()))?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&state)?)
+            .with_context(|| format!("写入缓存失败: {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+/// 缓存根目录：`COREX_CACHE_DIR` 或 `~/.corex/cache`
+fn cache_root() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("COREX_CACHE_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    Ok(dirs::home_dir()
+        .context("无法获取用户目录")?
+        .join(".corex")
+        .join("cache"))
+}
+
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// glob 展开后的文件指纹；匹配到目录时计入其下全部文件
+fn fingerprint(patterns: &[String], mode: Fingerprint) -> Result<String> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    for pattern in patterns {
+        let matches =
+            glob::glob(pattern).with_context(|| format!("无效的 glob 模式: {pattern}"))?;
+        for path in matches.filter_map(Result::ok) {
+            if path.is_dir() {
+                files.extend(
+                    WalkDir::new(&path)
+                        .into_iter()
+                        .filter_map(Result::ok)
+                        .filter(|e| e.file_type().is_file())
+                        .map(|e| e.into_path()),
+                );
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files.dedup();
+
+    let mut lines = Vec::with_capacity(files.len());
+    for file in &files {
+        lines.push(format!("{}\t{}", file.display(), file_key(file, mode)?));
+    }
+    Ok(md5_hex(lines.join("\n").as_bytes()))
+}
+
+fn file_key(path: &Path, mode: Fingerprint) -> Result<String> {
+    match mode {
+        Fingerprint::Mtime => {
+            let meta = fs::metadata(path)
+                .with_context(|| format!("读取文件信息失败: {}", path.display()))?;
+            let mtime = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos())
+                .unwrap_or(0);
+            Ok(format!("{}\t{}", meta.len(), mtime))
+        }
+        Fingerprint::Content => {
+            let bytes =
+                fs::read(path).with_context(|| format!("读取文件失败: {}", path.display()))?;
+            Ok(md5_hex(&bytes))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_tracks_content_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src").join("a.txt"), "a").unwrap();
+        let patterns = vec![dir.path().join("src").display().to_string()];
+
+        let before = fingerprint(&patterns, Fingerprint::Content).unwrap();
+        assert_eq!(
+            before,
+            fingerprint(&patterns, Fingerprint::Content).unwrap()
+        );
+
+        fs::write(dir.path().join("src").join("a.txt"), "b").unwrap();
+        assert_ne!(
+            before,
+            fingerprint(&patterns, Fingerprint::Content).unwrap()
+        );
+
+        fs::write(dir.path().join("src").join("c.txt"), "c").unwrap();
+        let glob = vec![dir.path().join("src").join("*.md").display().to_string()];
+        assert_eq!(
+            fingerprint(&glob, Fingerprint::Mtime).unwrap(),
+            fingerprint(&[], Fingerprint::Mtime).unwrap()
+        );
+    }
+}
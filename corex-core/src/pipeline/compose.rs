@@ -0,0 +1,569 @@
+//! 配置组合：多文件合并、`include` 其他文件、`templates` 步骤模板（`use:`）、Pipeline `extends` 继承、`profiles` 覆盖
+//!
+//! 在反序列化为 [`PipelinesConfig`] 之前于原始文档上解析，错误信息带来源文件路径。
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Value};
+
+use super::config::{CONFIG_VERSION, PipelineConfig, PipelinesConfig};
+
+/// 继承时不沿用的字段（触发源只属于声明它的 Pipeline）
+const NOT_INHERITED: [&str; 3] = ["id", "schedule", "watch"];
+
+/// 含步骤列表的字段
+const PHASES: [&str; 3] = ["steps", "on_failure", "finally"];
+
+/// 读取配置文件并解析 include / templates / extends
+pub fn compose(path: &Path) -> Result<PipelinesConfig> {
+    compose_files(&[path.to_path_buf()], None)
+}
+
+/// 按顺序合并多个配置文件（后者的 variables 覆盖前者，Pipeline ID 不可重复），再叠加 `profile`
+pub fn compose_files(paths: &[PathBuf], profile: Option<&str>) -> Result<PipelinesConfig> {
+    let mut composer = Composer::default();
+    for path in paths {
+        let root = composer.load(path)?;
+        let version = match root.get("version") {
+            Some(v) => v
+                .as_u64()
+                .with_context(|| format!("{}: version 须为整数", path.display()))?,
+            None => bail!("{}: 缺少 version", path.display()),
+        };
+        if version != u64::from(CONFIG_VERSION) {
+            bail!("配置 version 必须为 {}，当前为 {}", CONFIG_VERSION, version);
+        }
+    }
+
+    let profile = profile.map(|name| composer.profile(name)).transpose()?;
+    if let Some(profile) = &profile {
+        composer.variables.extend(profile.variables.clone());
+    }
+    let pipelines = composer
+        .pipelines
+        .iter()
+        .map(|(id, _, origin)| composer.resolve(id, origin, profile.as_ref()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(PipelinesConfig {
+        version: CONFIG_VERSION,
+        variables: composer.variables,
+        pipelines,
+    })
+}
+
+/// 选中的 profile：顶层 variables 与按 Pipeline ID 的覆盖
+struct Profile {
+    name: String,
+    variables: HashMap<String, String>,
+    /// Pipeline ID → `{ variables, steps: { 步骤 ID → 步骤字段 } }`
+    pipelines: Map<String, Value>,
+}
+
+#[derive(Default)]
+struct Composer {
+    /// 正在加载的文件（检测 include 循环）
+    stack: Vec<PathBuf>,
+    /// 已合并的文件（按规范化路径）
+    loaded: HashSet<PathBuf>,
+    variables: HashMap<String, String>,
+    templates: HashMap<String, (Map<String, Value>, PathBuf)>,
+    /// `(id, 原始定义, 来源文件)`，按出现顺序
+    pipelines: Vec<(String, Map<String, Value>, PathBuf)>,
+    /// 同名 profile 跨文件深度合并
+    profiles: Map<String, Value>,
+}
+
+impl Composer {
+    /// 加载单个文件：先加载其 include（本文件的 variables 覆盖被包含文件）
+    fn load(&mut self, path: &Path) -> Result<Map<String, Value>> {
+        let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if self.stack.contains(&key) {
+            bail!("{}: include 循环引用", path.display());
+        }
+
+        let file = path.display();
+        let doc = match read_document(path)? {
+            Value::Object(doc) => doc,
+            _ => bail!("{file}: 顶层须为对象"),
+        };
+        // 菱形 include（多个文件包含同一文件）只合并一次
+        if !self.loaded.insert(key.clone()) {
+            return Ok(doc);
+        }
+        self.stack.push(key);
+        if let Some(version) = doc.get("version")
+            && version.as_u64() != Some(u64::from(CONFIG_VERSION))
+        {
+            bail!("{file}: 配置 version 必须为 {CONFIG_VERSION}，当前为 {version}");
+        }
+
+        let includes = match doc.get("include") {
+            None => Vec::new(),
+            Some(Value::String(s)) => vec![s.clone()],
+            Some(Value::Array(items)) => items
+                .iter()
+                .map(|v| v.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()
+                .with_context(|| format!("{file}: include 须为路径或路径列表"))?,
+            Some(_) => bail!("{file}: include 须为路径或路径列表"),
+        };
+        let dir = path.parent().unwrap_or(Path::new("."));
+        for include in includes {
+            self.load(&dir.join(&include))
+                .with_context(|| format!("{file}: include '{include}' 失败"))?;
+        }
+
+        if let Some(variables) = doc.get("variables") {
+            let variables = variables
+                .as_object()
+                .with_context(|| format!("{file}: variables 须为对象"))?;
+            for (k, v) in variables {
+                let v = v
+                    .as_str()
+                    .with_context(|| format!("{file}: variables.{k} 须为字符串"))?;
+                self.variables.insert(k.clone(), v.to_string());
+            }
+        }
+
+        if let Some(templates) = doc.get("templates") {
+            let templates = templates
+                .as_object()
+                .with_context(|| format!("{file}: templates 须为对象"))?;
+            for (name, template) in templates {
+                let template = template
+                    .as_object()
+                    .with_context(|| format!("{file}: 模板 '{name}' 须为对象"))?;
+                if let Some((_, other)) = self.templates.get(name) {
+                    bail!("{file}: 模板 '{name}' 与 {} 中的定义重复", other.display());
+                }
+                self.templates
+                    .insert(name.clone(), (template.clone(), path.to_path_buf()));
+            }
+        }
+
+        if let Some(profiles) = doc.get("profiles") {
+            let profiles = profiles
+                .as_object()
+                .with_context(|| format!("{file}: profiles 须为对象"))?;
+            for (name, profile) in profiles {
+                if !profile.is_object() {
+                    bail!("{file}: profile '{name}' 须为对象");
+                }
+                let slot = self
+                    .profiles
+                    .entry(name.clone())
+                    .or_insert_with(|| Value::Object(Map::new()));
+                merge_value(slot, profile.clone());
+            }
+        }
+
+        if let Some(pipelines) = doc.get("pipelines") {
+            let pipelines = pipelines
+                .as_array()
+                .with_context(|| format!("{file}: pipelines 须为列表"))?;
+            for pipeline in pipelines {
+                let pipeline = pipeline
+                    .as_object()
+                    .with_context(|| format!("{file}: pipelines 中的项须为对象"))?;
+                let id = pipeline
+                    .get("id")
+                    .and_then(Value::as_str)
+                    .with_context(|| format!("{file}: Pipeline 缺少 id"))?;
+                if let Some((_, _, other)) = self.pipelines.iter().find(|(p, _, _)| p == id) {
+                    bail!(
+                        "{file}: Pipeline '{id}' 与 {} 中的定义重复",
+                        other.display()
+                    );
+                }
+                self.pipelines
+                    .push((id.to_string(), pipeline.clone(), path.to_path_buf()));
+            }
+        }
+
+        self.stack.pop();
+        Ok(doc)
+    }
+
+    /// 取出 profile 并校验其引用的 Pipeline 存在
+    fn profile(&self, name: &str) -> Result<Profile> {
+        let Some(profile) = self.profiles.get(name) else {
+            let mut names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            names.sort();
+            if names.is_empty() {
+                bail!("未知的 profile '{name}'（配置中未定义 profiles）");
+            }
+            bail!("未知的 profile '{name}'（可用 {}）", names.join(" / "));
+        };
+        let variables = match profile.get("variables") {
+            None => HashMap::new(),
+            Some(v) => serde_json::from_value(v.clone())
+                .with_context(|| format!("profile '{name}' variables 须为字符串映射"))?,
+        };
+        let pipelines = match profile.get("pipelines") {
+            None => Map::new(),
+            Some(Value::Object(map)) => map.clone(),
+            Some(_) => bail!("profile '{name}' pipelines 须为对象"),
+        };
+        for id in pipelines.keys() {
+            if !self.pipelines.iter().any(|(p, _, _)| p == id) {
+                bail!("profile '{name}' 引用未知 Pipeline '{id}'");
+            }
+        }
+        Ok(Profile {
+            name: name.to_string(),
+            variables,
+            pipelines,
+        })
+    }
+
+    fn resolve(
+        &self,
+        id: &str,
+        origin: &Path,
+        profile: Option<&Profile>,
+    ) -> Result<PipelineConfig> {
+        let file = origin.display();
+        let mut pipeline = self.inherit(id, &mut HashSet::new())?;
+        for phase in PHASES {
+            if let Some(Value::Array(steps)) = pipeline.get_mut(phase) {
+                for step in steps {
+                    self.apply_template(step)
+                        .with_context(|| format!("{file}: Pipeline '{id}'"))?;
+                }
+            }
+        }
+        if let Some(profile) = profile {
+            apply_profile(&mut pipeline, id, profile)?;
+        }
+        serde_json::from_value(Value::Object(pipeline))
+            .with_context(|| format!("{file}: Pipeline '{id}' 解析失败"))
+    }
+
+    /// 沿 `extends` 链合并：variables 按键覆盖，同 ID 步骤替换、其余追加，其他字段覆盖
+    fn inherit(&self, id: &str, visiting: &mut HashSet<String>) -> Result<Map<String, Value>> {
+        let (_, pipeline, origin) = self
+            .pipelines
+            .iter()
+            .find(|(p, _, _)| p == id)
+            .with_context(|| format!("未知 Pipeline '{id}'"))?;
+        let file = origin.display();
+        if !visiting.insert(id.to_string()) {
+            bail!("{file}: Pipeline '{id}' extends 循环继承");
+        }
+        let mut pipeline = pipeline.clone();
+        let Some(base) = pipeline.remove("extends") else {
+            return Ok(pipeline);
+        };
+        let base = base
+            .as_str()
+            .with_context(|| format!("{file}: Pipeline '{id}' extends 须为 Pipeline ID"))?;
+        if !self.pipelines.iter().any(|(p, _, _)| p == base) {
+            bail!("{file}: Pipeline '{id}' extends 未知 Pipeline '{base}'");
+        }
+
+        let mut merged = self.inherit(base, visiting)?;
+        for key in NOT_INHERITED {
+            merged.remove(key);
+        }
+        for (key, value) in pipeline {
+            let slot = merged.entry(key.clone()).or_insert(Value::Null);
+            match (key.as_str(), slot, value) {
+                ("variables", Value::Object(base), Value::Object(own)) => base.extend(own),
+                ("steps" | "on_failure" | "finally", Value::Array(base), Value::Array(own)) => {
+                    merge_steps(base, own)
+                }
+                (_, slot, value) => *slot = value,
+            }
+        }
+        Ok(merged)
+    }
+
+    /// `use: <模板>`：以模板为底，步骤自身字段覆盖；params 深度合并
+    fn apply_template(&self, step: &mut Value) -> Result<()> {
+        let Some(object) = step.as_object_mut() else {
+            return Ok(());
+        };
+        let Some(name) = object.remove("use") else {
+            return Ok(());
+        };
+        let step_id = object.get("id").and_then(Value::as_str).unwrap_or("?");
+        let name = name
+            .as_str()
+            .with_context(|| format!("步骤 '{step_id}' use 须为模板名"))?;
+        let (template, _) = self
+            .templates
+            .get(name)
+            .with_context(|| format!("步骤 '{step_id}' 引用未知模板 '{name}'"))?;
+
+        let mut merged = Value::Object(template.clone());
+        merge_value(&mut merged, Value::Object(std::mem::take(object)));
+        *step = merged;
+        Ok(())
+    }
+}
+
+fn read_document(path: &Path) -> Result<Value> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("读取配置文件失败: {}: {}", path.display(), e))?;
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("yaml") | Some("yml") => serde_yml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("{}: 解析 YAML 失败: {}", path.display(), e)),
+        _ => serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("{}: 解析 JSON 失败: {}", path.display(), e)),
+    }
+}
+
+/// profile 覆盖：variables 按键覆盖，步骤按 ID 深度合并（params 等字段）
+fn apply_profile(pipeline: &mut Map<String, Value>, id: &str, profile: &Profile) -> Result<()> {
+    let Some(overlay) = profile.pipelines.get(id) else {
+        return Ok(());
+    };
+    let name = &profile.name;
+    let overlay = overlay
+        .as_object()
+        .with_context(|| format!("profile '{name}' Pipeline '{id}' 须为对象"))?;
+    if let Some(variables) = overlay.get("variables") {
+        let slot = pipeline
+            .entry("variables")
+            .or_insert_with(|| Value::Object(Map::new()));
+        merge_value(slot, variables.clone());
+    }
+    let Some(steps) = overlay.get("steps") else {
+        return Ok(());
+    };
+    let steps = steps.as_object().with_context(|| {
+        format!("profile '{name}' Pipeline '{id}' steps 须为对象（步骤 ID → 字段）")
+    })?;
+    'overlay: for (step_id, fields) in steps {
+        for phase in PHASES {
+            if let Some(Value::Array(list)) = pipeline.get_mut(phase)
+                && let Some(step) = list
+                    .iter_mut()
+                    .find(|s| s.get("id").and_then(Value::as_str) == Some(step_id))
+            {
+                merge_value(step, fields.clone());
+                continue 'overlay;
+            }
+        }
+        bail!("profile '{name}' Pipeline '{id}' 无步骤 '{step_id}'");
+    }
+    Ok(())
+}
+
+/// 同 ID 步骤原位替换，新步骤追加到末尾
+fn merge_steps(base: &mut Vec<Value>, own: Vec<Value>) {
+    for step in own {
+        let id = step.get("id").cloned();
+        match base
+            .iter_mut()
+            .find(|s| id.is_some() && s.get("id") == id.as_ref())
+        {
+            Some(slot) => *slot = step,
+            None => base.push(step),
+        }
+    }
+}
+
+/// 对象按键递归合并，其余类型由 `over` 覆盖
+fn merge_value(base: &mut Value, over: Value) {
+    match (base, over) {
+        (Value::Object(base), Value::Object(over)) => {
+            for (key, value) in over {
+                match base.get_mut(&key) {
+                    Some(slot) => merge_value(slot, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (slot, over) => *slot = over,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn include_templates_and_extends() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("shared.yaml"),
+            r#"
+variables: { base: /shared, out: /out }
+templates:
+  copy_modules:
+    module: copy
+    description: 复制 node_modules
+    params: { from: '${var.base}/node_modules', to: '${var.out}', excludes: ['.ts'] }
+pipelines:
+  - id: base
+    schedule: '0 0 9 * * *'
+    variables: { mode: debug, level: '1' }
+    steps:
+      - { id: copy, use: copy_modules }
+      - { id: gen, module: generate, action: path, params: { from: a, to: b } }
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("pipelines.yaml"),
+            r#"
+version: 3
+include: [shared.yaml]
+variables: { base: /root }
+pipelines:
+  - id: child
+    extends: base
+    variables: { mode: release }
+    steps:
+      - { id: copy, use: copy_modules, params: { empty: false } }
+      - { id: pack, module: compression, action: compress, format: zip, params: { from: a, to: b.zip } }
+"#,
+        )
+        .unwrap();
+
+        let config = compose(&dir.path().join("pipelines.yaml")).unwrap();
+        assert_eq!(config.variables["base"], "/root");
+        assert_eq!(config.variables["out"], "/out");
+
+        let child = config.pipelines.iter().find(|p| p.id == "child").unwrap();
+        assert!(child.schedule.is_none());
+        assert_eq!(child.variables["mode"], "release");
+        assert_eq!(child.variables["level"], "1");
+        let ids: Vec<&str> = child.steps.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["copy", "gen", "pack"]);
+        assert_eq!(child.steps[0].module, "copy");
+        assert_eq!(
+            child.steps[0].params,
+            json!({
+                "from": "${var.base}/node_modules",
+                "to": "${var.out}",
+                "excludes": [".ts"],
+                "empty": false,
+            })
+        );
+    }
+
+    #[test]
+    fn diamond_include_loads_shared_file_once() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("common.yaml"),
+            "templates:\n  t: { module: copy }\npipelines:\n  - { id: shared, steps: [] }\n",
+        )
+        .unwrap();
+        for name in ["b.yaml", "c.yaml"] {
+            fs::write(dir.path().join(name), "include: common.yaml\n").unwrap();
+        }
+        fs::write(
+            dir.path().join("pipelines.yaml"),
+            "version: 3\ninclude: [b.yaml, c.yaml]\n",
+        )
+        .unwrap();
+        let config = compose(&dir.path().join("pipelines.yaml")).unwrap();
+        let ids: Vec<&str> = config.pipelines.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, ["shared"]);
+    }
+
+    #[test]
+    fn errors_point_to_originating_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("extra.yaml"),
+            "pipelines:\n  - id: p\n    steps:\n      - { id: s, use: missing }\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("pipelines.yaml"),
+            "version: 3\ninclude: extra.yaml\n",
+        )
+        .unwrap();
+        let err = format!(
+            "{:#}",
+            compose(&dir.path().join("pipelines.yaml")).unwrap_err()
+        );
+        assert!(err.contains("extra.yaml"), "{err}");
+        assert!(err.contains("未知模板 'missing'"), "{err}");
+
+        fs::write(dir.path().join("extra.yaml"), "include: pipelines.yaml\n").unwrap();
+        let err = format!(
+            "{:#}",
+            compose(&dir.path().join("pipelines.yaml")).unwrap_err()
+        );
+        assert!(err.contains("include 循环引用"), "{err}");
+    }
+
+    #[test]
+    fn merges_files_and_applies_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let global = dir.path().join("pipelines.yaml");
+        let fragment = dir.path().join("extra.yaml");
+        fs::write(
+            &global,
+            r#"
+version: 3
+variables: { out: /out, mode: debug }
+profiles:
+  prod:
+    variables: { mode: release }
+pipelines:
+  - id: build
+    steps:
+      - { id: pack, module: compression, action: compress, format: zip, params: { from: a, to: b.zip, level: 1 } }
+"#,
+        )
+        .unwrap();
+        fs::write(
+            &fragment,
+            r#"
+version: 3
+variables: { out: /local }
+profiles:
+  prod:
+    pipelines:
+      build:
+        variables: { target: prod }
+        steps: { pack: { params: { level: 9 } } }
+pipelines:
+  - { id: other, steps: [] }
+"#,
+        )
+        .unwrap();
+        let paths = [global.clone(), fragment.clone()];
+
+        let config = compose_files(&paths, None).unwrap();
+        assert_eq!(config.variables["out"], "/local");
+        assert_eq!(config.variables["mode"], "debug");
+        assert_eq!(config.pipelines.len(), 2);
+
+        let config = compose_files(&paths, Some("prod")).unwrap();
+        assert_eq!(config.variables["mode"], "release");
+        let build = &config.pipelines[0];
+        assert_eq!(build.variables["target"], "prod");
+        assert_eq!(
+            build.steps[0].params,
+            json!({ "from": "a", "to": "b.zip", "level": 9 })
+        );
+
+        let err = compose_files(&paths, Some("staging")).unwrap_err();
+        assert!(err.to_string().contains("可用 prod"), "{err}");
+
+        fs::write(
+            &fragment,
+            "version: 3\npipelines:\n  - { id: build, steps: [] }\n",
+        )
+        .unwrap();
+        let err = format!("{:#}", compose_files(&paths, None).unwrap_err());
+        assert!(
+            err.contains("extra.yaml") && err.contains("Pipeline 'build'"),
+            "{err}"
+        );
+        assert!(err.contains("pipelines.yaml 中的定义重复"), "{err}");
+    }
+}
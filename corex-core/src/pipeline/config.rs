@@ -1,13 +1,21 @@
 //! Pipeline v3 配置 schema
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
-use clap::Parser;
 use clap::builder::ArgAction;
+use clap::{Parser, Subcommand};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::pipeline::cache::Fingerprint;
+use crate::pipeline::diagram::GraphFormat;
+use crate::pipeline::expand::Foreach;
+use crate::pipeline::report::RunStatus;
+use crate::pipeline::report_format::ReportFormat;
+use crate::pipeline::stream::StageKind;
+
 pub const CONFIG_VERSION: u32 = 3;
 
 /// `corex pipeline` 子命令参数
@@ -21,15 +29,19 @@ pub struct PipelineArgs {
     #[arg(short, long)]
     pub id: Option<String>,
 
-    /// 覆盖 variables，如 -D base=D:/proj
+    /// 覆盖 variables / 提供 inputs，如 -D base=D:/proj
     #[arg(short = 'D', value_parser = crate::runtime::parse_define, action = ArgAction::Append)]
     pub define: Vec<(String, String)>,
 
+    /// 不询问未提供的 inputs（取默认值，缺少必填输入时报错）
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub no_input: bool,
+
     /// 仅验证配置
     #[arg(long, action = ArgAction::SetTrue)]
     pub validate: bool,
 
-    /// Dry-run
+    /// Dry-run：输出解析变量后的执行计划，不执行
     #[arg(long, action = ArgAction::SetTrue)]
     pub dry_run: bool,
 
@@ -37,13 +49,72 @@ pub struct PipelineArgs {
     #[arg(long, action = ArgAction::SetTrue)]
     pub once: bool,
 
-    /// 写入 JSON 执行报告
+    /// 写入执行报告
     #[arg(long)]
     pub report_file: Option<PathBuf>,
+
+    /// 报告格式：json（默认）/ junit / html，可多次指定或以逗号分隔；多种格式时按扩展名分别写入
+    #[arg(long, value_enum, value_delimiter = ',', requires = "report_file")]
+    pub report_format: Vec<ReportFormat>,
+
+    /// 叠加 yaml 中 `profiles` 的同名配置（缺省取 COREX_PROFILE）
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// 从失败的运行恢复（运行 ID 或 last），仅重新执行失败步骤及其后续
+    #[arg(long, value_name = "RUN_ID")]
+    pub resume: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<PipelineCommand>,
+}
+
+/// `corex pipeline` 子命令
+#[derive(Debug, Clone, Subcommand)]
+pub enum PipelineCommand {
+    /// 查看运行历史（新 → 旧）
+    History {
+        /// 仅显示指定 Pipeline
+        #[arg(short, long)]
+        pipeline: Option<String>,
+
+        /// 按运行状态过滤
+        #[arg(long, value_enum)]
+        status: Option<RunStatus>,
+
+        /// 最多显示条数
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// 查看单次运行详情（运行 ID 或 last）
+    Show {
+        #[arg(value_name = "RUN_ID")]
+        run_id: String,
+    },
+    /// 导出步骤 DAG（含隐式链、when 条件与并行分层）
+    Graph {
+        /// Pipeline ID（缺省时同 `corex pipeline` 选择）
+        #[arg(short, long)]
+        pipeline: Option<String>,
+
+        /// 输出格式
+        #[arg(short, long, value_enum, default_value = "ascii")]
+        output: GraphFormat,
+    },
+    /// 硬校验之外的告警（未定义 / 未使用变量、明文密码等）
+    Lint {
+        /// 仅检查指定 Pipeline
+        #[arg(short, long)]
+        pipeline: Option<String>,
+
+        /// 视为错误的告警：warnings（全部）或代码 / 名称，如 L006、plaintext-password
+        #[arg(long, value_name = "CODE")]
+        deny: Vec<String>,
+    },
 }
 
 /// 顶层 pipelines.yaml（v3）
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PipelinesConfig {
     /// 必须为 3
     pub version: u32,
@@ -54,7 +125,7 @@ pub struct PipelinesConfig {
 }
 
 /// 单条 Pipeline
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct PipelineConfig {
     pub id: String,
     #[serde(skip_serializing_if = "Option::is_none", default)]
@@ -63,12 +134,122 @@ pub struct PipelineConfig {
     pub schedule: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub watch: Option<WatchConfig>,
+    /// Pipeline 级 variables，覆盖顶层同名变量
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub variables: HashMap<String, String>,
+    /// 带类型的输入，以 `${var.NAME}` 引用；执行前校验，未提供时交互询问或取 default
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub inputs: BTreeMap<String, InputSpec>,
+    /// 整条 Pipeline 的总超时毫秒；超时后取消当前步骤并停止后续步骤
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub timeout_ms: Option<u64>,
+    /// 同时运行的步骤上限（stream 链计为一个）；缺省不限
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_parallel: Option<usize>,
+    /// 按 module 的并发上限，如 `{ morph: 1 }`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub concurrency: HashMap<String, usize>,
+    /// 同一 Pipeline 已在执行（本进程或其他进程）时的处理：skip（默认）/ wait / queue
+    #[serde(default, skip_serializing_if = "ConcurrencyPolicy::is_skip")]
+    pub concurrency_policy: ConcurrencyPolicy,
     #[serde(default)]
     pub steps: Vec<StepConfig>,
+    /// 主步骤失败后执行（可引用 `${run.failed_step}` / `${run.error}`）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_failure: Vec<StepConfig>,
+    /// 无论成败最后执行（如清理临时目录、恢复备份）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub finally: Vec<StepConfig>,
+    /// 运行结果（如 `version: ${var.version}`），成功后解析并写入 RunReport / IPC 响应
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub outputs: BTreeMap<String, String>,
+}
+
+impl PipelineConfig {
+    /// 顶层 variables 叠加 Pipeline 级 variables
+    pub fn scoped_variables(&self, global: &HashMap<String, String>) -> HashMap<String, String> {
+        let mut variables = global.clone();
+        variables.extend(self.variables.clone());
+        variables
+    }
+
+    /// on_failure / finally 步骤组成的子 Pipeline（各自独立建 DAG）
+    pub fn handler(&self, steps: &[StepConfig]) -> PipelineConfig {
+        PipelineConfig {
+            id: self.id.clone(),
+            max_parallel: self.max_parallel,
+            concurrency: self.concurrency.clone(),
+            steps: steps.to_vec(),
+            ..Default::default()
+        }
+    }
+}
+
+/// 同一 Pipeline 重叠触发时的策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ConcurrencyPolicy {
+    /// 跳过本次触发（手动执行时报错）
+    #[default]
+    Skip,
+    /// 等待当前运行结束后执行；已有等待中的触发时合并为一次
+    Wait,
+    /// 每次触发依次排队执行
+    Queue,
+}
+
+impl ConcurrencyPolicy {
+    pub fn is_skip(&self) -> bool {
+        *self == ConcurrencyPolicy::Skip
+    }
+}
+
+/// Pipeline 输入声明
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct InputSpec {
+    /// 类型：string（默认）/ path / bool / int / enum
+    #[serde(rename = "type", default)]
+    pub kind: InputType,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub description: Option<String>,
+    /// 默认值（字符串、数字或 bool）
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub default: Option<Value>,
+    /// 必填：未提供且无 default 时报错
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+    /// 可选值（仅 enum）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<String>,
+    /// 取值须匹配的正则
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub pattern: Option<String>,
+    /// 最小值（仅 int）
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub min: Option<i64>,
+    /// 最大值（仅 int）
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max: Option<i64>,
+    /// 路径须已存在（仅 path）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exists: bool,
+}
+
+/// 输入类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum InputType {
+    #[default]
+    String,
+    Path,
+    Bool,
+    Int,
+    Enum,
 }
 
 /// 文件监听配置（`corex watch run`）
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WatchConfig {
     /// 监听路径（文件或目录，可多个）
     pub paths: Vec<String>,
@@ -95,7 +276,7 @@ fn default_debounce_ms() -> u64 {
 }
 
 /// 步骤配置
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct StepConfig {
     pub id: String,
     pub module: String,
@@ -116,18 +297,104 @@ pub struct StepConfig {
     pub when: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub retry: Option<RetryConfig>,
+    /// 单次执行超时毫秒；超时后协作取消（exec 终止子进程、copy/scrub 停止遍历）
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub timeout_ms: Option<u64>,
+    /// 输入 glob（声明后启用增量缓存：inputs / outputs 均未变化时跳过并复用上次产物）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<String>,
+    /// 输出 glob（被删除或修改时缓存失效）；写成映射时报错并提示改用 `exports`
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_step_outputs"
+    )]
+    pub outputs: Vec<String>,
+    /// 成功后写入 variables 的值（如 `version: ${steps.read.artifact.data.version}`），
+    /// 依赖本步骤的后续步骤以 `${var.version}` 引用
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub exports: BTreeMap<String, String>,
+    /// 缓存指纹方式：mtime（大小 + 修改时间，默认）/ content（内容哈希）
+    #[serde(default, skip_serializing_if = "Fingerprint::is_mtime")]
+    pub fingerprint: Fingerprint,
+    /// 失败（含超时）时记为 failed_tolerated 并继续执行后续步骤
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub continue_on_error: bool,
+    /// 按列表展开为多个步骤（字面量数组或 `${var.*}` JSON 数组），params 中以 `${item}` 引用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foreach: Option<Foreach>,
+    /// 按各键取值的笛卡尔积展开，params 中以 `${matrix.key}` 引用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<BTreeMap<String, Foreach>>,
+    /// 展开自 foreach / matrix 的分组 ID（即原步骤 ID）；`depends_on` 引用分组即依赖全部成员
+    #[serde(skip)]
+    pub group: Option<String>,
+    /// Stage 种类：batch（默认）/ stream（与相邻 stream 步骤逐项传递 PipelineItem）
+    #[serde(default, skip_serializing_if = "StageKind::is_batch")]
+    pub kind: StageKind,
     /// 仅 flags（与 CLI `--flag` 对应），不含子命令嵌套
     #[serde(default)]
     pub params: Value,
 }
 
 /// 重试策略
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RetryConfig {
     #[serde(default = "default_retry_max")]
     pub max: u32,
     #[serde(default = "default_backoff")]
     pub backoff_ms: u64,
+    /// 退避方式：fixed（默认，每次等待 backoff_ms）/ exponential（逐次翻倍）
+    #[serde(default)]
+    pub strategy: RetryStrategy,
+    /// 单次等待上限
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_backoff_ms: Option<u64>,
+    /// 抖动：实际等待在计算值的 50%–100% 间随机
+    #[serde(default)]
+    pub jitter: bool,
+    /// 仅这些失败才重试（错误类别 `failed` / `timed_out`，或匹配错误信息的正则）；为空时均重试
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub retry_on: Vec<String>,
+    /// 这些失败不重试（优先于 retry_on）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub no_retry_on: Vec<String>,
+}
+
+/// 重试退避方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RetryStrategy {
+    #[default]
+    Fixed,
+    Exponential,
+}
+
+/// 步骤级 `outputs` 为缓存输出 glob 列表；按 Pipeline 级 `outputs` 的习惯写成映射时给出明确提示
+fn deserialize_step_outputs<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Shape {
+        Globs(Vec<String>),
+        Map(BTreeMap<String, Value>),
+    }
+
+    match Shape::deserialize(deserializer) {
+        Ok(Shape::Globs(globs)) => Ok(globs),
+        Ok(Shape::Map(map)) => {
+            let keys: Vec<&str> = map.keys().map(String::as_str).collect();
+            Err(serde::de::Error::custom(format!(
+                "步骤级 outputs 为增量缓存的输出 glob 列表；导出变量（{}）请改用 exports",
+                keys.join(", ")
+            )))
+        }
+        Err(_) => Err(serde::de::Error::custom(
+            "步骤级 outputs 须为 glob 列表（导出变量请使用 exports 映射）",
+        )),
+    }
 }
 
 fn default_retry_max() -> u32 {
@@ -149,60 +416,209 @@ pub fn find_config_path() -> PathBuf {
     base.join("pipelines.yaml")
 }
 
+/// 自动发现的配置文件（按合并顺序）：`~/.corex` 主配置、`~/.corex/pipelines.d/*.yaml`、
+/// 自当前目录向上最近的 `.corex/pipelines.yaml`
+pub fn discover_config_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let main = find_config_path();
+    if main.exists() {
+        paths.push(main);
+    }
+    let dir = dirs::home_dir()
+        .expect("无法获取用户目录")
+        .join(".corex")
+        .join("pipelines.d");
+    if let Ok(entries) = std::fs::read_dir(&dir) {
+        let mut fragments: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.is_file()
+                    && p.extension()
+                        .is_some_and(|ext| ext == "yaml" || ext == "yml")
+            })
+            .collect();
+        fragments.sort();
+        paths.extend(fragments);
+    }
+    if let Some(local) = find_project_config() {
+        let canonical = |p: &PathBuf| p.canonicalize().unwrap_or_else(|_| p.clone());
+        if !paths.iter().any(|p| canonical(p) == canonical(&local)) {
+            paths.push(local);
+        }
+    }
+    paths
+}
+
+/// 自当前目录向上查找 `.corex/pipelines.yaml`（或 `.yml`）
+fn find_project_config() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors().find_map(|dir| {
+        ["pipelines.yaml", "pipelines.yml"]
+            .into_iter()
+            .map(|name| dir.join(".corex").join(name))
+            .find(|p| p.is_file())
+    })
+}
+
+/// 读取配置并解析 include / templates / extends（见 [`compose`](crate::pipeline::compose)）
 pub fn load_config(path: &std::path::Path) -> anyhow::Result<PipelinesConfig> {
-    let content =
-        std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!("读取配置文件失败: {}", e))?;
-    let config: PipelinesConfig =
-        match path.extension().and_then(|e| e.to_str()) {
-            Some("yaml") | Some("yml") => serde_yml::from_str(&content)
-                .map_err(|e| anyhow::anyhow!("解析 YAML 失败: {}", e))?,
-            _ => serde_json::from_str(&content)
-                .map_err(|e| anyhow::anyhow!("解析 JSON 失败: {}", e))?,
-        };
-    if config.version != CONFIG_VERSION {
+    crate::pipeline::compose::compose(path)
+}
+
+/// 读取 `--config` 指定的文件，未指定时合并全部自动发现的配置；
+/// profile 缺省取环境变量 `COREX_PROFILE`
+pub fn load_configs(
+    config: Option<&str>,
+    profile: Option<&str>,
+) -> anyhow::Result<PipelinesConfig> {
+    let paths = match config {
+        Some(path) => vec![PathBuf::from(path)],
+        None => discover_config_paths(),
+    };
+    if paths.is_empty() || !paths[0].exists() {
+        let missing = paths.first().cloned().unwrap_or_else(find_config_path);
         anyhow::bail!(
-            "配置 version 必须为 {}，当前为 {}",
-            CONFIG_VERSION,
-            config.version
+            "配置文件未找到：{}，请先运行 `corex schedule generate` 生成配置",
+            missing.display()
         );
     }
-    Ok(config)
+    let env_profile = std::env::var("COREX_PROFILE")
+        .ok()
+        .filter(|p| !p.is_empty());
+    let profile = profile.or(env_profile.as_deref());
+    crate::pipeline::compose::compose_files(&paths, profile)
 }
 
 pub fn validate_config(config: &PipelinesConfig) -> anyhow::Result<()> {
+    use crate::pipeline::context::PipelineContext;
+    use crate::pipeline::expand::expand_pipeline;
     use crate::pipeline::graph::StageGraph;
+    use crate::pipeline::inputs;
+    use crate::pipeline::placeholder;
+    use crate::pipeline::stream::{fed_params, plan_chains};
+    use crate::pipeline::subpipeline;
 
+    for value in config.variables.values() {
+        placeholder::validate_text(value).map_err(|e| anyhow::anyhow!("variables {e}"))?;
+    }
     for pipeline in &config.pipelines {
+        for (name, spec) in &pipeline.inputs {
+            spec.validate()
+                .map_err(|e| anyhow::anyhow!("Pipeline '{}' 输入 '{}' {}", pipeline.id, name, e))?;
+        }
+        let mut variables = pipeline.scoped_variables(&config.variables);
+        inputs::fill_defaults(pipeline, &mut variables);
+        let ctx = PipelineContext::with_variables(variables);
+        pipeline
+            .variables
+            .values()
+            .chain(pipeline.outputs.values())
+            .chain(pipeline.watch.iter().flat_map(|w| &w.paths))
+            .try_for_each(|s| placeholder::validate_text(s))
+            .map_err(|e| anyhow::anyhow!("Pipeline '{}' {}", pipeline.id, e))?;
+        // 步骤 ID 在主步骤、on_failure、finally 间全局唯一（展开前后均检查）
         let mut seen = std::collections::HashSet::new();
-        for step in &pipeline.steps {
-            if !seen.insert(&step.id) {
+        for step in pipeline
+            .steps
+            .iter()
+            .chain(&pipeline.on_failure)
+            .chain(&pipeline.finally)
+        {
+            if !seen.insert(step.id.clone()) {
                 anyhow::bail!("Pipeline '{}' 步骤 ID '{}' 重复", pipeline.id, step.id);
             }
-            if !crate::invoke::known_modules().contains(&step.module.as_str()) {
-                anyhow::bail!(
-                    "Pipeline '{}' 步骤 '{}' 未知 module: {}",
-                    pipeline.id,
-                    step.id,
-                    step.module
-                );
+        }
+        let expanded = expand_pipeline(pipeline, &ctx)
+            .map_err(|e| anyhow::anyhow!("Pipeline '{}' {}", pipeline.id, e))?;
+        let phases = [
+            expanded.clone(),
+            expanded.handler(&expanded.on_failure),
+            expanded.handler(&expanded.finally),
+        ];
+        let mut seen = std::collections::HashSet::new();
+        for phase in &phases {
+            for step in &phase.steps {
+                if !seen.insert(&step.id) {
+                    anyhow::bail!("Pipeline '{}' 步骤 ID '{}' 重复", pipeline.id, step.id);
+                }
+                if step.module != subpipeline::MODULE
+                    && !crate::invoke::known_modules().contains(&step.module.as_str())
+                {
+                    anyhow::bail!(
+                        "Pipeline '{}' 步骤 '{}' 未知 module: {}",
+                        pipeline.id,
+                        step.id,
+                        step.module
+                    );
+                }
+                placeholder::validate_value(&step.params)
+                    .and_then(|_| {
+                        step.inputs
+                            .iter()
+                            .chain(&step.outputs)
+                            .chain(step.exports.values())
+                            .try_for_each(|s| placeholder::validate_text(s))
+                    })
+                    .map_err(|e| {
+                        anyhow::anyhow!("Pipeline '{}' 步骤 '{}' {}", pipeline.id, step.id, e)
+                    })?;
+                if let Some(ref retry) = step.retry {
+                    retry.validate().map_err(|e| {
+                        anyhow::anyhow!("Pipeline '{}' 步骤 '{}' {}", pipeline.id, step.id, e)
+                    })?;
+                }
+                if let Some(ref when) = step.when {
+                    crate::pipeline::expr::parse_when(when).map_err(|e| {
+                        anyhow::anyhow!(
+                            "Pipeline '{}' 步骤 '{}' when 表达式无效: {}",
+                            pipeline.id,
+                            step.id,
+                            e
+                        )
+                    })?;
+                }
+            }
+        }
+        for phase in &phases {
+            let graph = StageGraph::from_pipeline(phase)?;
+            graph.validate()?;
+            // stream 链下游步骤的 from 由上游 item 提供
+            let fed: std::collections::HashSet<String> = plan_chains(phase, &graph)?
+                .into_iter()
+                .flat_map(|chain| chain.into_iter().skip(1))
+                .collect();
+            for step in phase
+                .steps
+                .iter()
+                .filter(|s| s.module != subpipeline::MODULE)
+            {
+                // 预检线格式路由 +（无占位符时）params 结构
+                let wire = crate::invoke::WireArgs {
+                    action: step.action.clone(),
+                    format: step.format.clone(),
+                    algorithm: step.algorithm.clone(),
+                    flags: if fed.contains(&step.id) {
+                        fed_params(step)
+                    } else {
+                        step.params.clone()
+                    },
+                };
+                crate::invoke::validate_wire(&step.module, &wire).map_err(|e| {
+                    anyhow::anyhow!(
+                        "Pipeline '{}' 步骤 '{}' 线格式无效: {}",
+                        pipeline.id,
+                        step.id,
+                        e
+                    )
+                })?;
             }
-            // 预检线格式路由 +（无占位符时）params 结构
-            let wire = crate::invoke::WireArgs {
-                action: step.action.clone(),
-                format: step.format.clone(),
-                algorithm: step.algorithm.clone(),
-                flags: step.params.clone(),
-            };
-            crate::invoke::validate_wire(&step.module, &wire).map_err(|e| {
-                anyhow::anyhow!(
-                    "Pipeline '{}' 步骤 '{}' 线格式无效: {}",
-                    pipeline.id,
-                    step.id,
-                    e
-                )
-            })?;
         }
-        StageGraph::from_pipeline(pipeline)?.validate()?;
+        if pipeline.max_parallel == Some(0) || pipeline.concurrency.values().any(|n| *n == 0) {
+            anyhow::bail!(
+                "Pipeline '{}' max_parallel / concurrency 须大于 0",
+                pipeline.id
+            );
+        }
         if let Some(watch) = &pipeline.watch {
             if watch.paths.is_empty() {
                 anyhow::bail!(
@@ -212,7 +628,7 @@ pub fn validate_config(config: &PipelinesConfig) -> anyhow::Result<()> {
             }
         }
     }
-    Ok(())
+    subpipeline::validate(config)
 }
 
 /// validate 结构化结果（JSON 输出）
@@ -260,6 +676,31 @@ pipelines:
         assert_eq!(watch.debounce_ms, 300);
     }
 
+    #[test]
+    fn step_outputs_map_points_to_exports() {
+        let yaml = r#"
+version: 3
+pipelines:
+  - id: release
+    steps:
+      - id: read
+        module: scan
+        action: os
+        outputs: { version: "${steps.read.artifact.data.version}" }
+        params: {}
+"#;
+        let err = serde_yml::from_str::<PipelinesConfig>(yaml).unwrap_err();
+        assert!(err.to_string().contains("exports"), "{err}");
+        assert!(err.to_string().contains("version"), "{err}");
+
+        let yaml = yaml.replace(
+            r#"outputs: { version: "${steps.read.artifact.data.version}" }"#,
+            r#"outputs: ["dist/**"]"#,
+        );
+        let config: PipelinesConfig = serde_yml::from_str(&yaml).unwrap();
+        assert_eq!(config.pipelines[0].steps[0].outputs, vec!["dist/**"]);
+    }
+
     #[test]
     fn validate_rejects_empty_watch_paths() {
         let config = PipelinesConfig {
@@ -267,8 +708,6 @@ pipelines:
             variables: HashMap::new(),
             pipelines: vec![PipelineConfig {
                 id: "bad".into(),
-                description: None,
-                schedule: None,
                 watch: Some(WatchConfig {
                     paths: vec![],
                     includes: vec![],
@@ -283,9 +722,31 @@ pipelines:
                     params: serde_json::json!({}),
                     ..Default::default()
                 }],
+                ..Default::default()
             }],
         };
         let err = validate_config(&config).unwrap_err();
         assert!(err.to_string().contains("watch.paths"));
     }
+
+    #[test]
+    fn validate_rejects_invalid_when() {
+        let config = PipelinesConfig {
+            version: CONFIG_VERSION,
+            variables: HashMap::new(),
+            pipelines: vec![PipelineConfig {
+                id: "bad".into(),
+                steps: vec![StepConfig {
+                    id: "s".into(),
+                    module: "copy".into(),
+                    when: Some("steps.a.status ==".into()),
+                    params: serde_json::json!({ "from": "a", "to": "b" }),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        let err = validate_config(&config).unwrap_err();
+        assert!(err.to_string().contains("when 表达式无效"));
+    }
 }
@@ -1,15 +1,33 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use regex::Regex;
 use serde_json::Value;
 
 use crate::invoke::Artifact;
 
+use super::config::{PipelineConfig, PipelinesConfig};
+use super::expr;
+use super::placeholder::{self, Placeholder};
+use super::report::StepStatus;
+
+/// 变量值嵌套展开的最大深度（防止循环引用）
+const MAX_DEPTH: usize = 16;
+
 /// Pipeline 执行上下文（v3 变量语法）
 #[derive(Debug, Clone, Default)]
 pub struct PipelineContext {
     pub variables: HashMap<String, String>,
     pub step_artifacts: HashMap<String, Artifact>,
+    /// 已完成步骤的状态（`${steps.<id>.status}`）
+    pub step_status: HashMap<String, StepStatus>,
+    /// 运行状态（`${run.status}` / `${run.failed_step}` / `${run.error}`），供 on_failure / finally 引用
+    pub run: HashMap<String, String>,
+    /// 同一配置中的全部 Pipeline（供 `module: pipeline` 步骤按 ID 调用）
+    pub pipelines: Arc<HashMap<String, PipelineConfig>>,
+    /// 当前运行 ID（`${run.id}`，事件流标注用）
+    pub run_id: String,
+    /// 当前 Pipeline ID（`${pipeline.id}`）
+    pub pipeline_id: String,
 }
 
 impl PipelineContext {
@@ -24,18 +42,49 @@ impl PipelineContext {
         }
     }
 
+    /// 以配置的顶层 variables 建上下文，并登记全部 Pipeline
+    pub fn from_config(config: &PipelinesConfig) -> Self {
+        Self {
+            variables: config.variables.clone(),
+            pipelines: Arc::new(
+                config
+                    .pipelines
+                    .iter()
+                    .map(|p| (p.id.clone(), p.clone()))
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
     pub fn set_artifact(&mut self, step_id: String, artifact: Artifact) {
         self.step_artifacts.insert(step_id, artifact);
     }
 
-    /// 解析字符串中的 `${var.*}` / `${steps.*}` 占位符（支持变量嵌套引用）。
+    pub fn set_status(&mut self, step_id: String, status: StepStatus) {
+        self.step_status.insert(step_id, status);
+    }
+
+    /// 解析字符串中的占位符（语法见 [`placeholder`]，支持变量嵌套引用）；无法解析的保留原文
     pub fn parse(&self, input: &str) -> String {
-        let re = Regex::new(r"\$\{([^}]+)\}").unwrap();
+        self.expand(input, 0)
+    }
+
+    /// 解析单个引用（不含 `${}`），未知时返回 None
+    pub fn resolve(&self, reference: &str) -> Option<String> {
+        self.parse_reference(reference, 0)
+    }
+
+    fn expand(&self, input: &str, depth: usize) -> String {
+        if depth > MAX_DEPTH {
+            return input.to_string();
+        }
+        let re = placeholder::regex();
         let mut result = input.to_string();
         for _ in 0..32 {
             let next = re
                 .replace_all(&result, |caps: &regex::Captures| {
-                    self.parse_reference(&caps[1])
+                    self.parse_reference(&caps[1], depth)
                         .unwrap_or_else(|| caps[0].to_string())
                 })
                 .to_string();
@@ -47,21 +96,50 @@ impl PipelineContext {
         result
     }
 
-    fn parse_reference(&self, reference: &str) -> Option<String> {
-        let parts: Vec<&str> = reference.split('.').collect();
+    fn parse_reference(&self, reference: &str, depth: usize) -> Option<String> {
+        let placeholder = Placeholder::parse(reference);
+        let value = match self.lookup(&placeholder) {
+            Some(Value::String(s)) => Some(Value::String(self.expand(&s, depth + 1))),
+            other => other,
+        }
+        // 仅在声明了 `:-` 默认值时把空串视为未定义；否则空变量按空串替换
+        .filter(|v| placeholder.default.is_none() || v.as_str() != Some(""))
+        .or_else(|| placeholder.default.map(|d| Value::String(d.to_string())))?;
+        placeholder.apply(value)
+    }
+
+    fn lookup(&self, placeholder: &Placeholder) -> Option<Value> {
+        if let Some(format) = placeholder.now_format() {
+            return placeholder::is_valid_format(format)
+                .then(|| Value::String(chrono::Local::now().format(format).to_string()));
+        }
+        let parts: Vec<&str> = placeholder.path.split('.').collect();
+        let text = |s: &str| {
+            Some(s)
+                .filter(|s| !s.is_empty())
+                .map(|s| Value::String(s.into()))
+        };
         match parts.as_slice() {
-            ["env", name] => std::env::var(name).ok(),
-            ["var", name] => self.variables.get(*name).cloned(),
+            ["uuid"] => text(&uuid::Uuid::new_v4().to_string()),
+            ["pipeline", "id"] => text(&self.pipeline_id),
+            ["env", name] => std::env::var(name).ok().map(Value::String),
+            ["secret", name] => crate::secret::resolve(name).map(Value::String),
+            ["var", name] => self.variables.get(*name).cloned().map(Value::String),
+            ["run", "id"] if !self.run_id.is_empty() => text(&self.run_id),
+            ["run", key] => self.run.get(*key).cloned().map(Value::String),
+            ["steps", step_id, "status"] => self
+                .step_status
+                .get(*step_id)
+                .and_then(|s| serde_json::to_value(s).ok()),
             ["steps", step_id, "artifact", "path"] => self
                 .step_artifacts
                 .get(*step_id)
                 .and_then(|a| a.path.as_ref())
-                .map(|p| p.to_string_lossy().to_string()),
-            ["steps", step_id, "artifact", "data", key] => self
-                .step_artifacts
-                .get(*step_id)
-                .and_then(|a| a.data.get(*key))
-                .map(value_to_string),
+                .map(|p| Value::String(p.to_string_lossy().to_string())),
+            ["steps", step_id, "artifact", "data", key, rest @ ..] => {
+                let data = self.step_artifacts.get(*step_id)?.data.get(*key)?;
+                placeholder::lookup(data, rest).cloned()
+            }
             _ => None,
         }
     }
@@ -82,21 +160,10 @@ impl PipelineContext {
         }
     }
 
-    /// when 条件：非空且不为 false/0/no 视为 true
-    pub fn eval_when(&self, expr: &str) -> bool {
-        let parsed = self.parse(expr).trim().to_lowercase();
-        !parsed.is_empty()
-            && parsed != "false"
-            && parsed != "0"
-            && parsed != "no"
-            && parsed != "off"
-    }
-}
-
-fn value_to_string(v: &Value) -> String {
-    match v {
-        Value::String(s) => s.clone(),
-        other => other.to_string(),
+    /// when 条件表达式（见 [`expr::parse_when`]）；表达式无效时报错
+    pub fn eval_when(&self, when: &str) -> anyhow::Result<bool> {
+        let expr = expr::parse_when(when).map_err(|e| anyhow::anyhow!("when 表达式无效: {e}"))?;
+        Ok(expr.eval(self).truthy())
     }
 }
 
@@ -123,4 +190,54 @@ mod tests {
             "C:\\root\\Vue2\\front\\master\\version.json"
         );
     }
+
+    #[test]
+    fn parse_supports_defaults_filters_and_builtins() {
+        let mut ctx = PipelineContext::with_variables(HashMap::from([
+            ("out".to_string(), String::new()),
+            ("file".to_string(), "${var.dir}/App.Setup.exe".to_string()),
+            ("dir".to_string(), "build/win".to_string()),
+        ]));
+        ctx.run_id = "20260710-150000-123".into();
+        ctx.pipeline_id = "release".into();
+        let mut artifact = Artifact::default();
+        artifact.data.insert(
+            "CPU".into(),
+            serde_json::json!({ "cores": 8, "tags": ["x86"] }),
+        );
+        ctx.set_artifact("probe".into(), artifact);
+
+        assert_eq!(ctx.parse("${var.out:-./dist}"), "./dist");
+        assert_eq!(ctx.parse("${var.none:-${var.dir}}"), "build/win");
+        assert_eq!(ctx.parse("${var.file|stem|lower}"), "app.setup");
+        assert_eq!(ctx.parse("${var.file|dirname|upper}"), "BUILD/WIN");
+        assert_eq!(
+            ctx.parse("${pipeline.id}-${run.id}"),
+            "release-20260710-150000-123"
+        );
+        assert_eq!(ctx.parse("${steps.probe.artifact.data.CPU.cores}"), "8");
+        assert_eq!(ctx.parse("${steps.probe.artifact.data.CPU.tags.0}"), "x86");
+        assert_eq!(
+            ctx.parse("${steps.probe.artifact.data.CPU|json}"),
+            r#"{"cores":8,"tags":["x86"]}"#
+        );
+        assert_eq!(ctx.parse("${now:%Y}").len(), 4);
+        assert_eq!(ctx.parse("${uuid}").len(), 36);
+        assert_eq!(
+            ctx.parse("${steps.later.artifact.path}"),
+            "${steps.later.artifact.path}"
+        );
+    }
+
+    #[test]
+    fn parse_empty_value_without_default_is_empty() {
+        let mut ctx =
+            PipelineContext::with_variables(HashMap::from([("x".to_string(), String::new())]));
+        ctx.run.insert("error".into(), String::new());
+
+        assert_eq!(ctx.parse("[${var.x}]"), "[]");
+        assert_eq!(ctx.parse("[${run.error}]"), "[]");
+        assert_eq!(ctx.parse("${var.x:-fallback}"), "fallback");
+        assert!(!ctx.eval_when("${var.x}").unwrap());
+    }
 }
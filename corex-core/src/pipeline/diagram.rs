@@ -0,0 +1,277 @@
+//! Pipeline DAG 导出（`corex pipeline graph`）：DOT / Mermaid / ASCII
+
+use std::fmt::Write;
+
+use anyhow::Result;
+
+use super::config::{PipelineConfig, StepConfig};
+use super::graph::StageGraph;
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+    Ascii,
+}
+
+/// 步骤图快照：按层排列的步骤与边（`implicit` 为 depends_on 缺省时的隐式链）
+struct Diagram<'a> {
+    pipeline: &'a PipelineConfig,
+    layers: Vec<Vec<&'a StepConfig>>,
+    edges: Vec<Edge<'a>>,
+}
+
+struct Edge<'a> {
+    from: &'a str,
+    to: &'a str,
+    implicit: bool,
+}
+
+/// 渲染 Pipeline 主步骤 DAG；pipeline 须已展开 foreach / matrix
+pub fn render(pipeline: &PipelineConfig, format: GraphFormat) -> Result<String> {
+    let diagram = Diagram::new(pipeline)?;
+    Ok(match format {
+        GraphFormat::Dot => diagram.dot(),
+        GraphFormat::Mermaid => diagram.mermaid(),
+        GraphFormat::Ascii => diagram.ascii(),
+    })
+}
+
+impl<'a> Diagram<'a> {
+    fn new(pipeline: &'a PipelineConfig) -> Result<Self> {
+        let graph = StageGraph::from_pipeline(pipeline)?;
+        graph.validate()?;
+        let position = |id: &str| pipeline.steps.iter().position(|s| s.id == id);
+        let step = |id: &str| position(id).map(|i| &pipeline.steps[i]);
+
+        let layers = graph
+            .execution_layers()?
+            .iter()
+            .map(|layer| {
+                let mut steps: Vec<&StepConfig> = layer.iter().filter_map(|id| step(id)).collect();
+                steps.sort_by_key(|s| position(&s.id));
+                steps
+            })
+            .collect();
+
+        let mut edges: Vec<Edge> = graph
+            .edges()
+            .iter()
+            .filter_map(|(from, to)| {
+                let (from, to) = (step(from)?, step(to)?);
+                Some(Edge {
+                    from: &from.id,
+                    to: &to.id,
+                    implicit: to.depends_on.is_empty(),
+                })
+            })
+            .collect();
+        edges.sort_by_key(|e| (position(e.to), position(e.from)));
+
+        Ok(Self {
+            pipeline,
+            layers,
+            edges,
+        })
+    }
+
+    fn dot(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "digraph \"{}\" {{", dot_escape(&self.pipeline.id));
+        let _ = writeln!(out, "  rankdir=TB;");
+        let _ = writeln!(out, "  node [shape=box];");
+        for (i, layer) in self.layers.iter().enumerate() {
+            let _ = writeln!(out, "  subgraph layer_{i} {{");
+            let _ = writeln!(out, "    rank=same;");
+            for step in layer {
+                let mut label = format!("{}\\n{}", dot_escape(&step.id), dot_escape(&route(step)));
+                if let Some(ref when) = step.when {
+                    let _ = write!(label, "\\nwhen: {}", dot_escape(when));
+                }
+                let _ = writeln!(
+                    out,
+                    "    \"{}\" [label=\"{}\"];",
+                    dot_escape(&step.id),
+                    label
+                );
+            }
+            let _ = writeln!(out, "  }}");
+        }
+        for edge in &self.edges {
+            let style = if edge.implicit { " [style=dashed]" } else { "" };
+            let _ = writeln!(
+                out,
+                "  \"{}\" -> \"{}\"{};",
+                dot_escape(edge.from),
+                dot_escape(edge.to),
+                style
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn mermaid(&self) -> String {
+        // 步骤 ID 可含 `[` 等字符（foreach 展开），节点统一用序号命名
+        let node = |id: &str| {
+            let i = self.pipeline.steps.iter().position(|s| s.id == id);
+            format!("s{}", i.unwrap_or_default())
+        };
+        let mut out = String::from("flowchart TD\n");
+        for (i, layer) in self.layers.iter().enumerate() {
+            let _ = writeln!(out, "  subgraph layer_{i}[\"层 {i}\"]");
+            for step in layer {
+                let mut label = format!(
+                    "{}<br/>{}",
+                    mermaid_escape(&step.id),
+                    mermaid_escape(&route(step))
+                );
+                if let Some(ref when) = step.when {
+                    let _ = write!(label, "<br/>when: {}", mermaid_escape(when));
+                }
+                let _ = writeln!(out, "    {}[\"{}\"]", node(&step.id), label);
+            }
+            let _ = writeln!(out, "  end");
+        }
+        for edge in &self.edges {
+            let arrow = if edge.implicit { "-.->" } else { "-->" };
+            let _ = writeln!(out, "  {} {} {}", node(edge.from), arrow, node(edge.to));
+        }
+        out
+    }
+
+    fn ascii(&self) -> String {
+        let mut out = format!(
+            "{}（{} 步，{} 层）\n",
+            self.pipeline.id,
+            self.pipeline.steps.len(),
+            self.layers.len()
+        );
+        for (i, layer) in self.layers.iter().enumerate() {
+            let _ = writeln!(out, "\n层 {i}");
+            for step in layer {
+                let deps: Vec<String> = self
+                    .edges
+                    .iter()
+                    .filter(|e| e.to == step.id)
+                    .map(|e| {
+                        if e.implicit {
+                            format!("{}（隐式）", e.from)
+                        } else {
+                            e.from.to_string()
+                        }
+                    })
+                    .collect();
+                let _ = write!(out, "  ● {}  [{}]", step.id, route(step));
+                if !deps.is_empty() {
+                    let _ = write!(out, "  ← {}", deps.join(", "));
+                }
+                out.push('\n');
+                if let Some(ref when) = step.when {
+                    let _ = writeln!(out, "      when: {when}");
+                }
+            }
+        }
+        out
+    }
+}
+
+/// 线格式路由：`module action format|algorithm`
+fn route(step: &StepConfig) -> String {
+    [
+        Some(step.module.as_str()),
+        step.action.as_deref(),
+        step.format.as_deref().or(step.algorithm.as_deref()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn mermaid_escape(s: &str) -> String {
+    s.replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(id: &str, module: &str, deps: &[&str]) -> StepConfig {
+        StepConfig {
+            id: id.into(),
+            module: module.into(),
+            depends_on: deps.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn pipeline() -> PipelineConfig {
+        PipelineConfig {
+            id: "build-h5".into(),
+            steps: vec![
+                step("copy_cache", "copy", &[]),
+                StepConfig {
+                    action: Some("path".into()),
+                    when: Some("env_set(CI)".into()),
+                    ..step("gen_path", "generate", &["copy_cache"])
+                },
+                StepConfig {
+                    action: Some("compress".into()),
+                    format: Some("zip".into()),
+                    ..step("compress_wgt", "compression", &["copy_cache"])
+                },
+                step("notify", "exec", &[]),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ascii_lists_layers_and_implicit_edges() {
+        let out = render(&pipeline(), GraphFormat::Ascii).unwrap();
+        assert!(out.starts_with("build-h5（4 步，3 层）"), "{out}");
+        assert!(
+            out.contains(
+                "层 1\n  ● gen_path  [generate path]  ← copy_cache\n      when: env_set(CI)\n"
+            ),
+            "{out}"
+        );
+        assert!(
+            out.contains("  ● compress_wgt  [compression compress zip]  ← copy_cache\n"),
+            "{out}"
+        );
+        assert!(
+            out.contains("  ● notify  [exec]  ← compress_wgt（隐式）\n"),
+            "{out}"
+        );
+    }
+
+    #[test]
+    fn dot_and_mermaid_mark_implicit_edges() {
+        let p = pipeline();
+        let dot = render(&p, GraphFormat::Dot).unwrap();
+        assert!(dot.contains("\"copy_cache\" -> \"gen_path\";"), "{dot}");
+        assert!(
+            dot.contains("\"compress_wgt\" -> \"notify\" [style=dashed];"),
+            "{dot}"
+        );
+        assert!(dot.contains("when: env_set(CI)"), "{dot}");
+
+        let mermaid = render(&p, GraphFormat::Mermaid).unwrap();
+        assert!(mermaid.starts_with("flowchart TD\n"), "{mermaid}");
+        assert!(mermaid.contains("  s0 --> s1\n"), "{mermaid}");
+        assert!(mermaid.contains("  s2 -.-> s3\n"), "{mermaid}");
+        assert!(
+            mermaid.contains("s2[\"compress_wgt<br/>compression compress zip\"]"),
+            "{mermaid}"
+        );
+    }
+}
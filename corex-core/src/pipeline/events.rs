@@ -0,0 +1,115 @@
+//! 生命周期事件流（`--format ndjson`）：每个事件输出一行 JSON，供 UI 实时展示运行状态
+//!
+//! 每行含 `event` / `ts` / `run_id`，其余字段随事件类型而定。
+
+use serde::Serialize;
+
+use crate::runtime;
+
+use super::history::Trigger;
+use super::report::{RunReport, RunStatus, StepStatus, iso_now};
+
+/// 事件类型
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    /// watch / cron 触发（随后为同一 run_id 的 `run_started`）
+    Triggered {
+        pipeline_id: &'a str,
+        trigger: Trigger,
+    },
+    RunStarted {
+        pipeline_id: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        resumed_from: Option<&'a str>,
+    },
+    StepStarted {
+        step_id: &'a str,
+        module: &'a str,
+    },
+    /// 累计进度（copy / shade 等模块上报，节流输出）
+    StepProgress {
+        step_id: &'a str,
+        items: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        bytes: Option<u64>,
+    },
+    /// 第 `attempt` 次执行失败，等待 `backoff_ms` 后重试
+    StepRetry {
+        step_id: &'a str,
+        attempt: u32,
+        max: u32,
+        backoff_ms: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<&'a str>,
+    },
+    StepFinished {
+        step_id: &'a str,
+        status: StepStatus,
+        items: u64,
+        duration_ms: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<&'a str>,
+    },
+    RunFinished {
+        pipeline_id: &'a str,
+        status: RunStatus,
+        duration_ms: u64,
+        report: &'a RunReport,
+    },
+}
+
+#[derive(Serialize)]
+struct Line<'a> {
+    #[serde(flatten)]
+    event: &'a Event<'a>,
+    ts: String,
+    run_id: &'a str,
+}
+
+/// 事件流模式下输出一行事件；其他模式忽略
+pub fn emit(run_id: &str, event: Event) {
+    if !runtime::is_event_stream() {
+        return;
+    }
+    let line = Line {
+        event: &event,
+        ts: iso_now(),
+        run_id,
+    };
+    let _ = runtime::state().emitter.json(&line);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_flattens_event_fields() {
+        let event = Event::StepRetry {
+            step_id: "upload",
+            attempt: 1,
+            max: 3,
+            backoff_ms: 500,
+            error: Some("timeout"),
+        };
+        let line = Line {
+            event: &event,
+            ts: "2026-07-10T07:00:00+00:00".into(),
+            run_id: "20260710-150000-123",
+        };
+        assert_eq!(
+            serde_json::to_value(&line).unwrap(),
+            serde_json::json!({
+                "event": "step_retry",
+                "ts": "2026-07-10T07:00:00+00:00",
+                "run_id": "20260710-150000-123",
+                "step_id": "upload",
+                "attempt": 1,
+                "max": 3,
+                "backoff_ms": 500,
+                "error": "timeout"
+            })
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! foreach / matrix 展开：一个步骤按列表展开为 N 个具体步骤（同属一个分组）
+
+use anyhow::{Result, bail};
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::config::{PipelineConfig, StepConfig};
+use super::context::PipelineContext;
+
+/// foreach 列表：字面量数组，或解析为 JSON 数组的 `${var.*}` 表达式
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum Foreach {
+    Items(Vec<Value>),
+    Expr(String),
+}
+
+impl Foreach {
+    fn resolve(&self, ctx: &PipelineContext) -> Result<Vec<Value>> {
+        match self {
+            Foreach::Items(items) => Ok(items.clone()),
+            Foreach::Expr(expr) => {
+                let parsed = ctx.parse(expr);
+                match serde_json::from_str(&parsed) {
+                    Ok(Value::Array(items)) => Ok(items),
+                    _ => bail!("foreach 表达式需解析为 JSON 数组: {expr} → {parsed}"),
+                }
+            }
+        }
+    }
+}
+
+/// 展开 Pipeline 中（含 on_failure / finally）带 foreach / matrix 的步骤；
+/// 成员 ID 为 `<id>[<n>]`，`group` 记为原步骤 ID
+pub fn expand_pipeline(pipeline: &PipelineConfig, ctx: &PipelineContext) -> Result<PipelineConfig> {
+    Ok(PipelineConfig {
+        steps: expand_steps(&pipeline.steps, ctx)?,
+        on_failure: expand_steps(&pipeline.on_failure, ctx)?,
+        finally: expand_steps(&pipeline.finally, ctx)?,
+        ..pipeline.clone()
+    })
+}
+
+fn expand_steps(steps: &[StepConfig], ctx: &PipelineContext) -> Result<Vec<StepConfig>> {
+    let mut expanded = Vec::with_capacity(steps.len());
+    for step in steps {
+        let bindings = bindings(step, ctx)?;
+        if bindings.is_empty() {
+            expanded.push(step.clone());
+            continue;
+        }
+        for (n, binding) in bindings.iter().enumerate() {
+            expanded.push(StepConfig {
+                id: format!("{}[{n}]", step.id),
+                group: Some(step.id.clone()),
+                foreach: None,
+                matrix: None,
+                description: step.description.as_deref().map(|s| substitute(s, binding)),
+                when: step.when.as_deref().map(|s| substitute(s, binding)),
+                inputs: step.inputs.iter().map(|s| substitute(s, binding)).collect(),
+                outputs: step
+                    .outputs
+                    .iter()
+                    .map(|s| substitute(s, binding))
+                    .collect(),
+                exports: step
+                    .exports
+                    .iter()
+                    .map(|(k, v)| (k.clone(), substitute(v, binding)))
+                    .collect(),
+                params: substitute_value(&step.params, binding),
+                ..step.clone()
+            });
+        }
+    }
+    Ok(expanded)
+}
+
+/// 每个成员的占位符取值（`item` / `matrix`）；未声明 foreach / matrix 时为空
+fn bindings(step: &StepConfig, ctx: &PipelineContext) -> Result<Vec<Map<String, Value>>> {
+    let bindings: Vec<Map<String, Value>> = match (&step.foreach, &step.matrix) {
+        (None, None) => return Ok(Vec::new()),
+        (Some(_), Some(_)) => bail!("步骤 '{}' 不能同时设置 foreach 与 matrix", step.id),
+        (Some(foreach), None) => foreach
+            .resolve(ctx)?
+            .into_iter()
+            .map(|item| Map::from_iter([("item".to_string(), item)]))
+            .collect(),
+        (None, Some(matrix)) => {
+            let mut combos = vec![Map::new()];
+            for (key, values) in matrix {
+                let values = values.resolve(ctx)?;
+                combos = combos
+                    .into_iter()
+                    .flat_map(|combo| {
+                        values.iter().map(move |value| {
+                            let mut next = combo.clone();
+                            next.insert(key.clone(), value.clone());
+                            next
+                        })
+                    })
+                    .collect();
+            }
+            combos
+                .into_iter()
+                .map(|combo| Map::from_iter([("matrix".to_string(), Value::Object(combo))]))
+                .collect()
+        }
+    };
+    if bindings.is_empty() {
+        bail!("步骤 '{}' 的 foreach / matrix 展开为空", step.id);
+    }
+    Ok(bindings)
+}
+
+fn placeholder() -> Regex {
+    Regex::new(r"\$\{((?:item|matrix)(?:\.[^}.]+)*)\}").unwrap()
+}
+
+fn lookup<'a>(binding: &'a Map<String, Value>, path: &str) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let mut value = binding.get(parts.next()?)?;
+    for part in parts {
+        value = match value {
+            Value::Object(map) => map.get(part)?,
+            Value::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// 替换字符串中的 `${item}` / `${item.key}` / `${matrix.key}`；其余占位符保留到执行期解析
+fn substitute(input: &str, binding: &Map<String, Value>) -> String {
+    placeholder()
+        .replace_all(input, |caps: &Captures| match lookup(binding, &caps[1]) {
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// 递归替换 params；整串恰为单个占位符时保留原始 JSON 类型
+fn substitute_value(value: &Value, binding: &Map<String, Value>) -> Value {
+    match value {
+        Value::String(s) => {
+            if let Some(caps) = placeholder().captures(s)
+                && caps[0].len() == s.len()
+                && let Some(found) = lookup(binding, &caps[1])
+            {
+                return found.clone();
+            }
+            Value::String(substitute(s, binding))
+        }
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|v| substitute_value(v, binding)).collect())
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), substitute_value(v, binding)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[test]
+    fn foreach_expands_members_with_item_placeholders() {
+        let mut variables = HashMap::new();
+        variables.insert("apps".into(), r#"["h5", "admin"]"#.into());
+        let ctx = PipelineContext::with_variables(variables);
+        let step = StepConfig {
+            id: "pack".into(),
+            module: "compression".into(),
+            foreach: Some(Foreach::Expr("${var.apps}".into())),
+            params: json!({ "from": "dist/${item}", "to": "${var.out}/${item}.zip" }),
+            ..Default::default()
+        };
+
+        let steps = expand_steps(&[step], &ctx).unwrap();
+        let ids: Vec<&str> = steps.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["pack[0]", "pack[1]"]);
+        assert_eq!(steps[1].group.as_deref(), Some("pack"));
+        assert_eq!(
+            steps[1].params,
+            json!({ "from": "dist/admin", "to": "${var.out}/admin.zip" })
+        );
+    }
+
+    #[test]
+    fn matrix_expands_cartesian_product() {
+        let mut matrix = BTreeMap::new();
+        matrix.insert(
+            "format".to_string(),
+            Foreach::Items(vec![json!("png"), json!("jpg")]),
+        );
+        matrix.insert(
+            "quality".to_string(),
+            Foreach::Items(vec![json!(80), json!(100)]),
+        );
+        let step = StepConfig {
+            id: "shade".into(),
+            module: "shade".into(),
+            matrix: Some(matrix),
+            params: json!({ "format": "${matrix.format}", "quality": "${matrix.quality}" }),
+            ..Default::default()
+        };
+
+        let steps = expand_steps(&[step], &PipelineContext::new()).unwrap();
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[1].params, json!({ "format": "png", "quality": 100 }));
+    }
+}
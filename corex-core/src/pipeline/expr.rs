@@ -0,0 +1,495 @@
+//! when 条件表达式：比较、逻辑运算与内置函数
+//!
+//! ```text
+//! expr    := and ('||' and)*
+//! and     := unary ('&&' unary)*
+//! unary   := '!' unary | cmp
+//! cmp     := primary (('==' | '!=' | '<' | '>' | '<=' | '>=') primary)?
+//! primary := '(' expr ')' | 'str' | number | ${ref} | ref.path | word | func '(' args ')'
+//! ```
+
+use std::cmp::Ordering;
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use regex::Regex;
+
+use super::context::PipelineContext;
+use super::placeholder::{self, Placeholder};
+
+/// 引用路径的合法根（`steps.build.status` / `var.mode` …）
+const ROOTS: [&str; 5] = ["var", "env", "run", "steps", "pipeline"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// 字面量文本（执行期解析其中的 `${...}`）
+    Text(String),
+    Num(f64),
+    Bool(bool),
+    /// 引用：`${steps.x.status}` 或裸路径 `steps.x.status`
+    Ref(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Cmp(CmpOp, Box<Expr>, Box<Expr>),
+    Call(Func, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+/// 内置函数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Func {
+    /// `exists(path)`：路径存在
+    Exists,
+    /// `env_set(NAME)`：环境变量已设置且非空
+    EnvSet,
+    /// `matches(str, regex)`：正则匹配
+    Matches,
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<(Self, usize)> {
+        match name {
+            "exists" => Some((Func::Exists, 1)),
+            "env_set" => Some((Func::EnvSet, 1)),
+            "matches" => Some((Func::Matches, 2)),
+            _ => None,
+        }
+    }
+}
+
+/// 求值结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum Val {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+}
+
+impl Val {
+    /// 真值：布尔值本身；数字非 0；字符串非空且不为 false/0/no/off
+    pub fn truthy(&self) -> bool {
+        match self {
+            Val::Bool(b) => *b,
+            Val::Num(n) => *n != 0.0,
+            Val::Str(s) => {
+                let s = s.trim().to_lowercase();
+                !s.is_empty() && s != "false" && s != "0" && s != "no" && s != "off"
+            }
+        }
+    }
+
+    fn as_num(&self) -> Option<f64> {
+        match self {
+            Val::Num(n) => Some(*n),
+            Val::Str(s) => s.trim().parse().ok(),
+            Val::Bool(_) => None,
+        }
+    }
+
+    fn text(&self) -> String {
+        match self {
+            Val::Str(s) => s.clone(),
+            Val::Num(n) if n.fract() == 0.0 && n.abs() < 1e15 => format!("{}", *n as i64),
+            Val::Num(n) => n.to_string(),
+            Val::Bool(b) => b.to_string(),
+        }
+    }
+
+    /// 两侧均可解析为数字时按数值比较，否则按文本比较
+    fn compare(&self, other: &Val) -> Ordering {
+        match (self.as_num(), other.as_num()) {
+            (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            _ => self.text().cmp(&other.text()),
+        }
+    }
+}
+
+impl Expr {
+    /// 表达式引用的路径（含文本中的 `${...}`），如 `steps.build.status`
+    pub fn references(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        self.collect_references(&mut out);
+        out
+    }
+
+    fn collect_references(&self, out: &mut Vec<String>) {
+        match self {
+            Expr::Text(s) => out.extend(placeholder::paths(s)),
+            Expr::Ref(raw) => out.push(Placeholder::parse(raw).path.to_string()),
+            Expr::Num(_) | Expr::Bool(_) => {}
+            Expr::Not(inner) => inner.collect_references(out),
+            Expr::And(l, r) | Expr::Or(l, r) | Expr::Cmp(_, l, r) => {
+                l.collect_references(out);
+                r.collect_references(out);
+            }
+            Expr::Call(_, args) => args.iter().for_each(|a| a.collect_references(out)),
+        }
+    }
+
+    pub fn eval(&self, ctx: &PipelineContext) -> Val {
+        match self {
+            Expr::Text(s) => Val::Str(ctx.parse(s)),
+            Expr::Num(n) => Val::Num(*n),
+            Expr::Bool(b) => Val::Bool(*b),
+            // 未解析的引用视为空字符串
+            Expr::Ref(path) => Val::Str(ctx.resolve(path).unwrap_or_default()),
+            Expr::Not(inner) => Val::Bool(!inner.eval(ctx).truthy()),
+            Expr::And(l, r) => Val::Bool(l.eval(ctx).truthy() && r.eval(ctx).truthy()),
+            Expr::Or(l, r) => Val::Bool(l.eval(ctx).truthy() || r.eval(ctx).truthy()),
+            Expr::Cmp(op, l, r) => {
+                let ord = l.eval(ctx).compare(&r.eval(ctx));
+                Val::Bool(match op {
+                    CmpOp::Eq => ord == Ordering::Equal,
+                    CmpOp::Ne => ord != Ordering::Equal,
+                    CmpOp::Lt => ord == Ordering::Less,
+                    CmpOp::Gt => ord == Ordering::Greater,
+                    CmpOp::Le => ord != Ordering::Greater,
+                    CmpOp::Ge => ord != Ordering::Less,
+                })
+            }
+            Expr::Call(func, args) => {
+                let args: Vec<String> = args.iter().map(|a| a.eval(ctx).text()).collect();
+                Val::Bool(match func {
+                    Func::Exists => Path::new(&args[0]).exists(),
+                    Func::EnvSet => std::env::var_os(&args[0]).is_some_and(|v| !v.is_empty()),
+                    Func::Matches => Regex::new(&args[1]).is_ok_and(|re| re.is_match(&args[0])),
+                })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    Comma,
+    Not,
+    And,
+    Or,
+    Cmp(CmpOp),
+    Str(String),
+    Num(f64),
+    Ref(String),
+    Word(String),
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '[' | ']')
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            (',', _) => (Token::Comma, 1),
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('=', Some('=')) => (Token::Cmp(CmpOp::Eq), 2),
+            ('!', Some('=')) => (Token::Cmp(CmpOp::Ne), 2),
+            ('<', Some('=')) => (Token::Cmp(CmpOp::Le), 2),
+            ('>', Some('=')) => (Token::Cmp(CmpOp::Ge), 2),
+            ('<', _) => (Token::Cmp(CmpOp::Lt), 1),
+            ('>', _) => (Token::Cmp(CmpOp::Gt), 1),
+            ('!', _) => (Token::Not, 1),
+            ('$', Some('{')) => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|&c| c == '}')
+                    .ok_or_else(|| anyhow::anyhow!("`${{` 未闭合"))?;
+                let inner: String = chars[i + 2..i + end].iter().collect();
+                (Token::Ref(inner), end + 1)
+            }
+            ('\'' | '"', _) => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&q| q == c)
+                    .ok_or_else(|| anyhow::anyhow!("字符串未闭合"))?;
+                let text: String = chars[i + 1..i + 1 + end].iter().collect();
+                (Token::Str(text), end + 2)
+            }
+            (c, _)
+                if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) =>
+            {
+                let len = 1 + chars[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit() || **c == '.')
+                    .count();
+                let text: String = chars[i..i + len].iter().collect();
+                let num = text
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("无效数字: {text}"))?;
+                (Token::Num(num), len)
+            }
+            (c, _) if is_word_char(c) => {
+                let len = chars[i..].iter().take_while(|c| is_word_char(**c)).count();
+                (Token::Word(chars[i..i + len].iter().collect()), len)
+            }
+            (c, _) => bail!("无法识别的字符 '{c}'"),
+        };
+        tokens.push(token);
+        i += len;
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token, what: &str) -> Result<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            _ => bail!("缺少 {what}"),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut left = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        let left = self.primary()?;
+        if let Some(Token::Cmp(op)) = self.peek().cloned() {
+            self.pos += 1;
+            return Ok(Expr::Cmp(op, Box::new(left), Box::new(self.primary()?)));
+        }
+        Ok(left)
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let inner = self.or()?;
+                self.expect(Token::RParen, "')'")?;
+                Ok(inner)
+            }
+            Some(Token::Str(s)) => {
+                placeholder::validate_text(&s)?;
+                Ok(Expr::Text(s))
+            }
+            Some(Token::Num(n)) => Ok(Expr::Num(n)),
+            Some(Token::Ref(raw)) => {
+                Placeholder::parse(&raw).validate()?;
+                Ok(Expr::Ref(raw))
+            }
+            Some(Token::Word(word)) if self.peek() == Some(&Token::LParen) => self.call(word),
+            Some(Token::Word(word)) => word_expr(word),
+            Some(token) => bail!("意外的 {token:?}"),
+            None => bail!("表达式不完整"),
+        }
+    }
+
+    fn call(&mut self, name: String) -> Result<Expr> {
+        let (func, arity) =
+            Func::from_name(&name).ok_or_else(|| anyhow::anyhow!("未知函数: {name}"))?;
+        self.expect(Token::LParen, "'('")?;
+        let mut args = Vec::new();
+        if self.peek() != Some(&Token::RParen) {
+            loop {
+                args.push(self.or()?);
+                if self.peek() != Some(&Token::Comma) {
+                    break;
+                }
+                self.pos += 1;
+            }
+        }
+        self.expect(Token::RParen, "')'")?;
+        if args.len() != arity {
+            bail!("{name}() 需要 {arity} 个参数，实际 {} 个", args.len());
+        }
+        if func == Func::Matches
+            && let Expr::Text(ref pattern) = args[1]
+            && !pattern.contains("${")
+        {
+            Regex::new(pattern).map_err(|e| anyhow::anyhow!("matches() 正则无效: {e}"))?;
+        }
+        Ok(Expr::Call(func, args))
+    }
+}
+
+/// 裸词：`true` / `false`；带 `.` 的为引用路径（根须为 var/env/run/steps）；其余按字面文本（兼容 `yes` / `on` 等）
+fn word_expr(word: String) -> Result<Expr> {
+    match word.as_str() {
+        "true" => return Ok(Expr::Bool(true)),
+        "false" => return Ok(Expr::Bool(false)),
+        _ => {}
+    }
+    match word.split_once('.') {
+        Some((root, _)) if ROOTS.contains(&root) => Ok(Expr::Ref(word)),
+        Some((root, _)) => bail!(
+            "未知引用 '{word}'（根须为 {}，而非 {root}）",
+            ROOTS.join(" / ")
+        ),
+        None => Ok(Expr::Text(word)),
+    }
+}
+
+/// 解析 when 表达式
+pub fn parse(input: &str) -> Result<Expr> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+    };
+    if parser.tokens.is_empty() {
+        bail!("表达式为空");
+    }
+    let expr = parser.or()?;
+    if let Some(token) = parser.peek() {
+        bail!("多余的 {token:?}");
+    }
+    Ok(expr)
+}
+
+/// 解析 step `when`：语法无效且不含任何运算符（`==` `&&` `!` `(` 引号等）的旧式写法
+/// 按整串插值后取真值（已弃用，`corex pipeline lint` 报 L007）
+pub fn parse_when(input: &str) -> Result<Expr> {
+    match parse(input) {
+        Ok(expr) => Ok(expr),
+        Err(_) if is_legacy(input) => {
+            placeholder::validate_text(input)?;
+            Ok(Expr::Text(input.to_string()))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// 出现即视为表达式（而非旧式自由文本）的字符
+const OPERATOR_CHARS: &str = "=!<>&|()'\"";
+
+/// 是否为旧式 when（非表达式的自由文本，如 `${var.a}-${var.b}`）
+pub fn is_legacy(input: &str) -> bool {
+    parse(input).is_err()
+        && !input.trim().is_empty()
+        && !placeholder::regex()
+            .replace_all(input, "")
+            .chars()
+            .any(|c| OPERATOR_CHARS.contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::invoke::Artifact;
+    use crate::pipeline::report::StepStatus;
+    use std::collections::HashMap;
+
+    fn ctx() -> PipelineContext {
+        let mut variables = HashMap::new();
+        variables.insert("mode".into(), "release".into());
+        let mut ctx = PipelineContext::with_variables(variables);
+        let mut artifact = Artifact::default();
+        artifact.data.insert("count".into(), serde_json::json!(12));
+        ctx.set_artifact("build".into(), artifact);
+        ctx.set_status("build".into(), StepStatus::Success);
+        ctx.set_status("lint".into(), StepStatus::FailedTolerated);
+        ctx
+    }
+
+    fn eval(expr: &str) -> bool {
+        parse(expr).unwrap().eval(&ctx()).truthy()
+    }
+
+    #[test]
+    fn comparisons_and_logic() {
+        assert!(eval("${var.mode} == 'release'"));
+        assert!(eval("var.mode != 'debug' && steps.build.status == success"));
+        assert!(eval("steps.build.artifact.data.count > 9"));
+        assert!(!eval("steps.build.artifact.data.count < 9"));
+        assert!(eval("!(steps.lint.status == 'success') || false"));
+        assert!(eval("steps.missing.status == ''"));
+        assert!(!eval("${env.COREX_EXPR_TEST_UNSET}"));
+        assert!(eval("yes"));
+    }
+
+    #[test]
+    fn builtin_functions() {
+        assert!(eval("matches(var.mode, '^rel')"));
+        assert!(!eval("env_set('COREX_EXPR_TEST_UNSET')"));
+        assert!(eval(&format!("exists('{}')", env!("CARGO_MANIFEST_DIR"))));
+    }
+
+    #[test]
+    fn references_include_text_placeholders() {
+        let expr = parse("steps.build.status == success && exists('${var.out}/a.zip')").unwrap();
+        assert_eq!(expr.references(), ["steps.build.status", "var.out"]);
+    }
+
+    #[test]
+    fn legacy_when_falls_back_to_text() {
+        let legacy = parse_when("${var.mode} build").unwrap();
+        assert_eq!(legacy, Expr::Text("${var.mode} build".into()));
+        assert!(legacy.eval(&ctx()).truthy());
+        assert!(is_legacy("${var.a}-${var.b}"));
+        assert!(!is_legacy("var.mode == 'x'"));
+        assert!(parse_when("var.mode ==").is_err());
+        assert!(parse_when("${var.mode|upper} ==").is_err());
+    }
+
+    #[test]
+    fn parse_errors() {
+        for bad in [
+            "",
+            "var.mode ==",
+            "(a",
+            "'open",
+            "nope(1)",
+            "exists()",
+            "matches(a, '(')",
+            "stpes.build.status",
+            "a b",
+        ] {
+            assert!(parse(bad).is_err(), "{bad}");
+        }
+    }
+}
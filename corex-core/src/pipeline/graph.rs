@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use petgraph::Direction;
 use petgraph::algo::{is_cyclic_directed, toposort};
 use petgraph::graph::{DiGraph, NodeIndex};
@@ -22,21 +22,32 @@ impl StageGraph {
             index.insert(step.id.clone(), idx);
         }
 
+        // foreach / matrix 分组 → 成员
+        let mut groups: HashMap<&str, Vec<NodeIndex>> = HashMap::new();
+        for step in &pipeline.steps {
+            if let Some(ref group) = step.group {
+                groups.entry(group).or_default().push(index[&step.id]);
+            }
+        }
+
         for (i, step) in pipeline.steps.iter().enumerate() {
             let to = index[&step.id];
-            let deps: Vec<String> = if step.depends_on.is_empty() {
-                if i == 0 {
-                    vec![]
-                } else {
-                    vec![pipeline.steps[i - 1].id.clone()]
-                }
+            let deps: Vec<NodeIndex> = if step.depends_on.is_empty() {
+                implicit_deps(&pipeline.steps, i, &index, &groups)
             } else {
-                step.depends_on.clone()
+                let mut deps = Vec::new();
+                for dep in &step.depends_on {
+                    match (index.get(dep), groups.get(dep.as_str())) {
+                        (Some(idx), _) => deps.push(*idx),
+                        (None, Some(members)) => deps.extend(members),
+                        (None, None) => {
+                            anyhow::bail!("步骤 '{}' depends_on 未知: {}", step.id, dep)
+                        }
+                    }
+                }
+                deps
             };
-            for dep in deps {
-                let from = *index
-                    .get(&dep)
-                    .with_context(|| format!("步骤 '{}' depends_on 未知: {}", step.id, dep))?;
+            for from in deps {
                 graph.add_edge(from, to, ());
             }
         }
@@ -82,11 +93,63 @@ impl StageGraph {
         Ok(layers)
     }
 
+    /// 全部边 `(上游, 下游)`
+    pub fn edges(&self) -> Vec<(String, String)> {
+        self.graph
+            .edge_indices()
+            .filter_map(|e| self.graph.edge_endpoints(e))
+            .map(|(from, to)| (self.graph[from].clone(), self.graph[to].clone()))
+            .collect()
+    }
+
+    /// 直接依赖（含隐式链）
+    pub fn dependencies(&self, id: &str) -> Vec<String> {
+        self.neighbors(id, Direction::Incoming)
+    }
+
+    /// 直接下游
+    pub fn dependents(&self, id: &str) -> Vec<String> {
+        self.neighbors(id, Direction::Outgoing)
+    }
+
+    fn neighbors(&self, id: &str, dir: Direction) -> Vec<String> {
+        self.graph
+            .node_indices()
+            .find(|idx| self.graph[*idx] == id)
+            .map(|idx| {
+                self.graph
+                    .neighbors_directed(idx, dir)
+                    .map(|n| self.graph[n].clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     pub fn step_by_id<'a>(&self, pipeline: &'a PipelineConfig, id: &str) -> Option<&'a StepConfig> {
         pipeline.steps.iter().find(|s| s.id == id)
     }
 }
 
+/// 隐式链：依赖前一步骤；同组成员共享组前的依赖，组后的步骤依赖整组
+fn implicit_deps(
+    steps: &[StepConfig],
+    i: usize,
+    index: &HashMap<String, NodeIndex>,
+    groups: &HashMap<&str, Vec<NodeIndex>>,
+) -> Vec<NodeIndex> {
+    let mut i = i;
+    while i > 0 && steps[i].group.is_some() && steps[i - 1].group == steps[i].group {
+        i -= 1;
+    }
+    if i == 0 {
+        return vec![];
+    }
+    match steps[i - 1].group.as_deref() {
+        Some(group) => groups[group].clone(),
+        None => vec![index[&steps[i - 1].id]],
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,14 +168,12 @@ mod tests {
     fn implicit_chain_when_no_depends_on() {
         let pipeline = PipelineConfig {
             id: "p".into(),
-            description: None,
-            schedule: None,
-            watch: None,
             steps: vec![
                 step("a", "copy", vec![]),
                 step("b", "generate", vec![]),
                 step("c", "compression", vec![]),
             ],
+            ..Default::default()
         };
         let graph = StageGraph::from_pipeline(&pipeline).unwrap();
         graph.validate().unwrap();
@@ -127,14 +188,12 @@ mod tests {
     fn fork_join_layers() {
         let pipeline = PipelineConfig {
             id: "p".into(),
-            description: None,
-            schedule: None,
-            watch: None,
             steps: vec![
                 step("root", "copy", vec![]),
                 step("left", "generate", vec!["root"]),
                 step("right", "compression", vec!["root"]),
             ],
+            ..Default::default()
         };
         let graph = StageGraph::from_pipeline(&pipeline).unwrap();
         let layers = graph.execution_layers().unwrap();
@@ -145,14 +204,45 @@ mod tests {
         assert!(layers[1].contains(&"right".to_string()));
     }
 
+    #[test]
+    fn group_members_share_deps_and_join_downstream() {
+        let member = |n: usize| StepConfig {
+            group: Some("pack".into()),
+            ..step(&format!("pack[{n}]"), "compression", vec![])
+        };
+        let pipeline = PipelineConfig {
+            id: "p".into(),
+            steps: vec![
+                step("prep", "copy", vec![]),
+                member(0),
+                member(1),
+                step("upload", "copy", vec![]),
+                step("notify", "copy", vec!["pack"]),
+            ],
+            ..Default::default()
+        };
+        let graph = StageGraph::from_pipeline(&pipeline).unwrap();
+        let mut layers = graph.execution_layers().unwrap();
+        layers.iter_mut().for_each(|layer| layer.sort());
+        assert_eq!(
+            layers,
+            vec![
+                vec!["prep".to_string()],
+                vec!["pack[0]".to_string(), "pack[1]".to_string()],
+                vec!["notify".to_string(), "upload".to_string()],
+            ]
+        );
+        let mut upstream = graph.dependencies("notify");
+        upstream.sort();
+        assert_eq!(upstream, vec!["pack[0]", "pack[1]"]);
+    }
+
     #[test]
     fn cycle_is_rejected() {
         let pipeline = PipelineConfig {
             id: "p".into(),
-            description: None,
-            schedule: None,
-            watch: None,
             steps: vec![step("a", "copy", vec!["b"]), step("b", "copy", vec!["a"])],
+            ..Default::default()
         };
         let graph = StageGraph::from_pipeline(&pipeline).unwrap();
         assert!(graph.validate().is_err());
@@ -1,21 +1,29 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use crossterm::style::Stylize;
 
-use crate::pipeline::config::PipelineConfig;
+use crate::pipeline::config::{ConcurrencyPolicy, PipelineConfig};
 use crate::pipeline::context::PipelineContext;
+use crate::pipeline::history::Trigger;
+use crate::pipeline::lock::{self, TryLock};
 use crate::pipeline::runner::run_pipeline;
+use crate::runtime;
+use crate::utils::cancel;
 
-/// 正在执行的 pipeline id 集合（watch / cron 共享）
-pub type RunningSet = Arc<Mutex<HashSet<String>>>;
+/// 守护循环检查 Ctrl+C 的间隔
+pub const SHUTDOWN_POLL: Duration = Duration::from_millis(200);
+
+/// 各 pipeline 在本进程内登记的触发序号，队首为正在执行者（watch / cron 共享）
+pub type RunningSet = Arc<Mutex<HashMap<String, VecDeque<u64>>>>;
 
 /// 最近一次 pipeline 执行完成时间（watch 冷却抑制）
 pub type LastFinished = Arc<Mutex<Option<Instant>>>;
 
 pub fn new_set() -> RunningSet {
-    Arc::new(Mutex::new(HashSet::new()))
+    Arc::new(Mutex::new(HashMap::new()))
 }
 
 pub fn new_last_finished() -> LastFinished {
@@ -37,41 +45,96 @@ pub fn is_in_cooldown(last_finished: &LastFinished, cooldown: Duration) -> bool
         .is_some_and(|finished| finished.elapsed() < cooldown)
 }
 
-pub fn try_acquire(running: &RunningSet, pipeline_id: &str) -> bool {
+/// 按策略登记一次触发，返回排队序号；None 表示丢弃本次触发
+/// （skip：已有登记；wait：已有一个等待中的触发；queue：不丢弃）
+pub fn enqueue(running: &RunningSet, pipeline_id: &str, policy: ConcurrencyPolicy) -> Option<u64> {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+
     let mut guard = running.lock().expect("running lock poisoned");
-    if guard.contains(pipeline_id) {
-        false
-    } else {
-        guard.insert(pipeline_id.to_string());
-        true
+    let pending = guard.get(pipeline_id).map_or(0, VecDeque::len);
+    let admit = match policy {
+        ConcurrencyPolicy::Skip => pending == 0,
+        ConcurrencyPolicy::Wait => pending < 2,
+        ConcurrencyPolicy::Queue => true,
+    };
+    if !admit {
+        return None;
     }
+    let ticket = NEXT.fetch_add(1, Ordering::Relaxed);
+    guard
+        .entry(pipeline_id.to_string())
+        .or_default()
+        .push_back(ticket);
+    Some(ticket)
+}
+
+pub fn try_acquire(running: &RunningSet, pipeline_id: &str) -> bool {
+    enqueue(running, pipeline_id, ConcurrencyPolicy::Skip).is_some()
 }
 
+/// 移除队首登记
 pub fn release(running: &RunningSet, pipeline_id: &str) {
-    running
-        .lock()
-        .expect("running lock poisoned")
-        .remove(pipeline_id);
+    let mut guard = running.lock().expect("running lock poisoned");
+    if let Some(queue) = guard.get_mut(pipeline_id) {
+        queue.pop_front();
+        if queue.is_empty() {
+            guard.remove(pipeline_id);
+        }
+    }
+}
+
+/// 移除指定登记（执行结束或等待中关停）
+fn leave(running: &RunningSet, pipeline_id: &str, ticket: u64) {
+    let mut guard = running.lock().expect("running lock poisoned");
+    if let Some(queue) = guard.get_mut(pipeline_id) {
+        queue.retain(|t| *t != ticket);
+        if queue.is_empty() {
+            guard.remove(pipeline_id);
+        }
+    }
+}
+
+/// 等待轮到 `ticket`；关停时返回 false
+fn wait_turn(running: &RunningSet, pipeline_id: &str, ticket: u64) -> bool {
+    loop {
+        let front = running
+            .lock()
+            .expect("running lock poisoned")
+            .get(pipeline_id)
+            .and_then(|queue| queue.front().copied());
+        if front == Some(ticket) {
+            return true;
+        }
+        if cancel::shutdown().is_cancelled() {
+            return false;
+        }
+        std::thread::sleep(SHUTDOWN_POLL);
+    }
+}
+
+/// 等待运行中的 pipeline 全部结束（Ctrl+C 后等待其写出报告）
+pub fn wait_idle(running: &RunningSet) {
+    while !running.lock().expect("running lock poisoned").is_empty() {
+        std::thread::sleep(SHUTDOWN_POLL);
+    }
 }
 
-/// 后台执行 pipeline；若已在运行则跳过
+/// 后台执行 pipeline（记为 watch 触发）；按 concurrency_policy 跳过或排队，正在关停时跳过
 pub fn spawn(
     running: RunningSet,
     pipeline: &PipelineConfig,
-    variables: &HashMap<String, String>,
+    base: &PipelineContext,
     reason: &str,
     last_finished: Option<LastFinished>,
 ) {
-    let pipeline_id = pipeline.id.clone();
-    if !try_acquire(&running, &pipeline_id) {
-        eprintln!(
-            "  {} Pipeline '{}' 正在执行，跳过本次{}触发",
-            "⊘".yellow(),
-            pipeline_id,
-            reason
-        );
+    if cancel::shutdown().is_cancelled() {
         return;
     }
+    let pipeline_id = pipeline.id.clone();
+    let Some(ticket) = enqueue(&running, &pipeline_id, pipeline.concurrency_policy) else {
+        eprint_skipped(&pipeline_id, reason);
+        return;
+    };
 
     let desc = pipeline
         .description
@@ -79,60 +142,106 @@ pub fn spawn(
         .unwrap_or(&pipeline.id)
         .to_string();
     let pipeline = pipeline.clone();
-    let variables = variables.clone();
-
-    println!(
-        "\n  {} [{}] {} 触发: {}",
-        "⚡".yellow().bold(),
-        chrono::Local::now().format("%H:%M:%S"),
-        reason,
-        desc.bold()
-    );
+    let mut ctx = base.clone();
 
-    std::thread::spawn(move || {
-        let mut ctx = PipelineContext::with_variables(variables);
-        let result = run_pipeline(&pipeline, &mut ctx);
-        release(&running, &pipeline_id);
-        if let Some(last_finished) = last_finished {
-            mark_finished(&last_finished);
-        }
+    if !runtime::is_json_output() {
+        println!(
+            "\n  {} [{}] {} 触发: {}",
+            "⚡".yellow().bold(),
+            chrono::Local::now().format("%H:%M:%S"),
+            reason,
+            desc.bold()
+        );
+    }
 
-        match result {
-            Ok(()) => println!("  {} Pipeline '{}' 执行完成\n", "✓".green(), pipeline_id),
-            Err(e) => eprintln!(
-                "  {} Pipeline '{}' 执行失败: {}\n",
-                "×".red(),
-                pipeline_id,
-                e
-            ),
+    std::thread::spawn(move || {
+        let result = run_exclusive(&running, ticket, &pipeline, &mut ctx, Trigger::Watch);
+        if let Some(result) = result {
+            if let Some(last_finished) = last_finished {
+                mark_finished(&last_finished);
+            }
+            report_result(&pipeline_id, result);
         }
     });
 }
 
-/// 同步执行；若已在运行则跳过
-pub fn run_sync(
+/// 后台执行（记为 cron 触发）；按 concurrency_policy 跳过或排队等待，等待不阻塞调度循环，正在关停时跳过
+pub fn spawn_cron(
     running: &RunningSet,
     pipeline: &PipelineConfig,
-    variables: &HashMap<String, String>,
+    base: &PipelineContext,
     reason: &str,
 ) {
-    let pipeline_id = pipeline.id.clone();
-    if !try_acquire(running, &pipeline_id) {
-        eprintln!(
-            "  {} Pipeline '{}' 正在执行，跳过本次{}触发",
-            "⊘".yellow(),
-            pipeline_id,
-            reason
-        );
+    if cancel::shutdown().is_cancelled() {
         return;
     }
+    let pipeline_id = pipeline.id.clone();
+    let Some(ticket) = enqueue(running, &pipeline_id, pipeline.concurrency_policy) else {
+        eprint_skipped(&pipeline_id, reason);
+        return;
+    };
 
-    let mut ctx = PipelineContext::with_variables(variables.clone());
-    let result = run_pipeline(pipeline, &mut ctx);
-    release(running, &pipeline_id);
+    let running = running.clone();
+    let pipeline = pipeline.clone();
+    let mut ctx = base.clone();
+    std::thread::spawn(move || {
+        if let Some(result) = run_exclusive(&running, ticket, &pipeline, &mut ctx, Trigger::Cron) {
+            report_result(&pipeline_id, result);
+        }
+    });
+}
 
+/// 依次等待本进程内排队与跨进程锁后执行；未执行（被其他进程持有或关停）时返回 None
+fn run_exclusive(
+    running: &RunningSet,
+    ticket: u64,
+    pipeline: &PipelineConfig,
+    ctx: &mut PipelineContext,
+    trigger: Trigger,
+) -> Option<anyhow::Result<()>> {
+    let result = if wait_turn(running, &pipeline.id, ticket) {
+        match lock::acquire(&pipeline.id, pipeline.concurrency_policy) {
+            Ok(TryLock::Acquired(lock)) => {
+                let result = run_pipeline(pipeline, ctx, trigger);
+                drop(lock);
+                Some(result)
+            }
+            Ok(TryLock::Held(info)) => {
+                if !cancel::shutdown().is_cancelled() {
+                    eprintln!(
+                        "  {} Pipeline '{}' 正在被进程 {} 执行，跳过本次触发",
+                        "⊘".yellow(),
+                        pipeline.id,
+                        info.pid
+                    );
+                }
+                None
+            }
+            Err(e) => Some(Err(e)),
+        }
+    } else {
+        None
+    };
+    leave(running, &pipeline.id, ticket);
+    result
+}
+
+fn eprint_skipped(pipeline_id: &str, reason: &str) {
+    eprintln!(
+        "  {} Pipeline '{}' 正在执行，跳过本次{}触发",
+        "⊘".yellow(),
+        pipeline_id,
+        reason
+    );
+}
+
+/// 守护模式下单次执行的结果提示（事件流模式下由 run_finished 表达）
+fn report_result(pipeline_id: &str, result: anyhow::Result<()>) {
     match result {
-        Ok(()) => println!("  {} Pipeline '{}' 执行完成\n", "✓".green(), pipeline_id),
+        Ok(()) if !runtime::is_json_output() => {
+            println!("  {} Pipeline '{}' 执行完成\n", "✓".green(), pipeline_id)
+        }
+        Ok(()) => {}
         Err(e) => eprintln!(
             "  {} Pipeline '{}' 执行失败: {}\n",
             "×".red(),
@@ -163,6 +272,22 @@ mod tests {
         assert!(try_acquire(&running, "demo"));
     }
 
+    #[test]
+    fn enqueue_follows_policy() {
+        let running = new_set();
+        assert!(enqueue(&running, "a", ConcurrencyPolicy::Wait).is_some());
+        assert!(enqueue(&running, "a", ConcurrencyPolicy::Skip).is_none());
+        let waiting = enqueue(&running, "a", ConcurrencyPolicy::Wait).unwrap();
+        assert!(enqueue(&running, "a", ConcurrencyPolicy::Wait).is_none());
+        assert!(enqueue(&running, "a", ConcurrencyPolicy::Queue).is_some());
+
+        release(&running, "a");
+        assert!(wait_turn(&running, "a", waiting));
+        leave(&running, "a", waiting);
+        release(&running, "a");
+        wait_idle(&running);
+    }
+
     #[test]
     fn is_in_cooldown_after_mark_finished() {
         let last = new_last_finished();
@@ -0,0 +1,270 @@
+//! 运行历史：`corex pipeline history` / `show` 查询运行目录中的运行记录（见 [`state`]）
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use crossterm::style::Stylize;
+use serde::{Deserialize, Serialize};
+
+use crate::runtime;
+
+use super::report::{RunReport, RunStatus, StepStatus};
+use super::state::{self, run_ids};
+
+/// 触发来源
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Trigger {
+    /// `corex pipeline` / `corex schedule run`
+    #[default]
+    Manual,
+    Cron,
+    Watch,
+    /// 经 IPC（`module: pipeline`）调用
+    Ipc,
+}
+
+impl Trigger {
+    fn as_str(self) -> &'static str {
+        match self {
+            Trigger::Manual => "manual",
+            Trigger::Cron => "cron",
+            Trigger::Watch => "watch",
+            Trigger::Ipc => "ipc",
+        }
+    }
+}
+
+/// 单条运行记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub trigger: Trigger,
+    #[serde(flatten)]
+    pub report: RunReport,
+}
+
+impl From<state::RunState> for HistoryEntry {
+    fn from(state: state::RunState) -> Self {
+        Self {
+            trigger: state.trigger,
+            report: state.report,
+        }
+    }
+}
+
+/// 按条件列出运行记录（新 → 旧）；损坏的记录忽略
+fn list_in(
+    dir: &Path,
+    pipeline: Option<&str>,
+    status: Option<RunStatus>,
+    limit: usize,
+) -> Result<Vec<HistoryEntry>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let pipelines: Vec<PathBuf> = match pipeline {
+        Some(id) => vec![dir.join(state::encode_id(id))],
+        None => fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_dir())
+            .collect(),
+    };
+
+    let mut runs: Vec<(String, PathBuf)> = Vec::new();
+    for pipeline_dir in pipelines.iter().filter(|p| p.is_dir()) {
+        for run_id in run_ids(pipeline_dir)? {
+            let path = pipeline_dir.join(format!("{run_id}.json"));
+            runs.push((run_id, path));
+        }
+    }
+    runs.sort_by(|a, b| b.0.cmp(&a.0));
+
+    Ok(runs
+        .iter()
+        .filter_map(|(_, path)| state::read(path).ok().map(HistoryEntry::from))
+        .filter(|entry| status.is_none_or(|s| entry.report.status == s))
+        .take(limit)
+        .collect())
+}
+
+/// 按运行 ID 查找记录；`last` 表示最近一次运行
+fn find_in(dir: &Path, run_id: &str) -> Result<HistoryEntry> {
+    if run_id == "last" {
+        return list_in(dir, None, None, 1)?.pop().context("尚无运行历史");
+    }
+    state::read(&state::find(dir, run_id)?).map(HistoryEntry::from)
+}
+
+/// `corex pipeline history`
+pub fn list(pipeline: Option<&str>, status: Option<RunStatus>, limit: usize) -> Result<()> {
+    let entries = list_in(&state::history_dir()?, pipeline, status, limit)?;
+    if runtime::is_json_output() {
+        runtime::state().emitter.json(&entries)?;
+    } else if !runtime::is_quiet() {
+        print_history(&entries);
+    }
+    Ok(())
+}
+
+/// `corex pipeline show`
+pub fn show(run_id: &str) -> Result<()> {
+    let entry = find_in(&state::history_dir()?, run_id)?;
+    if runtime::is_json_output() {
+        runtime::state().emitter.json(&entry)?;
+    } else if !runtime::is_quiet() {
+        print_entry(&entry);
+    }
+    Ok(())
+}
+
+fn print_history(entries: &[HistoryEntry]) {
+    if entries.is_empty() {
+        println!("  暂无运行历史");
+        return;
+    }
+    // 中文表头按显示宽度（每字两列）对齐
+    println!(
+        "\n  运行 ID               Pipeline             触发     状态            耗时  开始时间"
+    );
+    for entry in entries {
+        let report = &entry.report;
+        println!(
+            "  {:<21} {:<20} {:<8} {} {:>10}  {}",
+            report.run_id,
+            report.pipeline_id,
+            entry.trigger.as_str(),
+            status_label(report.status),
+            format!("{} ms", report.duration_ms),
+            local_time(&report.started_at)
+        );
+    }
+    println!();
+}
+
+fn print_entry(entry: &HistoryEntry) {
+    let report = &entry.report;
+    println!(
+        "\n  {} {}  {}",
+        "▶".green().bold(),
+        report.pipeline_id.as_str().bold(),
+        status_label(report.status)
+    );
+    println!("  运行 ID: {}", report.run_id);
+    println!("  触发: {}", entry.trigger.as_str());
+    println!("  开始时间: {}", local_time(&report.started_at));
+    println!("  耗时: {} ms", report.duration_ms);
+    if let Some(ref from) = report.resumed_from {
+        println!("  恢复自: {from}");
+    }
+    if !report.outputs.is_empty() {
+        println!("  输出:");
+        for (name, value) in &report.outputs {
+            println!("     {name} = {value}");
+        }
+    }
+    println!();
+    for step in &report.steps {
+        let icon = match step.status {
+            StepStatus::Success | StepStatus::Cached => "✓".green(),
+            StepStatus::Skipped => "⊘".dim(),
+            StepStatus::FailedTolerated | StepStatus::Cancelled => "⚠".yellow(),
+            StepStatus::Failed | StepStatus::TimedOut => "×".red(),
+        };
+        let status = step.status.as_str();
+        println!(
+            "  {} {} — {} ({}, {} ms)",
+            icon,
+            step.id.as_str().bold(),
+            step.module,
+            status,
+            step.duration_ms
+        );
+        if let Some(ref err) = step.error {
+            println!("       {}", err.as_str().red());
+        }
+        for attempt in &step.attempts {
+            let delay = attempt
+                .delay_ms
+                .map(|ms| format!("，等待 {ms} ms 后重试"))
+                .unwrap_or_default();
+            let line = format!(
+                "第 {} 次: {}（{} ms{}）{}",
+                attempt.attempt,
+                attempt.status.as_str(),
+                attempt.duration_ms,
+                delay,
+                attempt.error.as_deref().unwrap_or_default()
+            );
+            println!("       {}", line.dim());
+        }
+    }
+    println!();
+}
+
+fn status_label(status: RunStatus) -> String {
+    match status {
+        RunStatus::Success => format!("{:<9}", "success").green().to_string(),
+        RunStatus::Failed => format!("{:<9}", "failed").red().to_string(),
+        RunStatus::Cancelled => format!("{:<9}", "cancelled").yellow().to_string(),
+    }
+}
+
+/// RFC 3339 → 本地时间
+fn local_time(started_at: &str) -> String {
+    DateTime::parse_from_rfc3339(started_at)
+        .map(|t| {
+            t.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|_| started_at.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(pipeline: &str, run_id: &str, status: RunStatus) -> RunReport {
+        RunReport {
+            run_id: run_id.into(),
+            status,
+            ..RunReport::new(pipeline)
+        }
+    }
+
+    #[test]
+    fn list_filters_by_pipeline_and_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = Local::now().format("%Y%m%d-%H%M%S").to_string();
+        for (pipeline, seq, status) in [
+            ("batch-copy", 1, RunStatus::Success),
+            ("batch-copy", 2, RunStatus::Failed),
+            ("build-h5", 3, RunStatus::Failed),
+        ] {
+            let run_id = format!("{now}-00{seq}");
+            let state = state::RunState {
+                trigger: Trigger::Cron,
+                variables: Default::default(),
+                report: report(pipeline, &run_id, status),
+            };
+            state::save_in(dir.path(), &state).unwrap();
+        }
+
+        let all = list_in(dir.path(), None, None, 10).unwrap();
+        let ids: Vec<&str> = all.iter().map(|e| e.report.pipeline_id.as_str()).collect();
+        assert_eq!(ids, ["build-h5", "batch-copy", "batch-copy"]);
+
+        let failed = list_in(dir.path(), Some("batch-copy"), Some(RunStatus::Failed), 10).unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].report.run_id, format!("{now}-002"));
+        assert_eq!(failed[0].trigger, Trigger::Cron);
+
+        let last = find_in(dir.path(), "last").unwrap();
+        assert_eq!(last.report.pipeline_id, "build-h5");
+        assert!(find_in(dir.path(), "20200101-000000-000").is_err());
+        let err = find_in(dir.path(), "../../x").unwrap_err().to_string();
+        assert!(err.contains("无效的运行 ID"), "{err}");
+    }
+}
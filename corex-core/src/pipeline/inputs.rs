@@ -0,0 +1,286 @@
+//! Pipeline 输入（`inputs:`）：带类型、默认值与校验的变量，以 `${var.NAME}` 引用。
+//!
+//! 取值优先级：-D / `COREX_VAR_*` / 同名 variables > 交互输入 > default；
+//! 执行前统一校验（手动、watch、cron、子 Pipeline 与 IPC 均经 [`resolve`]）。
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use dialoguer::theme::ColorfulTheme;
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use super::config::{InputSpec, InputType, PipelineConfig};
+
+impl InputSpec {
+    /// 校验声明本身：enum 须有 choices、pattern 为有效正则、min ≤ max、default 合法
+    pub fn validate(&self) -> Result<()> {
+        match self.kind {
+            InputType::Enum if self.choices.is_empty() => bail!("enum 类型须声明 choices"),
+            InputType::Enum => {}
+            _ if !self.choices.is_empty() => bail!("choices 仅用于 enum 类型"),
+            _ => {}
+        }
+        if let Some(ref pattern) = self.pattern
+            && let Err(e) = Regex::new(pattern)
+        {
+            bail!("pattern '{pattern}' 不是有效的正则: {e}");
+        }
+        if let (Some(min), Some(max)) = (self.min, self.max)
+            && min > max
+        {
+            bail!("min ({min}) 不能大于 max ({max})");
+        }
+        if let Some(ref default) = self.default {
+            // path 的存在性在执行时检查
+            let value = scalar(default)?;
+            if self.kind != InputType::Path {
+                self.check(&value)
+                    .map_err(|e| anyhow::anyhow!("default {e}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 校验取值并规范化（bool → true / false）
+    pub fn check(&self, value: &str) -> Result<String> {
+        let value = match self.kind {
+            InputType::String | InputType::Path => value.to_string(),
+            InputType::Bool => match value.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "y" | "1" => "true".to_string(),
+                "false" | "no" | "n" | "0" => "false".to_string(),
+                _ => bail!("期望 bool（true / false），实际 '{value}'"),
+            },
+            InputType::Int => {
+                let n: i64 = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("期望整数，实际 '{value}'"))?;
+                if self.min.is_some_and(|min| n < min) || self.max.is_some_and(|max| n > max) {
+                    bail!(
+                        "{n} 超出范围 [{}, {}]",
+                        self.min.map_or("-∞".into(), |v| v.to_string()),
+                        self.max.map_or("+∞".into(), |v| v.to_string())
+                    );
+                }
+                n.to_string()
+            }
+            InputType::Enum => {
+                if !self.choices.iter().any(|c| c == value) {
+                    bail!("'{value}' 不在可选值中（{}）", self.choices.join(" / "));
+                }
+                value.to_string()
+            }
+        };
+        if let Some(ref pattern) = self.pattern
+            && !value.is_empty()
+            && !Regex::new(pattern).is_ok_and(|re| re.is_match(&value))
+        {
+            bail!("'{value}' 不匹配 pattern {pattern}");
+        }
+        if self.kind == InputType::Path && self.exists && !Path::new(&value).exists() {
+            bail!("路径不存在: {value}");
+        }
+        Ok(value)
+    }
+
+    fn default_text(&self) -> Option<String> {
+        self.default.as_ref().and_then(|v| scalar(v).ok())
+    }
+
+    fn label(&self, name: &str) -> String {
+        match self.description {
+            Some(ref desc) => format!("{desc} ({name})"),
+            None => name.to_string(),
+        }
+    }
+}
+
+/// 执行前解析输入：已提供的值校验并规范化，未提供的取 default（可选输入无默认值时为空，bool 为 false）；
+/// 缺少必填输入或取值无效时报错
+pub fn resolve(pipeline: &PipelineConfig, variables: &mut HashMap<String, String>) -> Result<()> {
+    let mut missing = Vec::new();
+    for (name, spec) in &pipeline.inputs {
+        let provided = variables
+            .get(name)
+            .cloned()
+            .or_else(|| spec.default_text())
+            .filter(|v| !(spec.required && v.is_empty()));
+        let value = match provided {
+            Some(value) => spec.check(&value).map_err(|e| {
+                anyhow::anyhow!("Pipeline '{}' 输入 '{}' 无效: {}", pipeline.id, name, e)
+            })?,
+            None if spec.required => {
+                missing.push(name.as_str());
+                continue;
+            }
+            None if spec.kind == InputType::Bool => "false".to_string(),
+            None => String::new(),
+        };
+        variables.insert(name.clone(), value);
+    }
+    if !missing.is_empty() {
+        bail!(
+            "Pipeline '{}' 缺少必填输入: {}\n提示: 使用 -D {}=... 提供",
+            pipeline.id,
+            missing.join(", "),
+            missing[0]
+        );
+    }
+    Ok(())
+}
+
+/// 未提供的输入填入 default（无默认值时为空），不校验；供 validate / graph 等不执行的场景
+pub fn fill_defaults(pipeline: &PipelineConfig, variables: &mut HashMap<String, String>) {
+    for (name, spec) in &pipeline.inputs {
+        if !variables.contains_key(name) {
+            variables.insert(name.clone(), spec.default_text().unwrap_or_default());
+        }
+    }
+}
+
+/// 交互询问未提供的输入（default 预填），返回各输入的取值
+pub fn prompt(
+    pipeline: &PipelineConfig,
+    variables: &HashMap<String, String>,
+) -> Result<HashMap<String, String>> {
+    let theme = ColorfulTheme::default();
+    let mut answers = HashMap::new();
+    for (name, spec) in &pipeline.inputs {
+        if variables.contains_key(name) {
+            continue;
+        }
+        let default = spec.default_text();
+        let value = match spec.kind {
+            InputType::Bool => {
+                let default = default.as_deref().and_then(|d| spec.check(d).ok());
+                dialoguer::Confirm::with_theme(&theme)
+                    .with_prompt(spec.label(name))
+                    .default(default.as_deref() == Some("true"))
+                    .interact()?
+                    .to_string()
+            }
+            InputType::Enum => {
+                let selected = default
+                    .and_then(|d| spec.choices.iter().position(|c| *c == d))
+                    .unwrap_or(0);
+                let idx = dialoguer::Select::with_theme(&theme)
+                    .with_prompt(spec.label(name))
+                    .items(&spec.choices)
+                    .default(selected)
+                    .interact()?;
+                spec.choices[idx].clone()
+            }
+            InputType::String | InputType::Path | InputType::Int => {
+                let mut input = dialoguer::Input::<String>::with_theme(&theme)
+                    .with_prompt(spec.label(name))
+                    .allow_empty(!spec.required)
+                    .validate_with(|v: &String| -> Result<(), String> {
+                        if v.is_empty() {
+                            return Ok(());
+                        }
+                        spec.check(v).map(drop).map_err(|e| e.to_string())
+                    });
+                if let Some(default) = default {
+                    input = input.default(default);
+                }
+                let value = input.interact_text()?;
+                if value.is_empty() && !spec.required {
+                    continue;
+                }
+                value
+            }
+        };
+        answers.insert(name.clone(), value);
+    }
+    Ok(answers)
+}
+
+/// 带类型的输入值（IPC / `module: pipeline`）→ 变量字符串；仅接受已声明的输入
+pub fn from_json(
+    pipeline: &PipelineConfig,
+    values: &Map<String, Value>,
+) -> Result<HashMap<String, String>> {
+    values
+        .iter()
+        .map(|(name, value)| {
+            if !pipeline.inputs.contains_key(name) {
+                bail!("Pipeline '{}' 未声明输入 '{}'", pipeline.id, name);
+            }
+            let text = scalar(value)
+                .map_err(|e| anyhow::anyhow!("Pipeline '{}' 输入 '{}' {}", pipeline.id, name, e))?;
+            Ok((name.clone(), text))
+        })
+        .collect()
+}
+
+fn scalar(value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Bool(b) => Ok(b.to_string()),
+        Value::Number(n) => Ok(n.to_string()),
+        _ => bail!("须为字符串、数字或 bool"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pipeline(inputs: Value) -> PipelineConfig {
+        serde_json::from_value(json!({ "id": "release", "inputs": inputs })).unwrap()
+    }
+
+    #[test]
+    fn resolve_validates_and_applies_defaults() {
+        let p = pipeline(json!({
+            "env": { "type": "enum", "choices": ["dev", "prod"], "default": "dev" },
+            "dry": { "type": "bool" },
+            "workers": { "type": "int", "min": 1, "max": 8, "default": 2 },
+            "version": { "required": true, "pattern": "^\\d+\\.\\d+\\.\\d+$" }
+        }));
+        let mut vars = HashMap::from([("version".to_string(), "1.2.3".to_string())]);
+        resolve(&p, &mut vars).unwrap();
+        assert_eq!(vars["env"], "dev");
+        assert_eq!(vars["dry"], "false");
+        assert_eq!(vars["workers"], "2");
+
+        let err = resolve(&p, &mut HashMap::new()).unwrap_err();
+        assert!(err.to_string().contains("缺少必填输入: version"), "{err}");
+        for (name, bad) in [
+            ("env", "qa"),
+            ("workers", "9"),
+            ("version", "v1"),
+            ("dry", "maybe"),
+        ] {
+            let mut vars = HashMap::from([
+                ("version".to_string(), "1.2.3".to_string()),
+                (name.to_string(), bad.to_string()),
+            ]);
+            assert!(resolve(&p, &mut vars).is_err(), "{name}={bad}");
+        }
+    }
+
+    #[test]
+    fn specs_and_typed_values_are_checked() {
+        assert!(
+            pipeline(json!({ "env": { "type": "enum" } })).inputs["env"]
+                .validate()
+                .is_err()
+        );
+        assert!(
+            pipeline(json!({ "n": { "type": "int", "default": "x" } })).inputs["n"]
+                .validate()
+                .is_err()
+        );
+
+        let p = pipeline(json!({ "dry": { "type": "bool" }, "n": { "type": "int" } }));
+        let values = from_json(&p, json!({ "dry": true, "n": 3 }).as_object().unwrap()).unwrap();
+        assert_eq!(values["dry"], "true");
+        assert_eq!(values["n"], "3");
+        assert!(from_json(&p, json!({ "other": 1 }).as_object().unwrap()).is_err());
+        assert!(from_json(&p, json!({ "n": [1] }).as_object().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+//! Ctrl+C 处理：首次按下取消 [`cancel::shutdown`]，步骤在安全点停止、子进程被终止并照常写出报告；
+//! 再次按下立即退出（退出码 130）
+
+use std::sync::Once;
+
+use crossterm::style::Stylize;
+
+use crate::utils::cancel;
+
+/// 注册 Ctrl+C 处理（重复调用无副作用）
+pub fn install() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| {
+        let spawned = std::thread::Builder::new()
+            .name("ctrl-c".into())
+            .spawn(listen);
+        if let Err(e) = spawned {
+            eprintln!("  {} 注册 Ctrl+C 处理失败: {}", "⚠".yellow(), e);
+        }
+    });
+}
+
+fn listen() {
+    let rt = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(rt) => rt,
+        Err(e) => {
+            eprintln!("  {} 注册 Ctrl+C 处理失败: {}", "⚠".yellow(), e);
+            return;
+        }
+    };
+
+    rt.block_on(async {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            let token = cancel::shutdown();
+            if token.is_cancelled() {
+                eprintln!("\n  {} 强制退出", "×".red().bold());
+                std::process::exit(130);
+            }
+            token.cancel();
+            eprintln!(
+                "\n  {} 正在取消，等待步骤停止并写出报告...（再次 Ctrl+C 强制退出）",
+                "⊘".yellow().bold()
+            );
+        }
+    });
+}
@@ -0,0 +1,458 @@
+//! pipelines.yaml 的 JSON Schema（`corex schema`）：由配置结构与各模块 schema::Args 生成，
+//! `params` 按 module / action / format / algorithm 条件约束
+
+use anyhow::{Result, bail};
+use clap::{CommandFactory, Subcommand};
+use schemars::JsonSchema;
+use schemars::generate::SchemaSettings;
+use serde_json::{Map, Value, json};
+
+use super::config::{PipelinesConfig, StepConfig};
+use super::subpipeline;
+
+/// `corex schema` 子命令
+#[derive(Debug, Clone, Subcommand)]
+pub enum Args {
+    /// 输出 pipelines.yaml 的 JSON Schema
+    Pipelines,
+    /// 输出单个 module 步骤的 JSON Schema
+    Module {
+        /// module 名，如 copy / compression
+        name: String,
+    },
+}
+
+/// `corex schema` 命令处理：Schema 输出到 stdout
+pub fn run(args: &Args) -> Result<()> {
+    let schema = match args {
+        Args::Pipelines => pipelines_schema(),
+        Args::Module { name } => module_schema(name)?,
+    };
+    println!("{}", serde_json::to_string_pretty(&schema)?);
+    Ok(())
+}
+
+/// 一条线格式路由及其 params Schema
+struct Route {
+    module: &'static str,
+    action: Option<&'static str>,
+    /// compression 的 format / codec 的 algorithm
+    variant: Option<&'static str>,
+    params: Value,
+}
+
+fn route<T: JsonSchema + CommandFactory>(
+    module: &'static str,
+    action: Option<&'static str>,
+    variant: Option<&'static str>,
+) -> Route {
+    let mut params = schema::<T>();
+    describe(&mut params, &T::command());
+    allow_placeholders(&mut params);
+    Route {
+        module,
+        action,
+        variant,
+        params,
+    }
+}
+
+/// 无参子命令：params 须为空对象
+fn unit(module: &'static str, action: &'static str) -> Route {
+    Route {
+        module,
+        action: Some(action),
+        variant: None,
+        params: json!({ "type": "object", "maxProperties": 0 }),
+    }
+}
+
+/// 全部已启用模块的路由（与 `invoke::assemble` 的路由表一致）
+fn routes() -> Vec<Route> {
+    let mut routes = vec![Route {
+        module: subpipeline::MODULE,
+        action: None,
+        variant: None,
+        params: subpipeline::params_schema(),
+    }];
+    #[cfg(feature = "copy")]
+    routes.push(route::<crate::copy::schema::Args>("copy", None, None));
+    #[cfg(feature = "scrub")]
+    routes.push(route::<crate::scrub::schema::Args>("scrub", None, None));
+    #[cfg(feature = "shade")]
+    routes.push(route::<crate::shade::schema::Args>("shade", None, None));
+    #[cfg(feature = "generate")]
+    {
+        use crate::generate::schema::*;
+        routes.push(route::<PathArgs>("generate", Some("path"), None));
+        routes.push(route::<UuidArgs>("generate", Some("uuid"), None));
+        routes.push(route::<CvidArgs>("generate", Some("cvid"), None));
+    }
+    #[cfg(feature = "scan")]
+    routes.push(route::<crate::scan::schema::OsArgs>(
+        "scan",
+        Some("os"),
+        None,
+    ));
+    #[cfg(feature = "capture")]
+    {
+        use crate::capture::schema::*;
+        routes.push(route::<ScreenshotArgs>("capture", Some("screenshot"), None));
+        routes.push(unit("capture", "tape"));
+        routes.push(unit("capture", "monitors"));
+        routes.push(unit("capture", "windows"));
+        routes.push(route::<CropArgs>("capture", Some("crop"), None));
+        routes.push(route::<ClipboardArgs>("capture", Some("clipboard"), None));
+    }
+    #[cfg(feature = "morph")]
+    {
+        use crate::morph::schema::*;
+        routes.extend([
+            route::<MetaArgs>("morph", Some("meta"), None),
+            route::<RenderArgs>("morph", Some("render"), None),
+            route::<ThumbnailsArgs>("morph", Some("thumbnails"), None),
+            route::<MatchArgs>("morph", Some("match"), None),
+            route::<ExportArgs>("morph", Some("export"), None),
+            route::<MergeArgs>("morph", Some("merge"), None),
+            route::<StackArgs>("morph", Some("stack"), None),
+            route::<SplitArgs>("morph", Some("split"), None),
+            route::<ImagesArgs>("morph", Some("images"), None),
+            route::<DocumentArgs>("morph", Some("document"), None),
+            route::<ReorderArgs>("morph", Some("reorder"), None),
+            route::<RotateArgs>("morph", Some("rotate"), None),
+            route::<RemoveArgs>("morph", Some("remove"), None),
+            route::<ExtractArgs>("morph", Some("extract"), None),
+        ]);
+    }
+    #[cfg(feature = "bootstrap")]
+    routes.extend([
+        unit("bootstrap", "env"),
+        unit("bootstrap", "inspect"),
+        unit("bootstrap", "force"),
+    ]);
+    #[cfg(feature = "exec")]
+    routes.push(route::<crate::exec::schema::RunArgs>(
+        "exec",
+        Some("run"),
+        None,
+    ));
+    #[cfg(feature = "engine")]
+    routes.push(route::<crate::engine::schema::SuggestionArgs>(
+        "engine",
+        Some("suggestion"),
+        None,
+    ));
+    #[cfg(feature = "compression")]
+    {
+        use crate::compression::schema::*;
+        routes.extend([
+            route::<ZipFormatArgs>("compression", Some("compress"), Some("zip")),
+            route::<TarGzFormatArgs>("compression", Some("compress"), Some("tar-gz")),
+            route::<SevenZFormatArgs>("compression", Some("compress"), Some("7z")),
+            route::<ZipDecompressArgs>("compression", Some("decompress"), Some("zip")),
+            route::<TarGzDecompressArgs>("compression", Some("decompress"), Some("tar-gz")),
+            route::<SevenZDecompressArgs>("compression", Some("decompress"), Some("7z")),
+        ]);
+    }
+    #[cfg(feature = "codec")]
+    {
+        use crate::codec::schema::*;
+        routes.extend([
+            route::<Base64Args>("codec", Some("encode"), Some("base64")),
+            route::<Base64Args>("codec", Some("decode"), Some("base64")),
+            route::<Md5Args>("codec", Some("hash"), Some("md5")),
+        ]);
+    }
+    routes
+}
+
+/// 步骤级字段名：compression 用 format，codec 用 algorithm
+fn variant_key(module: &str) -> &'static str {
+    if module == "codec" {
+        "algorithm"
+    } else {
+        "format"
+    }
+}
+
+/// pipelines.yaml 完整 Schema
+pub fn pipelines_schema() -> Value {
+    let mut root = schema::<PipelinesConfig>();
+    let routes = routes();
+    let step = step_schema(&routes, None);
+    let mut template = step.clone();
+    if let Some(template) = template.as_object_mut() {
+        template.remove("required");
+        template.remove("anyOf");
+    }
+
+    let props = &mut root["properties"];
+    props["include"] = json!({
+        "description": "合并其他 YAML / JSON 文件（相对当前文件）",
+        "anyOf": [
+            { "type": "string" },
+            { "type": "array", "items": { "type": "string" } }
+        ]
+    });
+    props["templates"] = json!({
+        "description": "步骤模板，步骤中以 `use: <名称>` 引用",
+        "type": "object",
+        "additionalProperties": template
+    });
+    props["profiles"] = json!({
+        "description": "按名称选择（--profile / COREX_PROFILE）的覆盖配置",
+        "type": "object",
+        "additionalProperties": {
+            "type": "object",
+            "properties": {
+                "variables": { "type": "object", "additionalProperties": { "type": "string" } },
+                "pipelines": {
+                    "description": "Pipeline ID → { variables, steps: { 步骤 ID → 深度合并的步骤字段 } }",
+                    "type": "object",
+                    "additionalProperties": {
+                        "type": "object",
+                        "properties": {
+                            "variables": { "type": "object", "additionalProperties": { "type": "string" } },
+                            "steps": { "type": "object", "additionalProperties": { "type": "object" } }
+                        }
+                    }
+                }
+            }
+        }
+    });
+    let pipeline = &mut props["pipelines"]["items"];
+    pipeline["properties"]["extends"] = json!({
+        "description": "继承的 Pipeline ID",
+        "type": "string"
+    });
+    for phase in ["steps", "on_failure", "finally"] {
+        pipeline["properties"][phase] = json!({ "type": "array", "items": step });
+    }
+    with_meta(root, "corex pipelines.yaml")
+}
+
+/// 单个 module 的步骤 Schema
+pub fn module_schema(name: &str) -> Result<Value> {
+    let routes = routes();
+    if !routes.iter().any(|r| r.module == name) {
+        bail!("未知或未启用的模块: {name}");
+    }
+    Ok(with_meta(
+        step_schema(&routes, Some(name)),
+        &format!("corex {name} 步骤"),
+    ))
+}
+
+/// 步骤 Schema：`module` 枚举 + 按路由约束 action / format|algorithm / params
+fn step_schema(routes: &[Route], only: Option<&str>) -> Value {
+    let routes: Vec<&Route> = routes
+        .iter()
+        .filter(|r| only.is_none_or(|m| r.module == m))
+        .collect();
+    let mut modules: Vec<&str> = routes.iter().map(|r| r.module).collect();
+    modules.dedup();
+
+    let mut rules = Vec::new();
+    for module in &modules {
+        let of_module: Vec<&&Route> = routes.iter().filter(|r| r.module == *module).collect();
+        let mut actions: Vec<&str> = of_module.iter().filter_map(|r| r.action).collect();
+        actions.dedup();
+        if !actions.is_empty() {
+            rules.push(rule(
+                json!({ "module": { "const": module } }),
+                json!({ "properties": { "action": { "enum": actions } }, "required": ["action"] }),
+            ));
+        }
+        for action in &actions {
+            let mut variants: Vec<&str> = of_module
+                .iter()
+                .filter(|r| r.action == Some(action))
+                .filter_map(|r| r.variant)
+                .collect();
+            variants.dedup();
+            if !variants.is_empty() {
+                let key = variant_key(module);
+                rules.push(rule(
+                    json!({ "module": { "const": module }, "action": { "const": action } }),
+                    json!({ "properties": { key: { "enum": variants } }, "required": [key] }),
+                ));
+            }
+        }
+        for r in of_module {
+            let mut when = json!({ "module": { "const": r.module } });
+            if let Some(action) = r.action {
+                when["action"] = json!({ "const": action });
+            }
+            if let Some(variant) = r.variant {
+                when[variant_key(r.module)] = json!({ "const": variant });
+            }
+            rules.push(rule(when, json!({ "properties": { "params": r.params } })));
+        }
+    }
+
+    let mut step = schema::<StepConfig>();
+    step["properties"]["module"]["enum"] = json!(modules);
+    step["properties"]["use"] = json!({
+        "description": "引用 templates 中的步骤模板（与本步骤字段深度合并）",
+        "type": "string"
+    });
+    step["required"] = json!(["id"]);
+    step["anyOf"] = json!([{ "required": ["module"] }, { "required": ["use"] }]);
+    step["allOf"] = Value::Array(rules);
+    step
+}
+
+/// `if` 中列出的字段须同时存在且匹配
+fn rule(properties: Value, then: Value) -> Value {
+    let required: Vec<&String> = properties
+        .as_object()
+        .map(|m| m.keys().collect())
+        .unwrap_or_default();
+    json!({
+        "if": { "properties": properties, "required": required },
+        "then": then
+    })
+}
+
+/// 内联全部子 Schema 的 Draft-07 Schema（不含 `$schema` / `title`）
+pub(crate) fn schema<T: JsonSchema>() -> Value {
+    let mut value = SchemaSettings::draft07()
+        .with(|s| {
+            s.inline_subschemas = true;
+            s.meta_schema = None;
+        })
+        .into_generator()
+        .into_root_schema_for::<T>()
+        .to_value();
+    if let Some(map) = value.as_object_mut() {
+        map.remove("title");
+    }
+    value
+}
+
+fn with_meta(mut schema: Value, title: &str) -> Value {
+    let mut meta = Map::new();
+    meta.insert(
+        "$schema".into(),
+        json!("http://json-schema.org/draft-07/schema#"),
+    );
+    meta.insert("title".into(), json!(title));
+    if let Some(map) = schema.as_object_mut() {
+        meta.append(map);
+    }
+    Value::Object(meta)
+}
+
+/// 以 clap 定义补全 Schema：缺少描述的字段取帮助文本（`#[arg(help = ...)]` 不会进入 schemars），
+/// 必填项以 clap 为准（bool 开关、带默认值的参数可省略）
+fn describe(schema: &mut Value, command: &clap::Command) {
+    let Some(props) = schema["properties"].as_object_mut() else {
+        return;
+    };
+    for arg in command.get_arguments() {
+        if let (Some(prop), Some(help)) = (props.get_mut(arg.get_id().as_str()), arg.get_help())
+            && prop.get("description").is_none()
+        {
+            prop["description"] = json!(help.to_string());
+        }
+    }
+    let required: Vec<&str> = command
+        .get_arguments()
+        .filter(|arg| arg.is_required_set())
+        .map(|arg| arg.get_id().as_str())
+        .filter(|id| props.contains_key(*id))
+        .collect();
+    schema["required"] = json!(required);
+}
+
+/// params 执行期才解析 `${...}`：非字符串字段同时允许含占位符的字符串
+fn allow_placeholders(schema: &mut Value) {
+    let Some(props) = schema["properties"].as_object_mut() else {
+        return;
+    };
+    for prop in props.values_mut() {
+        let accepts_string = match prop.get("type") {
+            Some(Value::String(t)) => t == "string",
+            Some(Value::Array(types)) => types.contains(&json!("string")),
+            _ => false,
+        };
+        if accepts_string {
+            continue;
+        }
+        let description = prop.as_object_mut().and_then(|p| p.remove("description"));
+        let placeholder = json!({ "type": "string", "pattern": "\\$\\{" });
+        *prop = json!({ "anyOf": [prop.take(), placeholder] });
+        if let Some(description) = description {
+            prop["description"] = description;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params_for<'a>(step: &'a Value, module: &str, action: Option<&str>) -> &'a Value {
+        step["allOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| {
+                let when = &r["if"]["properties"];
+                when["module"]["const"] == module
+                    && action.is_none_or(|a| when["action"]["const"] == a)
+                    && r["then"]["properties"].get("params").is_some()
+            })
+            .map(|r| &r["then"]["properties"]["params"])
+            .unwrap()
+    }
+
+    #[test]
+    fn pipelines_schema_covers_config_and_steps() {
+        let schema = pipelines_schema();
+        assert_eq!(schema["$schema"], "http://json-schema.org/draft-07/schema#");
+        assert!(schema["properties"]["version"].is_object());
+        assert!(schema["properties"]["templates"].is_object());
+
+        let pipeline = &schema["properties"]["pipelines"]["items"];
+        assert!(pipeline["properties"]["extends"].is_object());
+        let step = &pipeline["properties"]["steps"]["items"];
+        assert!(step["properties"]["when"].is_object());
+        let modules = step["properties"]["module"]["enum"].as_array().unwrap();
+        assert!(modules.contains(&json!("pipeline")));
+        assert_eq!(pipeline["properties"]["finally"]["items"], *step);
+    }
+
+    #[cfg(feature = "copy")]
+    #[test]
+    fn copy_params_follow_args() {
+        let step = module_schema("copy").unwrap();
+        let params = params_for(&step, "copy", None);
+        let required = params["required"].as_array().unwrap();
+        assert!(required.contains(&json!("from")));
+        assert!(!required.contains(&json!("empty")));
+        assert_eq!(
+            params["properties"]["from"]["description"],
+            "源路径（文件或目录）"
+        );
+        // bool 字段允许 `${var.*}` 占位符
+        assert_eq!(params["properties"]["empty"]["anyOf"][1]["type"], "string");
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn compression_params_depend_on_action_and_format() {
+        let step = module_schema("compression").unwrap();
+        let zip = step["allOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| {
+                let when = &r["if"]["properties"];
+                when["action"]["const"] == "compress" && when["format"]["const"] == "zip"
+            })
+            .unwrap();
+        assert!(zip["then"]["properties"]["params"]["properties"]["method"].is_object());
+        assert!(module_schema("unknown").is_err());
+    }
+}
@@ -0,0 +1,807 @@
+//! `corex pipeline lint`：硬校验（validate）之外的告警，代码稳定，CI 可用 `--deny` 拒绝
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Result, bail};
+use crossterm::style::Stylize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::runtime::{self, merge_variables};
+use crate::utils::Filter;
+
+use super::config::{PipelineConfig, PipelinesConfig, StepConfig, validate_config};
+use super::context::PipelineContext;
+use super::expand::{Foreach, expand_pipeline};
+use super::expr;
+use super::graph::StageGraph;
+use super::inputs;
+use super::placeholder::{self, Placeholder};
+use super::subpipeline;
+
+/// 触发间隔低于此秒数的 schedule 视为过于频繁
+#[cfg(feature = "schedule")]
+const MIN_SCHEDULE_SECS: i64 = 60;
+
+/// 视为步骤输出路径的 params 字段
+const OUTPUT_KEYS: [&str; 3] = ["to", "dest", "output"];
+
+/// 告警代码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum LintCode {
+    /// 引用未定义的 `${var.x}`
+    #[serde(rename = "L001")]
+    UndefinedVariable,
+    /// `${steps.y.*}` 中 y 不是当前步骤的上游，或 `${var.x}` 的导出步骤不是当前步骤的上游
+    #[serde(rename = "L002")]
+    NonAncestorStep,
+    /// 定义后未被引用的变量
+    #[serde(rename = "L003")]
+    UnusedVariable,
+    /// schedule 触发过于频繁
+    #[serde(rename = "L004")]
+    FrequentSchedule,
+    /// watch 路径包含步骤输出目录（触发循环）
+    #[serde(rename = "L005")]
+    WatchOutputOverlap,
+    /// params 中明文 password
+    #[serde(rename = "L006")]
+    PlaintextPassword,
+    /// 旧式（非表达式）when
+    #[serde(rename = "L007")]
+    LegacyWhen,
+}
+
+impl LintCode {
+    pub const ALL: [LintCode; 7] = [
+        LintCode::UndefinedVariable,
+        LintCode::NonAncestorStep,
+        LintCode::UnusedVariable,
+        LintCode::FrequentSchedule,
+        LintCode::WatchOutputOverlap,
+        LintCode::PlaintextPassword,
+        LintCode::LegacyWhen,
+    ];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            LintCode::UndefinedVariable => "L001",
+            LintCode::NonAncestorStep => "L002",
+            LintCode::UnusedVariable => "L003",
+            LintCode::FrequentSchedule => "L004",
+            LintCode::WatchOutputOverlap => "L005",
+            LintCode::PlaintextPassword => "L006",
+            LintCode::LegacyWhen => "L007",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LintCode::UndefinedVariable => "undefined-variable",
+            LintCode::NonAncestorStep => "non-ancestor-step",
+            LintCode::UnusedVariable => "unused-variable",
+            LintCode::FrequentSchedule => "frequent-schedule",
+            LintCode::WatchOutputOverlap => "watch-output-overlap",
+            LintCode::PlaintextPassword => "plaintext-password",
+            LintCode::LegacyWhen => "legacy-when",
+        }
+    }
+
+    /// 按代码（`L001`）或名称（`undefined-variable`）查找
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.code().eq_ignore_ascii_case(s) || c.name() == s)
+    }
+}
+
+/// 单条告警；`pipeline` 为空表示顶层 variables
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintWarning {
+    pub code: LintCode,
+    pub name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pipeline: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<String>,
+    pub message: String,
+}
+
+/// lint 结构化结果（JSON 输出）
+#[derive(Debug, Serialize)]
+pub struct LintReport {
+    /// 无被 `--deny` 拒绝的告警
+    pub ok: bool,
+    pub pipeline_count: usize,
+    pub warnings: Vec<LintWarning>,
+}
+
+/// `--deny` 取值：`warnings`（全部）或告警代码 / 名称
+#[derive(Debug, Default)]
+struct Deny {
+    all: bool,
+    codes: HashSet<&'static str>,
+}
+
+impl Deny {
+    fn parse(values: &[String]) -> Result<Self> {
+        let mut deny = Deny::default();
+        for value in values {
+            if value == "warnings" {
+                deny.all = true;
+            } else if let Some(code) = LintCode::parse(value) {
+                deny.codes.insert(code.code());
+            } else {
+                bail!("未知的 lint 代码: {value}（可用 warnings / L001…L007 / 告警名称）");
+            }
+        }
+        Ok(deny)
+    }
+
+    fn denies(&self, warning: &LintWarning) -> bool {
+        self.all || self.codes.contains(warning.code.code())
+    }
+}
+
+/// `corex pipeline lint` 入口；`config` 为未合并 `-D` / `COREX_VAR_*` 的原始配置
+pub fn run(
+    config: &PipelinesConfig,
+    pipeline: Option<&str>,
+    deny: &[String],
+    define: &[(String, String)],
+) -> Result<()> {
+    let deny = Deny::parse(deny)?;
+    let overrides = merge_variables(HashMap::new(), define);
+    let mut merged = config.clone();
+    merged.variables = merge_variables(merged.variables, define);
+    validate_config(&merged)?;
+    if let Some(id) = pipeline
+        && !config.pipelines.iter().any(|p| p.id == id)
+    {
+        bail!("未找到 Pipeline: {id}");
+    }
+
+    let warnings: Vec<LintWarning> = lint(config, &overrides)
+        .into_iter()
+        .filter(|w| pipeline.is_none() || w.pipeline.as_deref() == pipeline)
+        .collect();
+    let denied = warnings.iter().filter(|w| deny.denies(w)).count();
+    let report = LintReport {
+        ok: denied == 0,
+        pipeline_count: config.pipelines.len(),
+        warnings,
+    };
+
+    if runtime::is_json_output() {
+        runtime::state().emitter.json(&report)?;
+    } else if !runtime::is_quiet() {
+        print(&report);
+    }
+    if denied > 0 {
+        bail!("lint 未通过：{denied} 条告警被 --deny 拒绝");
+    }
+    Ok(())
+}
+
+fn print(report: &LintReport) {
+    if report.warnings.is_empty() {
+        println!(
+            "  {} lint 通过，未发现问题 ({} 条 pipeline)",
+            "✓".green().bold(),
+            report.pipeline_count
+        );
+        return;
+    }
+    for w in &report.warnings {
+        let location = match (&w.pipeline, &w.step) {
+            (Some(p), Some(s)) => format!("{p} / {s}"),
+            (Some(p), None) => p.clone(),
+            (None, _) => "variables".to_string(),
+        };
+        println!(
+            "  {} {} {} [{}] {}",
+            "⚠".yellow(),
+            w.code.code().yellow().bold(),
+            w.name.dim(),
+            location,
+            w.message
+        );
+    }
+    println!("\n  共 {} 条告警", report.warnings.len());
+}
+
+/// 引用位置：所属 Pipeline（None 为顶层 variables）与步骤
+struct Reference {
+    pipeline: Option<String>,
+    step: Option<String>,
+    path: String,
+    /// 带 `:-` 缺省值，未定义时不告警
+    defaulted: bool,
+}
+
+/// 对配置执行全部 lint 规则；`overrides` 为外部提供的变量（`-D` / `COREX_VAR_*`）
+pub fn lint(config: &PipelinesConfig, overrides: &HashMap<String, String>) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    let mut refs = Vec::new();
+    for value in config.variables.values() {
+        collect_text(value, None, None, &mut refs);
+    }
+    for pipeline in &config.pipelines {
+        collect_pipeline(pipeline, &mut refs);
+    }
+
+    check_variables(config, overrides, &refs, &mut warnings);
+    for pipeline in &config.pipelines {
+        let mut variables = pipeline.scoped_variables(&config.variables);
+        variables.extend(overrides.clone());
+        inputs::fill_defaults(pipeline, &mut variables);
+        let ctx = PipelineContext::with_variables(variables);
+        let Ok(expanded) = expand_pipeline(pipeline, &ctx) else {
+            continue;
+        };
+        check_step_refs(&expanded, &mut warnings);
+        #[cfg(feature = "schedule")]
+        check_schedule(pipeline, &mut warnings);
+        check_watch_overlap(&expanded, &ctx, &mut warnings);
+        check_passwords(pipeline, &mut warnings);
+        check_legacy_when(pipeline, &mut warnings);
+    }
+
+    let mut unique = Vec::new();
+    for w in warnings {
+        if !unique.contains(&w) {
+            unique.push(w);
+        }
+    }
+    unique
+}
+
+fn warn(
+    code: LintCode,
+    pipeline: Option<&str>,
+    step: Option<&str>,
+    message: String,
+) -> LintWarning {
+    LintWarning {
+        code,
+        name: code.name(),
+        pipeline: pipeline.map(str::to_string),
+        step: step.map(str::to_string),
+        message,
+    }
+}
+
+fn collect_pipeline(pipeline: &PipelineConfig, refs: &mut Vec<Reference>) {
+    let id = Some(pipeline.id.as_str());
+    for value in pipeline.variables.values() {
+        collect_text(value, id, None, refs);
+    }
+    if let Some(watch) = &pipeline.watch {
+        for path in &watch.paths {
+            collect_text(path, id, None, refs);
+        }
+    }
+    // 运行结果与步骤导出值同样在 Pipeline 作用域内解析
+    for value in pipeline.outputs.values() {
+        collect_text(value, id, None, refs);
+    }
+    for step in all_steps(pipeline) {
+        collect_step(step, id, refs);
+        for value in step.exports.values() {
+            collect_text(value, id, Some(&step.id), refs);
+        }
+    }
+}
+
+fn collect_step(step: &StepConfig, pipeline: Option<&str>, refs: &mut Vec<Reference>) {
+    let at = Some(step.id.as_str());
+    let mut texts: Vec<&str> = Vec::new();
+    collect_strings(&step.params, &mut texts);
+    texts.extend(step.inputs.iter().map(String::as_str));
+    texts.extend(step.outputs.iter().map(String::as_str));
+    if let Some(Foreach::Expr(expr)) = &step.foreach {
+        texts.push(expr);
+    }
+    for values in step.matrix.iter().flat_map(|m| m.values()) {
+        if let Foreach::Expr(expr) = values {
+            texts.push(expr);
+        }
+    }
+    for text in texts {
+        collect_text(text, pipeline, at, refs);
+    }
+    if let Some(when) = &step.when
+        && let Ok(expr) = expr::parse_when(when)
+    {
+        refs.extend(expr.references().into_iter().map(|path| Reference {
+            pipeline: pipeline.map(str::to_string),
+            step: at.map(str::to_string),
+            path,
+            defaulted: false,
+        }));
+    }
+}
+
+fn collect_text(text: &str, pipeline: Option<&str>, step: Option<&str>, refs: &mut Vec<Reference>) {
+    refs.extend(placeholder::scan(text).iter().map(|raw| {
+        let parsed = Placeholder::parse(raw);
+        Reference {
+            pipeline: pipeline.map(str::to_string),
+            step: step.map(str::to_string),
+            path: parsed.path.to_string(),
+            defaulted: parsed.default.is_some(),
+        }
+    }));
+}
+
+fn collect_strings<'a>(value: &'a Value, out: &mut Vec<&'a str>) {
+    match value {
+        Value::String(s) => out.push(s),
+        Value::Array(arr) => arr.iter().for_each(|v| collect_strings(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_strings(v, out)),
+        _ => {}
+    }
+}
+
+fn all_steps(pipeline: &PipelineConfig) -> impl Iterator<Item = &StepConfig> {
+    pipeline
+        .steps
+        .iter()
+        .chain(&pipeline.on_failure)
+        .chain(&pipeline.finally)
+}
+
+/// 某步骤 `exports` 写入的变量
+fn exported(pipeline: &PipelineConfig, name: &str) -> bool {
+    all_steps(pipeline).any(|s| s.exports.contains_key(name))
+}
+
+fn var_name(path: &str) -> Option<&str> {
+    path.strip_prefix("var.")
+}
+
+/// L001 / L003：未定义与未使用的变量
+fn check_variables(
+    config: &PipelinesConfig,
+    overrides: &HashMap<String, String>,
+    refs: &[Reference],
+    warnings: &mut Vec<LintWarning>,
+) {
+    // 子 Pipeline 还可使用调用方作用域与 params.variables 传入的变量
+    let mut inherited: HashMap<&str, HashSet<String>> = HashMap::new();
+    for caller in &config.pipelines {
+        for step in all_steps(caller).filter(|s| s.module == subpipeline::MODULE) {
+            let Some(target) = step.params.get("pipeline").and_then(Value::as_str) else {
+                continue;
+            };
+            let names = inherited.entry(target).or_default();
+            names.extend(caller.variables.keys().cloned());
+            for key in ["variables", "inputs"] {
+                if let Some(Value::Object(passed)) = step.params.get(key) {
+                    names.extend(passed.keys().cloned());
+                }
+            }
+        }
+    }
+    let defined = |pipeline: Option<&str>, name: &str| {
+        config.variables.contains_key(name)
+            || overrides.contains_key(name)
+            || match pipeline {
+                Some(id) => {
+                    config.pipelines.iter().any(|p| {
+                        p.id == id
+                            && (p.variables.contains_key(name)
+                                || p.inputs.contains_key(name)
+                                || exported(p, name))
+                    }) || inherited.get(id).is_some_and(|names| names.contains(name))
+                }
+                // 顶层变量值在各 Pipeline 作用域内解析
+                None => config.pipelines.iter().any(|p| {
+                    p.variables.contains_key(name)
+                        || p.inputs.contains_key(name)
+                        || exported(p, name)
+                }),
+            }
+    };
+    for r in refs {
+        if let Some(name) = var_name(&r.path)
+            && !r.defaulted
+            && !defined(r.pipeline.as_deref(), name)
+        {
+            warnings.push(warn(
+                LintCode::UndefinedVariable,
+                r.pipeline.as_deref(),
+                r.step.as_deref(),
+                format!("引用了未定义的变量 ${{var.{name}}}"),
+            ));
+        }
+    }
+
+    let used = |pipeline: Option<&str>, name: &str| {
+        refs.iter().any(|r| {
+            var_name(&r.path) == Some(name)
+                && (pipeline.is_none() || r.pipeline.is_none() || r.pipeline.as_deref() == pipeline)
+        })
+    };
+    let mut names: Vec<&String> = config.variables.keys().collect();
+    names.sort();
+    for name in names {
+        if !used(None, name) {
+            warnings.push(warn(
+                LintCode::UnusedVariable,
+                None,
+                None,
+                format!("变量 '{name}' 未被引用"),
+            ));
+        }
+    }
+    for pipeline in &config.pipelines {
+        let mut names: Vec<&String> = pipeline.variables.keys().collect();
+        names.sort();
+        for name in names {
+            if !used(Some(&pipeline.id), name) {
+                warnings.push(warn(
+                    LintCode::UnusedVariable,
+                    Some(&pipeline.id),
+                    None,
+                    format!("Pipeline 变量 '{name}' 未被引用"),
+                ));
+            }
+        }
+    }
+}
+
+/// L002：`${steps.y.*}` 与步骤 `exports` 导出的 `${var.x}` 须来自上游步骤（on_failure / finally 还可引用任意主步骤）
+fn check_step_refs(expanded: &PipelineConfig, warnings: &mut Vec<LintWarning>) {
+    let main: HashSet<&str> = expanded
+        .steps
+        .iter()
+        .flat_map(|s| [Some(s.id.as_str()), s.group.as_deref()])
+        .flatten()
+        .collect();
+    let phases = [
+        (expanded.clone(), HashSet::new()),
+        (expanded.handler(&expanded.on_failure), main.clone()),
+        (expanded.handler(&expanded.finally), main),
+    ];
+    for (phase, visible) in &phases {
+        let Ok(graph) = StageGraph::from_pipeline(phase) else {
+            continue;
+        };
+        for step in &phase.steps {
+            let upstream = ancestors(&graph, phase, &step.id);
+            let label = step.group.as_deref().unwrap_or(&step.id);
+            let mut refs = Vec::new();
+            collect_step(step, None, &mut refs);
+            for r in refs {
+                if let Some(name) = var_name(&r.path) {
+                    check_export_ref(expanded, phase, step, name, &upstream, visible, warnings);
+                    continue;
+                }
+                let Some(target) = r
+                    .path
+                    .strip_prefix("steps.")
+                    .and_then(|p| p.split('.').next())
+                else {
+                    continue;
+                };
+                if !upstream.contains(target) && !visible.contains(target) {
+                    warnings.push(warn(
+                        LintCode::NonAncestorStep,
+                        Some(&expanded.id),
+                        Some(label),
+                        format!("引用的步骤 '{target}' 不是其上游，执行时可能尚未完成"),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// 引用的变量由某步骤导出时，至少一个导出步骤须是上游，否则同层步骤可能在导出前读取
+fn check_export_ref(
+    expanded: &PipelineConfig,
+    phase: &PipelineConfig,
+    step: &StepConfig,
+    name: &str,
+    upstream: &HashSet<String>,
+    visible: &HashSet<&str>,
+    warnings: &mut Vec<LintWarning>,
+) {
+    let exporters: Vec<&str> = phase
+        .steps
+        .iter()
+        .chain(&expanded.steps)
+        .filter(|s| s.exports.contains_key(name))
+        .map(|s| s.group.as_deref().unwrap_or(&s.id))
+        .collect();
+    let Some(exporter) = exporters.first() else {
+        return;
+    };
+    if exporters
+        .iter()
+        .any(|e| upstream.contains(*e) || visible.contains(e))
+    {
+        return;
+    }
+    warnings.push(warn(
+        LintCode::NonAncestorStep,
+        Some(&expanded.id),
+        Some(step.group.as_deref().unwrap_or(&step.id)),
+        format!(
+            "变量 ${{var.{name}}} 由步骤 '{exporter}' 导出，但该步骤不是其上游，执行时可能尚未写入"
+        ),
+    ));
+}
+
+/// 全部上游步骤 ID 及其分组 ID
+fn ancestors(graph: &StageGraph, pipeline: &PipelineConfig, id: &str) -> HashSet<String> {
+    let mut seen = HashSet::new();
+    let mut stack = graph.dependencies(id);
+    while let Some(next) = stack.pop() {
+        if seen.insert(next.clone()) {
+            stack.extend(graph.dependencies(&next));
+        }
+    }
+    let groups: Vec<String> = pipeline
+        .steps
+        .iter()
+        .filter(|s| seen.contains(&s.id))
+        .filter_map(|s| s.group.clone())
+        .collect();
+    seen.extend(groups);
+    seen
+}
+
+/// L004：相邻两次触发间隔低于 [`MIN_SCHEDULE_SECS`]；未启用 `schedule` 时不检查
+#[cfg(feature = "schedule")]
+fn check_schedule(pipeline: &PipelineConfig, warnings: &mut Vec<LintWarning>) {
+    let Some(expr) = &pipeline.schedule else {
+        return;
+    };
+    let Ok(schedule) = expr.parse::<cron::Schedule>() else {
+        return;
+    };
+    let times: Vec<_> = schedule.upcoming(chrono::Utc).take(10).collect();
+    let Some(min) = times.windows(2).map(|w| (w[1] - w[0]).num_seconds()).min() else {
+        return;
+    };
+    if min < MIN_SCHEDULE_SECS {
+        warnings.push(warn(
+            LintCode::FrequentSchedule,
+            Some(&pipeline.id),
+            None,
+            format!(
+                "schedule \"{expr}\" 最短每 {min} 秒触发一次（建议不低于 {MIN_SCHEDULE_SECS} 秒）"
+            ),
+        ));
+    }
+}
+
+/// L005：步骤输出落在 watch 路径内且未被 watch 过滤，执行后会再次触发
+fn check_watch_overlap(
+    expanded: &PipelineConfig,
+    ctx: &PipelineContext,
+    warnings: &mut Vec<LintWarning>,
+) {
+    let Some(watch) = &expanded.watch else {
+        return;
+    };
+    let filter = Filter::new(&watch.includes, &watch.excludes);
+    let roots: Vec<PathBuf> = watch
+        .paths
+        .iter()
+        .map(|p| normalize(&ctx.parse(p)))
+        .collect();
+    for step in all_steps(expanded) {
+        let params = ctx.parse_value(&step.params);
+        let mut outputs: Vec<PathBuf> = OUTPUT_KEYS
+            .iter()
+            .filter_map(|key| params.get(*key)?.as_str())
+            .map(normalize)
+            .collect();
+        outputs.extend(step.outputs.iter().map(|o| glob_base(&ctx.parse(o))));
+        for output in outputs {
+            if output.as_os_str().is_empty() || output.to_string_lossy().contains("${") {
+                continue;
+            }
+            let Some(root) = roots.iter().find(|root| output.starts_with(root)) else {
+                continue;
+            };
+            if filter.is_filtered(&output) {
+                continue;
+            }
+            warnings.push(warn(
+                LintCode::WatchOutputOverlap,
+                Some(&expanded.id),
+                Some(step.group.as_deref().unwrap_or(&step.id)),
+                format!(
+                    "输出 '{}' 位于 watch 路径 '{}' 内，执行后会再次触发（可加入 watch.excludes）",
+                    output.display(),
+                    root.display()
+                ),
+            ));
+        }
+    }
+}
+
+/// 去掉 `.` 分量，便于按前缀比较
+fn normalize(path: &str) -> PathBuf {
+    Path::new(path)
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+/// glob 中首个通配分量之前的目录
+fn glob_base(pattern: &str) -> PathBuf {
+    normalize(pattern)
+        .components()
+        .take_while(|c| !c.as_os_str().to_string_lossy().contains(['*', '?', '[']))
+        .collect()
+}
+
+/// L006：params 中的明文 password（应改用 `${secret.*}` / `${env.*}`）
+fn check_passwords(pipeline: &PipelineConfig, warnings: &mut Vec<LintWarning>) {
+    for step in all_steps(pipeline) {
+        if has_plaintext_password(&step.params) {
+            warnings.push(warn(
+                LintCode::PlaintextPassword,
+                Some(&pipeline.id),
+                Some(&step.id),
+                "params.password 为明文，建议改用 ${secret.NAME} 或 ${env.NAME}".into(),
+            ));
+        }
+    }
+}
+
+fn has_plaintext_password(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => map.iter().any(|(k, v)| {
+            let literal = v
+                .as_str()
+                .is_some_and(|s| !s.is_empty() && !s.contains("${"));
+            (k.eq_ignore_ascii_case("password") && literal) || has_plaintext_password(v)
+        }),
+        Value::Array(arr) => arr.iter().any(has_plaintext_password),
+        _ => false,
+    }
+}
+
+/// L007：旧式 when（按整串插值取真值），应改写为表达式
+fn check_legacy_when(pipeline: &PipelineConfig, warnings: &mut Vec<LintWarning>) {
+    for step in all_steps(pipeline) {
+        if let Some(ref when) = step.when
+            && expr::is_legacy(when)
+        {
+            warnings.push(warn(
+                LintCode::LegacyWhen,
+                Some(&pipeline.id),
+                Some(&step.id),
+                format!("when '{when}' 不是表达式，按旧式整串真值判断（已弃用）"),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(yaml: &str) -> Vec<(&'static str, Option<String>, String)> {
+        let config: PipelinesConfig = serde_yml::from_str(yaml).unwrap();
+        lint(&config, &HashMap::new())
+            .into_iter()
+            .map(|w| (w.code.code(), w.step, w.message))
+            .collect()
+    }
+
+    #[test]
+    fn reports_each_rule_with_stable_codes() {
+        let warnings = codes(
+            r#"
+version: 3
+variables: { out: dist, unused: x, label: y }
+pipelines:
+  - id: dev
+    schedule: "*/10 * * * * *"
+    watch: { paths: ["./src"] }
+    outputs: { name: "${var.label}", typo: "${var.lable}" }
+    steps:
+      - id: build
+        module: copy
+        params: { from: "${var.missing}", to: "./src/out" }
+      - id: zip
+        module: compression
+        action: compress
+        format: zip
+        params: { from: "${steps.build.artifact.path}", to: "${var.out}/a.zip", password: hunter2 }
+      - id: early
+        module: copy
+        depends_on: [build]
+        when: "${var.out} ready"
+        params: { from: "${steps.zip.artifact.path}", to: "${var.out}" }
+    finally:
+      - id: tidy
+        module: copy
+        when: steps.zip.status == success
+        params: { from: "${var.out}", to: "${secret.DEST}" }
+"#,
+        );
+        let has = |code: &str, step: Option<&str>| {
+            warnings
+                .iter()
+                .any(|(c, s, _)| *c == code && s.as_deref() == step)
+        };
+        assert!(has("L001", Some("build")), "{warnings:?}");
+        assert!(has("L002", Some("early")), "{warnings:?}");
+        assert!(!has("L002", Some("zip")), "{warnings:?}");
+        assert!(!has("L002", Some("tidy")), "{warnings:?}");
+        assert!(has("L003", None), "{warnings:?}");
+        assert!(
+            warnings
+                .iter()
+                .any(|(c, s, m)| *c == "L001" && s.is_none() && m.contains("var.lable")),
+            "{warnings:?}"
+        );
+        assert!(!warnings.iter().any(|(_, _, m)| m.contains("'label'")));
+        #[cfg(feature = "schedule")]
+        assert!(has("L004", None), "{warnings:?}");
+        assert!(has("L005", Some("build")), "{warnings:?}");
+        assert!(has("L006", Some("zip")), "{warnings:?}");
+        assert!(has("L007", Some("early")), "{warnings:?}");
+        assert!(!has("L007", Some("tidy")), "{warnings:?}");
+        assert_eq!(warnings.iter().filter(|w| w.0 == "L003").count(), 1);
+    }
+
+    #[test]
+    fn exported_variables_must_come_from_upstream() {
+        let warnings = codes(
+            r#"
+version: 3
+pipelines:
+  - id: rel
+    steps:
+      - id: init
+        module: copy
+        params: { from: ./a, to: ./b }
+      - id: probe
+        module: copy
+        depends_on: [init]
+        params: { from: ./a, to: ./b }
+        exports: { version: "${steps.probe.artifact.path}" }
+      - id: pack
+        module: copy
+        depends_on: [probe]
+        params: { from: ./b, to: "./dist/${var.version}" }
+      - id: sibling
+        module: copy
+        depends_on: [init]
+        params: { from: ./b, to: "./out/${var.version}" }
+    finally:
+      - id: notify
+        module: copy
+        params: { from: ./b, to: "./log/${var.version}" }
+"#,
+        );
+        let l002: Vec<_> = warnings
+            .iter()
+            .filter(|(c, _, _)| *c == "L002")
+            .map(|(_, s, m)| (s.as_deref(), m.as_str()))
+            .collect();
+        assert_eq!(l002.len(), 1, "{warnings:?}");
+        assert_eq!(l002[0].0, Some("sibling"));
+        assert!(l002[0].1.contains("'probe'"));
+        assert!(!warnings.iter().any(|(c, _, _)| *c == "L001"));
+    }
+
+    #[test]
+    fn deny_accepts_warnings_codes_and_names() {
+        let deny = Deny::parse(&["L005".into(), "plaintext-password".into()]).unwrap();
+        assert!(!deny.all);
+        assert!(deny.codes.contains("L005") && deny.codes.contains("L006"));
+        assert!(Deny::parse(&["warnings".into()]).unwrap().all);
+        assert!(Deny::parse(&["L999".into()]).is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! 跨进程 Pipeline 锁：对 `~/.corex/locks/<pipeline>.lock` 加操作系统建议锁（unix flock / Windows LockFileEx），
+//! 持有进程退出（含崩溃、被强杀）时由系统释放。锁文件常驻不删除，内容仅记录持有进程 PID 供提示。
+//!
+//! 手动执行、watch、cron 均经此加锁，同一 Pipeline 同时只有一次运行。
+
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use crossterm::style::Stylize;
+use serde::{Deserialize, Serialize};
+
+use crate::runtime;
+use crate::utils::cancel;
+
+use super::config::{ConcurrencyPolicy, PipelineConfig};
+use super::report::iso_now;
+use super::state::encode_id;
+
+/// 等待锁时的轮询间隔
+const POLL: Duration = Duration::from_millis(500);
+
+/// 锁文件内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockInfo {
+    pub pid: u32,
+    pub started_at: String,
+}
+
+/// 持有中的锁；drop 时关闭文件即释放，锁文件保留
+#[derive(Debug)]
+pub struct PipelineLock {
+    _file: File,
+}
+
+/// 获取结果
+#[derive(Debug)]
+pub enum TryLock {
+    Acquired(PipelineLock),
+    /// 被其他运行持有（wait / queue 下表示等待期间收到 Ctrl+C）
+    Held(LockInfo),
+}
+
+/// 锁目录：`COREX_LOCK_DIR` 或 `~/.corex/locks`
+pub fn lock_dir() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("COREX_LOCK_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    Ok(dirs::home_dir()
+        .context("无法获取用户目录")?
+        .join(".corex")
+        .join("locks"))
+}
+
+/// 按策略获取锁：skip 仅尝试一次；wait / queue 等待持有者结束
+pub fn acquire(pipeline_id: &str, policy: ConcurrencyPolicy) -> Result<TryLock> {
+    let dir = lock_dir()?;
+    let mut notified = false;
+    loop {
+        let info = match try_lock_in(&dir, pipeline_id)? {
+            TryLock::Held(info) => info,
+            acquired => return Ok(acquired),
+        };
+        if policy.is_skip() || cancel::shutdown().is_cancelled() {
+            return Ok(TryLock::Held(info));
+        }
+        if !notified && !runtime::is_json_output() {
+            eprintln!(
+                "  {} Pipeline '{}' 正在被进程 {} 执行，等待其结束...",
+                "⏳".yellow(),
+                pipeline_id,
+                info.pid
+            );
+            notified = true;
+        }
+        std::thread::sleep(POLL);
+    }
+}
+
+/// 手动执行用：按 Pipeline 的 concurrency_policy 获取锁，未能获取时报错
+pub fn hold(pipeline: &PipelineConfig) -> Result<PipelineLock> {
+    match acquire(&pipeline.id, pipeline.concurrency_policy)? {
+        TryLock::Acquired(lock) => Ok(lock),
+        TryLock::Held(_) if cancel::shutdown().is_cancelled() => bail!("Pipeline 已取消"),
+        TryLock::Held(info) => bail!(
+            "Pipeline '{}' 正在被进程 {} 执行（{} 起）\n提示: 设置 concurrency_policy: wait 可等待其结束",
+            pipeline.id,
+            info.pid,
+            info.started_at
+        ),
+    }
+}
+
+/// 尝试获取一次；锁文件名按 [`encode_id`] 编码，不同 ID 不会共用同一把锁
+fn try_lock_in(dir: &Path, pipeline_id: &str) -> Result<TryLock> {
+    fs::create_dir_all(dir).with_context(|| format!("创建锁目录失败: {}", dir.display()))?;
+    let path = dir.join(format!("{}.lock", encode_id(pipeline_id)));
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)
+        .with_context(|| format!("打开锁文件失败: {}", path.display()))?;
+    match file.try_lock() {
+        Ok(()) => {
+            let info = LockInfo {
+                pid: std::process::id(),
+                started_at: iso_now(),
+            };
+            file.set_len(0)
+                .and_then(|()| file.write_all(serde_json::to_string(&info)?.as_bytes()))
+                .with_context(|| format!("写入锁文件失败: {}", path.display()))?;
+            Ok(TryLock::Acquired(PipelineLock { _file: file }))
+        }
+        Err(TryLockError::WouldBlock) => Ok(TryLock::Held(holder(&path))),
+        Err(TryLockError::Error(e)) => {
+            Err(e).with_context(|| format!("获取锁失败: {}", path.display()))
+        }
+    }
+}
+
+/// 锁文件记录的持有者；持有者尚未写入或平台禁止读取加锁文件时 PID 为 0
+fn holder(path: &Path) -> LockInfo {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_else(|| LockInfo {
+            pid: 0,
+            started_at: iso_now(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_is_exclusive_and_released_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock = match try_lock_in(dir.path(), "build/h5").unwrap() {
+            TryLock::Acquired(lock) => lock,
+            TryLock::Held(_) => panic!("首次应获取成功"),
+        };
+        assert!(dir.path().join("build%2Fh5.lock").exists());
+        match try_lock_in(dir.path(), "build/h5").unwrap() {
+            TryLock::Held(info) => assert_eq!(info.pid, std::process::id()),
+            TryLock::Acquired(_) => panic!("持有期间不应再次获取"),
+        }
+        assert!(matches!(
+            try_lock_in(dir.path(), "build_h5").unwrap(),
+            TryLock::Acquired(_)
+        ));
+        drop(lock);
+        assert!(dir.path().join("build%2Fh5.lock").exists());
+        assert!(matches!(
+            try_lock_in(dir.path(), "build/h5").unwrap(),
+            TryLock::Acquired(_)
+        ));
+    }
+}
@@ -1,12 +1,30 @@
+pub mod cache;
+pub mod compose;
 pub mod config;
 pub mod context;
+pub mod diagram;
+pub mod events;
+pub mod expand;
+pub mod expr;
 pub mod graph;
 pub mod guard;
+pub mod history;
+pub mod inputs;
+pub mod interrupt;
+pub mod jsonschema;
+pub mod lock;
+pub mod lint;
 pub mod orchestrator;
+pub mod placeholder;
+pub mod plan;
 pub mod report;
+pub mod report_format;
+pub mod retry;
 pub mod runner;
+pub mod state;
 pub mod step_params;
 pub mod stream;
+pub mod subpipeline;
 pub mod trigger;
 
 pub use runner::run;
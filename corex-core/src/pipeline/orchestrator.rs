@@ -1,27 +1,228 @@
-use std::time::Instant;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use crossterm::style::Stylize;
 use tokio::task::JoinSet;
-use tracing::info_span;
+use tracing::{Instrument, info_span};
 
 use crate::invoke::Artifact;
 use crate::runtime;
+use crate::utils::cancel::{self, CancelToken};
+use crate::utils::observe::{self, Progress};
+use crate::utils::redact;
 
+use super::cache::StepCache;
 use super::config::{PipelineConfig, StepConfig};
 use super::context::PipelineContext;
+use super::events::{self, Event};
+use super::expand::expand_pipeline;
 use super::graph::StageGraph;
-use super::report::{RunReport, RunStatus, StepReport, StepStatus};
-use super::stream::{run_batch_stage, run_path_stream_blocking};
+use super::inputs;
+use super::report::{Attempt, RunReport, RunStatus, StepPhase, StepReport, StepStatus};
+use super::stream::{plan_chains, run_batch_stage, run_path_stream_blocking, run_stream_chain};
+use super::subpipeline;
 
-type StepOutcome = (Artifact, u64, u64, StepStatus, Option<String>);
+/// 单个步骤的执行结果
+#[derive(Debug, Clone)]
+struct StepOutcome {
+    artifact: Artifact,
+    items: u64,
+    duration_ms: u64,
+    status: StepStatus,
+    error: Option<String>,
+    /// 发生重试时的各次尝试
+    attempts: Vec<Attempt>,
+    /// `module: pipeline` 步骤的子 Pipeline 报告
+    child: Option<RunReport>,
+}
+
+impl StepOutcome {
+    fn new(
+        artifact: Artifact,
+        items: u64,
+        duration_ms: u64,
+        status: StepStatus,
+        error: Option<String>,
+    ) -> Self {
+        Self {
+            artifact,
+            items,
+            duration_ms,
+            status,
+            error,
+            attempts: Vec::new(),
+            child: None,
+        }
+    }
+}
+
+/// 运行中的调度单元：单元 ID 与其各步骤结果
+type UnitTasks = JoinSet<(String, Result<Vec<(StepConfig, StepOutcome)>>)>;
+
+/// 超时 / 取消后等待步骤响应取消的最长时间
+const CANCEL_GRACE: Duration = Duration::from_secs(5);
+
+/// 轮询取消令牌的间隔
+const CANCEL_POLL: Duration = Duration::from_millis(100);
+
+/// 事件流中同一步骤 `step_progress` 的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
 
-/// 执行一条 Pipeline（DAG 分层 + when/retry）；失败时仍返回完整 RunReport
+/// Pipeline 总超时截止点，及整次运行的取消令牌（步骤令牌为其子令牌）
+#[derive(Debug, Clone)]
+struct Deadline {
+    /// 运行开始时刻（报告中步骤启动时刻的基准）
+    started: Instant,
+    at: Option<Instant>,
+    total_ms: u64,
+    cancel: CancelToken,
+}
+
+impl Deadline {
+    fn new(started: Instant, timeout_ms: Option<u64>, cancel: CancelToken) -> Self {
+        Self {
+            started,
+            at: timeout_ms.map(|ms| started + Duration::from_millis(ms)),
+            total_ms: timeout_ms.unwrap_or(0),
+            cancel,
+        }
+    }
+
+    fn expired(&self) -> bool {
+        self.at.is_some_and(|at| Instant::now() >= at) || self.cancel.is_cancelled()
+    }
+
+    fn message(&self) -> String {
+        if self.cancel.is_cancelled() {
+            return "Pipeline 已取消".into();
+        }
+        format!("Pipeline 总超时（{} ms）", self.total_ms)
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// 到达截止点或被取消时步骤应记录的状态
+    fn stop_status(&self) -> StepStatus {
+        if self.cancelled() {
+            StepStatus::Cancelled
+        } else {
+            StepStatus::TimedOut
+        }
+    }
+
+    /// 单个步骤 / stream 链的取消令牌
+    fn token(&self) -> CancelToken {
+        self.cancel.child()
+    }
+
+    /// 调度单元使用的截止点：取消令牌为运行令牌的子令牌，可单独中止该单元
+    fn unit(&self) -> Self {
+        Self {
+            cancel: self.token(),
+            ..self.clone()
+        }
+    }
+
+    /// 单次执行的时限：步骤 timeout_ms 与 Pipeline 剩余时间取较小者，附超时说明
+    fn limit(&self, timeout_ms: Option<u64>) -> Option<(Duration, String)> {
+        let step = timeout_ms.map(|ms| {
+            (
+                Duration::from_millis(ms),
+                format!("步骤执行超时（{ms} ms）"),
+            )
+        });
+        let remaining = self
+            .at
+            .map(|at| (at.saturating_duration_since(Instant::now()), self.message()));
+        match (step, remaining) {
+            (Some(s), Some(p)) => Some(if p.0 < s.0 { p } else { s }),
+            (s, p) => s.or(p),
+        }
+    }
+}
+
+/// 执行一条 Pipeline（DAG 分层 + when/retry，失败后 on_failure，最后 finally）；失败时仍返回完整 RunReport
 pub fn run_pipeline(pipeline: &PipelineConfig, ctx: &mut PipelineContext) -> Result<RunReport> {
-    let started = Instant::now();
+    execute_pipeline(pipeline, ctx, RunReport::new(&pipeline.id))
+}
+
+/// 同 [`run_pipeline`]，使用预先生成的运行 ID（触发事件需先于运行输出）
+pub fn run_pipeline_with_id(
+    pipeline: &PipelineConfig,
+    ctx: &mut PipelineContext,
+    run_id: String,
+) -> Result<RunReport> {
+    let report = RunReport {
+        run_id,
+        ..RunReport::new(&pipeline.id)
+    };
+    execute_pipeline(pipeline, ctx, report)
+}
+
+/// 从失败的运行恢复：沿用 `completed` 步骤的结果与产物，仅执行失败步骤及其后续
+pub fn resume_pipeline(
+    pipeline: &PipelineConfig,
+    ctx: &mut PipelineContext,
+    resumed_from: &str,
+    completed: Vec<StepReport>,
+) -> Result<RunReport> {
     let mut report = RunReport::new(&pipeline.id);
-    let graph = StageGraph::from_pipeline(pipeline)?;
-    let layers = graph.execution_layers()?;
+    report.resumed_from = Some(resumed_from.to_string());
+    for step in &completed {
+        ctx.set_status(step.id.clone(), step.status);
+        if let Some(ref artifact) = step.artifact {
+            ctx.set_artifact(step.id.clone(), artifact.clone());
+        }
+    }
+    if !runtime::is_quiet() && !runtime::is_json_output() {
+        println!(
+            "\n  {} 从运行 {} 恢复，沿用 {} 个已完成步骤",
+            "↻".cyan().bold(),
+            resumed_from,
+            completed.len()
+        );
+    }
+    report.steps = completed;
+    execute_pipeline(pipeline, ctx, report)
+}
+
+fn execute_pipeline(
+    pipeline: &PipelineConfig,
+    ctx: &mut PipelineContext,
+    mut report: RunReport,
+) -> Result<RunReport> {
+    ctx.variables.extend(pipeline.variables.clone());
+    inputs::resolve(pipeline, &mut ctx.variables)?;
+    ctx.run_id = report.run_id.clone();
+    ctx.pipeline_id = pipeline.id.clone();
+    let expanded = expand_pipeline(pipeline, ctx)?;
+    let pipeline = &expanded;
+    // --resume 沿用的已完成步骤不再执行，在此重新写入其 exports
+    for done in &report.steps {
+        if matches!(done.status, StepStatus::Success | StepStatus::Cached)
+            && let Some(step) = pipeline.steps.iter().find(|s| s.id == done.id)
+        {
+            export(step, ctx);
+        }
+    }
+    let started = Instant::now();
+    // 作为 `module: pipeline` 步骤执行时随父步骤一并取消，否则随 Ctrl+C 取消
+    let cancel = cancel::current()
+        .unwrap_or_else(|| cancel::shutdown().clone())
+        .child();
+    let deadline = Deadline::new(started, pipeline.timeout_ms, cancel.clone());
+    events::emit(
+        &report.run_id,
+        Event::RunStarted {
+            pipeline_id: &pipeline.id,
+            resumed_from: report.resumed_from.as_deref(),
+        },
+    );
 
     if !runtime::is_quiet() && !runtime::is_json_output() {
         println!(
@@ -41,27 +242,64 @@ pub fn run_pipeline(pipeline: &PipelineConfig, ctx: &mut PipelineContext) -> Res
         .context("创建 tokio runtime 失败")?;
 
     rt.block_on(async {
-        'layers: for layer in layers {
-            if layer.len() == 1 {
-                let step_id = &layer[0];
-                let step = graph
-                    .step_by_id(pipeline, step_id)
-                    .context("步骤未找到")?
-                    .clone();
-                let outcome = execute_step_with_retry(&step, ctx).await?;
-                if apply_outcome(&mut report, &step, outcome, ctx) {
-                    break 'layers;
-                }
-            } else {
-                if run_parallel_layer(&layer, pipeline, &graph, ctx, &mut report).await? {
-                    break 'layers;
-                }
-            }
+        let main = run_dag(pipeline, deadline, ctx, &mut report).await;
+        if let Err(e) = &main {
+            report.fail();
+            report.error = Some(redact::mask(&format!("{e:#}")));
+        }
+        if cancel.is_cancelled() && report.status != RunStatus::Success {
+            report.cancel();
         }
-        anyhow::Ok(())
-    })?;
 
+        // on_failure / finally 不受 Pipeline 总超时限制；取消后 finally 仍完整执行（on_failure 跳过）
+        let handler_cancel = if cancel.is_cancelled() {
+            CancelToken::new()
+        } else {
+            cancel
+        };
+        let unbounded = Deadline::new(started, None, handler_cancel);
+        ctx.run
+            .insert("status".into(), report.status.as_str().into());
+        if report.status == RunStatus::Failed {
+            if let Some(step) = report.steps.iter().find(|s| s.status.is_failure()) {
+                ctx.run.insert("failed_step".into(), step.id.clone());
+                ctx.run
+                    .insert("error".into(), step.error.clone().unwrap_or_default());
+            } else if let Some(e) = &report.error {
+                ctx.run.insert("error".into(), e.clone());
+            }
+            let handlers = pipeline.handler(&pipeline.on_failure);
+            run_phase(
+                &handlers,
+                StepPhase::OnFailure,
+                unbounded.clone(),
+                ctx,
+                &mut report,
+            )
+            .await;
+        }
+        let finally = pipeline.handler(&pipeline.finally);
+        run_phase(&finally, StepPhase::Finally, unbounded, ctx, &mut report).await;
+    });
+    // 超时后未响应取消的阻塞任务不再等待
+    rt.shutdown_background();
     report.duration_ms = started.elapsed().as_millis() as u64;
+    if report.status == RunStatus::Success {
+        report.outputs = pipeline
+            .outputs
+            .iter()
+            .map(|(name, value)| (name.clone(), redact::mask(&ctx.parse(value))))
+            .collect();
+    }
+    events::emit(
+        &report.run_id,
+        Event::RunFinished {
+            pipeline_id: &pipeline.id,
+            status: report.status,
+            duration_ms: report.duration_ms,
+            report: &report,
+        },
+    );
 
     if report.status == RunStatus::Failed {
         if !runtime::is_quiet() && !runtime::is_json_output() {
@@ -72,20 +310,288 @@ pub fn run_pipeline(pipeline: &PipelineConfig, ctx: &mut PipelineContext) -> Res
             );
             eprint_failed(&report);
         }
+    } else if report.status == RunStatus::Cancelled {
+        if !runtime::is_quiet() && !runtime::is_json_output() {
+            eprintln!(
+                "\n  {} Pipeline 已取消（{} ms）",
+                "⊘".yellow().bold(),
+                report.duration_ms
+            );
+        }
     } else if !runtime::is_quiet() && !runtime::is_json_output() {
         println!(
             "\n  {} Pipeline 执行完成（{} ms）",
             "✓".green().bold(),
             report.duration_ms
         );
+        for (name, value) in &report.outputs {
+            println!("     {} = {}", name.as_str().cyan(), value);
+        }
     }
 
     Ok(report)
 }
 
+/// 按依赖就绪调度：步骤在自身依赖全部完成后立即启动（受 max_parallel 与模块并发上限约束）；
+/// 遇到未被容忍的失败即不再启动新步骤，并中止运行中的步骤。
+/// 已在报告中的步骤（恢复运行沿用的结果）不再执行。
+async fn run_dag(
+    pipeline: &PipelineConfig,
+    deadline: Deadline,
+    ctx: &mut PipelineContext,
+    report: &mut RunReport,
+) -> Result<()> {
+    let graph = StageGraph::from_pipeline(pipeline)?;
+    let chains = plan_chains(pipeline, &graph)?;
+    let timeline = Timeline::new(deadline.started, &graph)?;
+    let mut done: HashSet<String> = report.steps.iter().map(|s| s.id.clone()).collect();
+
+    // 调度单元（stream 链以链首代表）及其链外依赖，按拓扑序排列
+    let mut pending: Vec<(String, Vec<String>)> = Vec::new();
+    for id in graph.execution_order()? {
+        if done.contains(&id) || chains.iter().any(|c| c[1..].contains(&id)) {
+            continue;
+        }
+        let members = match chains.iter().find(|c| c[0] == id) {
+            Some(chain) => chain.clone(),
+            None => vec![id.clone()],
+        };
+        let mut deps: Vec<String> = members
+            .iter()
+            .flat_map(|m| graph.dependencies(m))
+            .filter(|d| !members.contains(d))
+            .collect();
+        deps.sort();
+        deps.dedup();
+        pending.push((id, deps));
+    }
+
+    let mut slots = Slots::new(pipeline);
+    let mut set: UnitTasks = JoinSet::new();
+    let mut running: HashMap<String, Running> = HashMap::new();
+    loop {
+        let mut i = 0;
+        while i < pending.len() {
+            let (id, deps) = &pending[i];
+            if !deps.iter().all(|d| done.contains(d)) {
+                i += 1;
+                continue;
+            }
+            let unit = ExecUnit::resolve(id, pipeline, &graph, &chains)?;
+            let unit_modules = unit.modules();
+            if !slots.acquire(&unit_modules) {
+                i += 1;
+                continue;
+            }
+            let (id, _) = pending.remove(i);
+            let deadline = deadline.unit();
+            running.insert(
+                id.clone(),
+                Running {
+                    steps: unit.steps(),
+                    modules: unit_modules,
+                    cancel: deadline.cancel.clone(),
+                    started: Instant::now(),
+                },
+            );
+            let mut local = ctx.clone();
+            set.spawn(async move {
+                let outcomes = unit.execute(&mut local, deadline).await;
+                (id, outcomes)
+            });
+        }
+
+        let Some(joined) = set.join_next().await else {
+            if let Some((id, _)) = pending.first() {
+                anyhow::bail!("步骤 '{id}' 的依赖未能完成");
+            }
+            return Ok(());
+        };
+        match joined {
+            Ok((id, Ok(outcomes))) => {
+                let unit = running.remove(&id).expect("运行中的单元已登记");
+                slots.release(&unit.modules);
+                done.extend(outcomes.iter().map(|(step, _)| step.id.clone()));
+                if apply_outcomes(report, outcomes, ctx, &timeline, unit.started) {
+                    abort_running(&mut set, running, &deadline, report, ctx, &timeline).await;
+                    return Ok(());
+                }
+            }
+            Ok((id, Err(e))) => {
+                running.remove(&id);
+                report.fail();
+                abort_running(&mut set, running, &deadline, report, ctx, &timeline).await;
+                return Err(e);
+            }
+            Err(e) => {
+                report.fail();
+                abort_running(&mut set, running, &deadline, report, ctx, &timeline).await;
+                return Err(e.into());
+            }
+        }
+    }
+}
+
+/// 运行中的调度单元：所含步骤、占用的 module 槽位、单元取消令牌与启动时刻
+struct Running {
+    steps: Vec<StepConfig>,
+    modules: Vec<String>,
+    cancel: CancelToken,
+    started: Instant,
+}
+
+/// 中止运行中的单元：取消各单元令牌并至多等待 [`CANCEL_GRACE`]，期间结束的单元照常写入报告；
+/// 仍未结束的单元不再等待，其步骤记为 cancelled。因失败中止时运行状态保持 failed
+async fn abort_running(
+    set: &mut UnitTasks,
+    mut running: HashMap<String, Running>,
+    deadline: &Deadline,
+    report: &mut RunReport,
+    ctx: &mut PipelineContext,
+    timeline: &Timeline,
+) {
+    let status = report.status;
+    let message = if deadline.cancelled() {
+        deadline.message()
+    } else {
+        "其他步骤失败，运行中的步骤已中止".to_string()
+    };
+    for unit in running.values() {
+        unit.cancel.cancel();
+    }
+    let drain = async {
+        while let Some(joined) = set.join_next().await {
+            if let Ok((id, Ok(mut outcomes))) = joined
+                && let Some(unit) = running.remove(&id)
+            {
+                for (_, outcome) in &mut outcomes {
+                    if outcome.status == StepStatus::Cancelled {
+                        outcome.error = Some(message.clone());
+                    }
+                }
+                apply_outcomes(report, outcomes, ctx, timeline, unit.started);
+            }
+        }
+    };
+    let _ = tokio::time::timeout(CANCEL_GRACE, drain).await;
+    set.abort_all();
+
+    for unit in running.into_values() {
+        let duration_ms = unit.started.elapsed().as_millis() as u64;
+        let outcomes = unit
+            .steps
+            .into_iter()
+            .map(|step| {
+                let outcome = stopped(StepStatus::Cancelled, duration_ms, message.clone());
+                (step, outcome)
+            })
+            .collect();
+        apply_outcomes(report, outcomes, ctx, timeline, unit.started);
+    }
+    report.status = status;
+}
+
+/// 步骤报告的启动时刻与所在 DAG 层（供 HTML 报告绘制时间线）
+struct Timeline {
+    started: Instant,
+    layers: HashMap<String, usize>,
+}
+
+impl Timeline {
+    fn new(started: Instant, graph: &StageGraph) -> Result<Self> {
+        let layers = graph
+            .execution_layers()?
+            .into_iter()
+            .enumerate()
+            .flat_map(|(n, ids)| ids.into_iter().map(move |id| (id, n)))
+            .collect();
+        Ok(Self { started, layers })
+    }
+
+    /// 为刚完成的步骤记录启动时刻（所属调度单元启动时距运行开始的毫秒数）与层号
+    fn stamp(&self, steps: &mut [StepReport], started: Instant) {
+        let started_ms = started.saturating_duration_since(self.started).as_millis() as u64;
+        for step in steps {
+            step.started_ms = Some(started_ms);
+            step.layer = self.layers.get(&step.id).copied();
+        }
+    }
+}
+
+/// 并发槽位：Pipeline 级 `max_parallel` 与按 module 的 `concurrency` 上限
+struct Slots {
+    max: Option<usize>,
+    limits: HashMap<String, usize>,
+    total: usize,
+    by_module: HashMap<String, usize>,
+}
+
+impl Slots {
+    fn new(pipeline: &PipelineConfig) -> Self {
+        Self {
+            max: pipeline.max_parallel,
+            limits: pipeline.concurrency.clone(),
+            total: 0,
+            by_module: HashMap::new(),
+        }
+    }
+
+    /// 有空闲槽位时占用并返回 true
+    fn acquire(&mut self, modules: &[String]) -> bool {
+        if self.max.is_some_and(|max| self.total >= max) {
+            return false;
+        }
+        let full = modules.iter().any(|m| {
+            self.limits
+                .get(m)
+                .is_some_and(|limit| self.by_module.get(m).copied().unwrap_or(0) >= *limit)
+        });
+        if full {
+            return false;
+        }
+        self.total += 1;
+        for m in modules {
+            *self.by_module.entry(m.clone()).or_default() += 1;
+        }
+        true
+    }
+
+    fn release(&mut self, modules: &[String]) {
+        self.total = self.total.saturating_sub(1);
+        for m in modules {
+            if let Some(count) = self.by_module.get_mut(m) {
+                *count = count.saturating_sub(1);
+            }
+        }
+    }
+}
+
+/// 执行 on_failure / finally 阶段；步骤报告标记所属阶段，调度错误计为失败
+async fn run_phase(
+    handlers: &PipelineConfig,
+    phase: StepPhase,
+    deadline: Deadline,
+    ctx: &mut PipelineContext,
+    report: &mut RunReport,
+) {
+    if handlers.steps.is_empty() {
+        return;
+    }
+    let start = report.steps.len();
+    if let Err(e) = run_dag(handlers, deadline, ctx, report).await {
+        report.fail();
+        if !runtime::is_quiet() && !runtime::is_json_output() {
+            eprintln!("  {} {}", "×".red(), redact::mask(&e.to_string()));
+        }
+    }
+    for step in &mut report.steps[start..] {
+        step.phase = phase;
+    }
+}
+
 fn eprint_failed(report: &RunReport) {
     for step in &report.steps {
-        if step.status == StepStatus::Failed {
+        if step.status.is_failure() {
             if let Some(ref err) = step.error {
                 eprintln!(
                     "     {} [{}] {}",
@@ -96,57 +602,194 @@ fn eprint_failed(report: &RunReport) {
             }
         }
     }
+    if let Some(ref err) = report.error {
+        eprintln!("     {} {}", "×".red(), err);
+    }
 }
 
-/// 并行执行同层步骤；任一步失败则 abort 其余任务。返回 true 表示本层失败。
-async fn run_parallel_layer(
-    layer: &[String],
-    pipeline: &PipelineConfig,
-    graph: &StageGraph,
-    ctx: &mut PipelineContext,
-    report: &mut RunReport,
-) -> Result<bool> {
-    let mut set: JoinSet<Result<(StepConfig, StepOutcome)>> = JoinSet::new();
-    for step_id in layer {
-        let step = graph
-            .step_by_id(pipeline, step_id)
-            .context("步骤未找到")?
-            .clone();
-        let vars = ctx.variables.clone();
-        let artifacts = ctx.step_artifacts.clone();
-        set.spawn(async move {
-            let mut local = PipelineContext {
-                variables: vars,
-                step_artifacts: artifacts,
-            };
-            let outcome = execute_step_with_retry(&step, &mut local).await?;
-            Ok((step, outcome))
-        });
+/// 调度单元：普通步骤（附所属 Pipeline ID）或整条 stream 链
+enum ExecUnit {
+    Step(Box<StepConfig>, String),
+    Chain(Vec<StepConfig>),
+}
+
+impl ExecUnit {
+    fn resolve(
+        step_id: &str,
+        pipeline: &PipelineConfig,
+        graph: &StageGraph,
+        chains: &[Vec<String>],
+    ) -> Result<Self> {
+        let lookup = |id: &str| {
+            graph
+                .step_by_id(pipeline, id)
+                .cloned()
+                .context("步骤未找到")
+        };
+        match chains.iter().find(|c| c[0] == step_id) {
+            Some(chain) => Ok(ExecUnit::Chain(
+                chain.iter().map(|id| lookup(id)).collect::<Result<_>>()?,
+            )),
+            None => Ok(ExecUnit::Step(
+                Box::new(lookup(step_id)?),
+                pipeline.id.clone(),
+            )),
+        }
     }
 
-    let mut layer_failed = false;
-    while let Some(res) = set.join_next().await {
-        match res {
-            Ok(Ok((step, outcome))) => {
-                if apply_outcome(report, &step, outcome, ctx) {
-                    layer_failed = true;
-                    set.abort_all();
-                }
-            }
-            Ok(Err(e)) => {
-                report.fail();
-                set.abort_all();
-                return Err(e);
+    /// 单元包含的步骤
+    fn steps(&self) -> Vec<StepConfig> {
+        match self {
+            ExecUnit::Step(step, _) => vec![(**step).clone()],
+            ExecUnit::Chain(steps) => steps.clone(),
+        }
+    }
+
+    /// 单元涉及的 module（去重），用于并发上限
+    fn modules(&self) -> Vec<String> {
+        let mut modules: Vec<String> = match self {
+            ExecUnit::Step(step, _) => vec![step.module.clone()],
+            ExecUnit::Chain(steps) => steps.iter().map(|s| s.module.clone()).collect(),
+        };
+        modules.sort();
+        modules.dedup();
+        modules
+    }
+
+    async fn execute(
+        self,
+        ctx: &mut PipelineContext,
+        deadline: Deadline,
+    ) -> Result<Vec<(StepConfig, StepOutcome)>> {
+        let (tolerate, mut outcomes) = match self {
+            ExecUnit::Step(step, pipeline_id) => {
+                let outcome = execute_step_cached(&pipeline_id, &step, ctx, deadline).await?;
+                (step.continue_on_error, vec![(*step, outcome)])
             }
-            Err(e) => {
-                report.fail();
-                set.abort_all();
-                return Err(e.into());
+            ExecUnit::Chain(steps) => (
+                steps[0].continue_on_error,
+                execute_stream_chain(steps, ctx, deadline).await,
+            ),
+        };
+        // continue_on_error：失败 / 超时记为 failed_tolerated，不中断后续步骤；取消不容忍
+        if tolerate {
+            for (_, outcome) in &mut outcomes {
+                if outcome.status.is_failure() && outcome.status != StepStatus::Cancelled {
+                    outcome.status = StepStatus::FailedTolerated;
+                }
             }
         }
+        Ok(outcomes)
+    }
+}
+
+/// 执行 stream 链：链首 when 为假时整链跳过；任一步失败则整链记为失败（仅出错步骤带 error）；
+/// 链首 timeout_ms / Pipeline 总超时到达时整链记为超时，被取消时记为 cancelled
+async fn execute_stream_chain(
+    steps: Vec<StepConfig>,
+    ctx: &PipelineContext,
+    deadline: Deadline,
+) -> Vec<(StepConfig, StepOutcome)> {
+    let started = Instant::now();
+    let status_all =
+        |status: StepStatus, error: Option<String>| -> Vec<(StepConfig, StepOutcome)> {
+            let duration_ms = started.elapsed().as_millis() as u64;
+            steps
+                .iter()
+                .enumerate()
+                .map(|(i, step)| {
+                    let error = if i == 0 { error.clone() } else { None };
+                    (
+                        step.clone(),
+                        StepOutcome::new(Artifact::default(), 0, duration_ms, status, error),
+                    )
+                })
+                .collect()
+        };
+
+    if let Some(ref when) = steps[0].when {
+        match ctx.eval_when(when) {
+            Ok(true) => {}
+            Ok(false) => return status_all(StepStatus::Skipped, None),
+            Err(e) => return status_all(StepStatus::Failed, Some(e.to_string())),
+        }
+    }
+    if deadline.expired() {
+        return status_all(deadline.stop_status(), Some(deadline.message()));
+    }
+
+    if !runtime::is_quiet() && !runtime::is_json_output() {
+        let labels: Vec<&str> = steps
+            .iter()
+            .map(|s| s.description.as_deref().unwrap_or(&s.module))
+            .collect();
+        println!("  {} {}", "▸".cyan(), labels.join(" → "));
+    }
+    for step in &steps {
+        events::emit(
+            &ctx.run_id,
+            Event::StepStarted {
+                step_id: &step.id,
+                module: &step.module,
+            },
+        );
+    }
+
+    let span = info_span!("pipeline_stream", head = %steps[0].id, stages = steps.len());
+    let token = deadline.token();
+    let chain = run_stream_chain(&steps, ctx, &token).instrument(span);
+    let outcome = match with_limit(deadline.limit(steps[0].timeout_ms), &token, chain).await {
+        Ok(outcome) => outcome,
+        Err(message) => return status_all(deadline.stop_status(), Some(message)),
+    };
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let failed_status = if deadline.cancelled() {
+        StepStatus::Cancelled
+    } else {
+        StepStatus::Failed
+    };
+
+    let failed = outcome.failed;
+    steps
+        .into_iter()
+        .zip(outcome.stages)
+        .enumerate()
+        .map(|(i, (step, (artifact, items)))| {
+            let result = match &failed {
+                None => StepOutcome::new(artifact, items, duration_ms, StepStatus::Success, None),
+                Some((index, err)) => {
+                    let error = (*index == i).then(|| err.to_string());
+                    StepOutcome::new(artifact, items, duration_ms, failed_status, error)
+                }
+            };
+            (step, result)
+        })
+        .collect()
+}
+
+/// 批量写入调度单元结果（`started` 为单元启动时刻）；返回 true 表示其中有步骤失败。
+fn apply_outcomes(
+    report: &mut RunReport,
+    outcomes: Vec<(StepConfig, StepOutcome)>,
+    ctx: &mut PipelineContext,
+    timeline: &Timeline,
+    started: Instant,
+) -> bool {
+    let start = report.steps.len();
+    let mut failed = false;
+    for (step, outcome) in outcomes {
+        failed |= apply_outcome(report, &step, outcome, ctx);
+    }
+    timeline.stamp(&mut report.steps[start..], started);
+    failed
+}
+
+/// 成功步骤的 `exports` 写入 variables，依赖它的后续步骤以 `${var.NAME}` 引用
+fn export(step: &StepConfig, ctx: &mut PipelineContext) {
+    for (name, value) in &step.exports {
+        let value = ctx.parse(value);
+        ctx.variables.insert(name.clone(), value);
     }
-    while set.join_next().await.is_some() {}
-    Ok(layer_failed)
 }
 
 /// 将步骤结果写入 report 与上下文；返回 true 表示该步失败。
@@ -156,68 +799,339 @@ fn apply_outcome(
     outcome: StepOutcome,
     ctx: &mut PipelineContext,
 ) -> bool {
-    let (artifact, items, duration_ms, status, err) = outcome;
-    let is_success = status == StepStatus::Success;
-    let is_failed = status == StepStatus::Failed;
-    if is_failed {
+    let StepOutcome {
+        mut artifact,
+        items,
+        duration_ms,
+        status,
+        error: err,
+        attempts,
+        child,
+    } = outcome;
+    let is_success = matches!(status, StepStatus::Success | StepStatus::Cached);
+    let is_failed = status.is_failure();
+    ctx.set_status(step.id.clone(), status);
+    if status == StepStatus::Cancelled {
+        report.cancel();
+    } else if is_failed {
         report.fail();
     } else if is_success {
         ctx.set_artifact(step.id.clone(), artifact.clone());
+        export(step, ctx);
     }
+    // 上下文保留原值供后续步骤引用，报告中的密钥值脱敏
+    let artifact = is_success.then(|| {
+        artifact.data.values_mut().for_each(redact::mask_value);
+        artifact
+    });
+    let error = err.map(|e| redact::mask(&e));
+    events::emit(
+        &ctx.run_id,
+        Event::StepFinished {
+            step_id: &step.id,
+            status,
+            items,
+            duration_ms,
+            error: error.as_deref(),
+        },
+    );
     report.steps.push(StepReport {
         id: step.id.clone(),
         module: step.module.clone(),
         status,
-        artifact: if is_success { Some(artifact) } else { None },
+        phase: StepPhase::Main,
+        artifact,
         items,
         duration_ms,
-        error: err,
+        error,
+        // 仅发生重试时写入各次尝试
+        attempts: if attempts.len() > 1 {
+            attempts
+        } else {
+            Vec::new()
+        },
+        pipeline: child.map(Box::new),
+        started_ms: None,
+        layer: None,
     });
     is_failed
 }
 
+/// 在时限内等待 `fut`；超时或令牌被外部取消时取消令牌，再给予 [`CANCEL_GRACE`] 收尾，返回说明
+async fn with_limit<T>(
+    limit: Option<(Duration, String)>,
+    token: &CancelToken,
+    fut: impl Future<Output = T>,
+) -> Result<T, String> {
+    let mut fut = Box::pin(fut);
+    let (limit, message) = match limit {
+        Some((limit, message)) => (Some(limit), message),
+        None => (None, String::new()),
+    };
+    let expired = async {
+        match limit {
+            Some(limit) => tokio::time::sleep(limit).await,
+            None => std::future::pending().await,
+        }
+    };
+    let message = tokio::select! {
+        value = &mut fut => return Ok(value),
+        _ = expired => message,
+        _ = cancelled(token) => "Pipeline 已取消".to_string(),
+    };
+    token.cancel();
+    let _ = tokio::time::timeout(CANCEL_GRACE, fut).await;
+    Err(message)
+}
+
+/// 令牌被取消时完成
+async fn cancelled(token: &CancelToken) {
+    while !token.is_cancelled() {
+        tokio::time::sleep(CANCEL_POLL).await;
+    }
+}
+
+/// 声明 inputs 的步骤先查缓存：命中则记为 cached 并复用上次产物；成功后写入缓存
+async fn execute_step_cached(
+    pipeline_id: &str,
+    step: &StepConfig,
+    ctx: &mut PipelineContext,
+    deadline: Deadline,
+) -> Result<StepOutcome> {
+    let skipped = match step.when.as_deref().map(|when| ctx.eval_when(when)) {
+        Some(Ok(run)) => !run,
+        // 运行时无法求值的 when 记为该步骤失败，不中断整个调度
+        Some(Err(e)) => {
+            return Ok(StepOutcome::new(
+                Artifact::default(),
+                0,
+                0,
+                StepStatus::Failed,
+                Some(e.to_string()),
+            ));
+        }
+        None => false,
+    };
+    if step.inputs.is_empty() || skipped {
+        return execute_step_with_retry(step, ctx, &deadline).await;
+    }
+
+    let started = Instant::now();
+    let (cache, hit) = tokio::task::spawn_blocking({
+        let (pipeline_id, step, ctx) = (pipeline_id.to_string(), step.clone(), ctx.clone());
+        move || -> Result<_> {
+            let cache = StepCache::open(&pipeline_id, &step, &ctx)?.context("步骤未声明 inputs")?;
+            let hit = cache.hit();
+            Ok((cache, hit))
+        }
+    })
+    .await??;
+
+    if let Some((artifact, items)) = hit {
+        if !runtime::is_quiet() && !runtime::is_json_output() {
+            println!(
+                "  {} {}（缓存命中）",
+                "▸".dark_grey(),
+                step.description.as_deref().unwrap_or(&step.module)
+            );
+        }
+        return Ok(StepOutcome::new(
+            artifact,
+            items,
+            started.elapsed().as_millis() as u64,
+            StepStatus::Cached,
+            None,
+        ));
+    }
+
+    let outcome = execute_step_with_retry(step, ctx, &deadline).await?;
+    if outcome.status == StepStatus::Success {
+        let (artifact, items) = (outcome.artifact.clone(), outcome.items);
+        let stored = tokio::task::spawn_blocking(move || cache.store(&artifact, items)).await?;
+        if let Err(e) = stored
+            && !runtime::is_quiet()
+        {
+            eprintln!("  {} 步骤 '{}' 写入缓存失败: {}", "⚠".yellow(), step.id, e);
+        }
+    }
+    Ok(outcome)
+}
+
+/// 按 `retry` 策略执行：失败后依 retry_on / no_retry_on 判断是否重试，等待退避时长（可被取消打断）；
+/// 发生重试时各次尝试随结果写入报告
 async fn execute_step_with_retry(
     step: &StepConfig,
     ctx: &mut PipelineContext,
+    deadline: &Deadline,
 ) -> Result<StepOutcome> {
     let max = step.retry.as_ref().map(|r| r.max).unwrap_or(1).max(1);
-    let backoff = step.retry.as_ref().map(|r| r.backoff_ms).unwrap_or(0);
-    let mut last_err = None;
-    for attempt in 0..max {
-        if attempt > 0 && backoff > 0 {
-            tokio::time::sleep(tokio::time::Duration::from_millis(backoff)).await;
-        }
-        match execute_step_once(step, ctx).await {
-            Ok(outcome) => return Ok(outcome),
-            Err(e) => last_err = Some(e),
-        }
-    }
-    Ok((
-        Artifact::default(),
-        0,
-        0,
-        StepStatus::Failed,
-        last_err.map(|e| e.to_string()),
-    ))
+    let policy = match step.retry {
+        Some(ref retry) => Some((retry, retry.policy()?)),
+        None => None,
+    };
+    let mut attempts: Vec<Attempt> = Vec::new();
+    let mut last = None;
+    let mut delay = 0;
+    for attempt in 1..=max {
+        if delay > 0 {
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_millis(delay)) => {}
+                _ = cancelled(&deadline.cancel) => {}
+            }
+        }
+        if deadline.expired() {
+            let outcome = if deadline.cancelled() {
+                stopped(StepStatus::Cancelled, 0, deadline.message())
+            } else {
+                last.unwrap_or_else(|| stopped(StepStatus::TimedOut, 0, deadline.message()))
+            };
+            return Ok(StepOutcome {
+                attempts,
+                ..outcome
+            });
+        }
+        let mut failed = match execute_step_once(step, ctx, deadline).await {
+            // 单次超时 / 失败可重试；Pipeline 总超时则不再重试
+            Ok(outcome) if outcome.status.is_retryable() => outcome,
+            Ok(outcome) => {
+                if !attempts.is_empty() {
+                    attempts.push(Attempt {
+                        attempt,
+                        status: outcome.status,
+                        duration_ms: outcome.duration_ms,
+                        error: outcome.error.as_deref().map(redact::mask),
+                        delay_ms: None,
+                    });
+                }
+                return Ok(StepOutcome {
+                attempts,
+                ..outcome
+            });
+            }
+            Err(e) => StepOutcome::new(
+                Artifact::default(),
+                0,
+                0,
+                StepStatus::Failed,
+                Some(e.to_string()),
+            ),
+        };
+        // 取消期间的失败（含模块响应取消返回的错误）记为 cancelled，不再重试
+        if deadline.cancelled() {
+            failed.status = StepStatus::Cancelled;
+            return Ok(StepOutcome { attempts, ..failed });
+        }
+        let retry = policy.as_ref().filter(|(_, policy)| {
+            attempt < max
+                && !deadline.expired()
+                && policy.should_retry(failed.status, failed.error.as_deref())
+        });
+        delay = retry.map_or(0, |(retry, _)| retry.delay_ms(attempt));
+        let error = failed.error.as_deref().map(redact::mask);
+        attempts.push(Attempt {
+            attempt,
+            status: failed.status,
+            duration_ms: failed.duration_ms,
+            error: error.clone(),
+            delay_ms: retry.map(|_| delay),
+        });
+        if retry.is_none() {
+            return Ok(StepOutcome { attempts, ..failed });
+        }
+        events::emit(
+            &ctx.run_id,
+            Event::StepRetry {
+                step_id: &step.id,
+                attempt,
+                max,
+                backoff_ms: delay,
+                error: error.as_deref(),
+            },
+        );
+        last = Some(failed);
+    }
+    Ok(StepOutcome {
+        attempts,
+        ..last.expect("retry max >= 1")
+    })
+}
+
+/// 超时 / 取消时的步骤结果
+fn stopped(status: StepStatus, duration_ms: u64, message: String) -> StepOutcome {
+    StepOutcome::new(Artifact::default(), 0, duration_ms, status, Some(message))
 }
 
-async fn execute_step_once(step: &StepConfig, ctx: &mut PipelineContext) -> Result<StepOutcome> {
+/// 单次执行：阻塞线程中运行并绑定取消令牌，超时后协作取消
+async fn execute_step_once(
+    step: &StepConfig,
+    ctx: &PipelineContext,
+    deadline: &Deadline,
+) -> Result<StepOutcome> {
+    let started = Instant::now();
     let span = info_span!("pipeline_step", step_id = %step.id, module = %step.module);
-    let _enter = span.enter();
-    execute_step_blocking(step, ctx)
+    let token = deadline.token();
+    let progress = step_progress(&ctx.run_id, &step.id);
+    let handle = tokio::task::spawn_blocking({
+        let (step, ctx, token) = (step.clone(), ctx.clone(), token.clone());
+        move || {
+            let _enter = span.enter();
+            let run = || cancel::scope(&token, || execute_step_blocking(&step, &ctx));
+            match progress {
+                Some(progress) => observe::scope(&progress, run),
+                None => run(),
+            }
+        }
+    });
+    match with_limit(deadline.limit(step.timeout_ms), &token, handle).await {
+        Ok(joined) => joined?,
+        Err(message) => Ok(stopped(
+            deadline.stop_status(),
+            started.elapsed().as_millis() as u64,
+            message,
+        )),
+    }
 }
 
-fn execute_step_blocking(step: &StepConfig, ctx: &mut PipelineContext) -> Result<StepOutcome> {
+/// 事件流模式下的进度回调：按 [`PROGRESS_INTERVAL`] 节流输出 `step_progress`
+fn step_progress(run_id: &str, step_id: &str) -> Option<Progress> {
+    if !runtime::is_event_stream() {
+        return None;
+    }
+    let (run_id, step_id) = (run_id.to_string(), step_id.to_string());
+    let last = Mutex::new(None::<Instant>);
+    Some(Progress::new(move |items, bytes| {
+        let mut last = last.lock().unwrap_or_else(|e| e.into_inner());
+        if last.is_some_and(|at| at.elapsed() < PROGRESS_INTERVAL) {
+            return;
+        }
+        *last = Some(Instant::now());
+        events::emit(
+            &run_id,
+            Event::StepProgress {
+                step_id: &step_id,
+                items,
+                bytes,
+            },
+        );
+    }))
+}
+
+fn execute_step_blocking(step: &StepConfig, ctx: &PipelineContext) -> Result<StepOutcome> {
     let started = Instant::now();
 
     if let Some(ref when) = step.when {
-        if !ctx.eval_when(when) {
-            return Ok((
+        let status = match ctx.eval_when(when) {
+            Ok(true) => None,
+            Ok(false) => Some((StepStatus::Skipped, None)),
+            Err(e) => Some((StepStatus::Failed, Some(e.to_string()))),
+        };
+        if let Some((status, error)) = status {
+            return Ok(StepOutcome::new(
                 Artifact::default(),
                 0,
                 started.elapsed().as_millis() as u64,
-                StepStatus::Skipped,
-                None,
+                status,
+                error,
             ));
         }
     }
@@ -229,6 +1143,43 @@ fn execute_step_blocking(step: &StepConfig, ctx: &mut PipelineContext) -> Result
             step.description.as_deref().unwrap_or(&step.module)
         );
     }
+    events::emit(
+        &ctx.run_id,
+        Event::StepStarted {
+            step_id: &step.id,
+            module: &step.module,
+        },
+    );
+
+    if step.module == subpipeline::MODULE {
+        let report = subpipeline::run(step, ctx)?;
+        let (status, error) = match report.status {
+            RunStatus::Success => (StepStatus::Success, None),
+            RunStatus::Failed => (
+                StepStatus::Failed,
+                Some(format!(
+                    "子 Pipeline '{}' {}",
+                    report.pipeline_id,
+                    report.message()
+                )),
+            ),
+            RunStatus::Cancelled => (
+                StepStatus::Cancelled,
+                Some(format!("子 Pipeline '{}' 已取消", report.pipeline_id)),
+            ),
+        };
+        let outcome = StepOutcome::new(
+            subpipeline::artifact(&report),
+            report.steps.len() as u64,
+            started.elapsed().as_millis() as u64,
+            status,
+            error,
+        );
+        return Ok(StepOutcome {
+            child: Some(report),
+            ..outcome
+        });
+    }
 
     let (artifact, items) = if step.module == "generate"
         && step.action.as_deref() == Some("path")
@@ -239,7 +1190,7 @@ fn execute_step_blocking(step: &StepConfig, ctx: &mut PipelineContext) -> Result
         run_batch_stage(step, ctx)?
     };
 
-    Ok((
+    Ok(StepOutcome::new(
         artifact,
         items,
         started.elapsed().as_millis() as u64,
@@ -0,0 +1,227 @@
+//! 占位符语法：`${path[:-default][|filter...]}`
+//!
+//! ```text
+//! path    := var.NAME | env.NAME | secret.NAME | run.KEY | pipeline.id
+//!          | steps.ID.status | steps.ID.artifact.path | steps.ID.artifact.data.KEY(.KEY)*
+//!          | now[:FORMAT] | uuid
+//! filter  := upper | lower | basename | dirname | stem | json
+//! ```
+
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use regex::Regex;
+use serde_json::Value;
+
+/// 可用过滤器
+pub const FILTERS: [&str; 6] = ["upper", "lower", "basename", "dirname", "stem", "json"];
+
+/// `${now}` 缺省格式
+pub const NOW_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// 匹配最内层 `${...}`（嵌套时由内向外解析）
+pub fn regex() -> Regex {
+    Regex::new(r"\$\{([^{}]+)\}").unwrap()
+}
+
+/// 单个占位符（不含 `${}`）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholder<'a> {
+    pub path: &'a str,
+    /// `:-` 之后的缺省值（引用未解析或为空时使用）
+    pub default: Option<&'a str>,
+    pub filters: Vec<&'a str>,
+}
+
+impl<'a> Placeholder<'a> {
+    /// 先切出 `:-` 缺省值，缺省值中的 `|` 仅在其后为已知过滤器名时视为过滤器（`${var.x:-a|b}` 的缺省值为 `a|b`）
+    pub fn parse(raw: &'a str) -> Self {
+        let (head, default) = match raw.split_once(":-") {
+            Some((head, default)) => (head, Some(default)),
+            None => (raw, None),
+        };
+        let mut parts = head.split('|');
+        let path = parts.next().unwrap_or_default().trim();
+        let mut filters: Vec<&str> = parts.map(str::trim).collect();
+        let default = default.map(|mut default| {
+            let mut trailing = Vec::new();
+            while let Some((rest, filter)) = default.rsplit_once('|')
+                && FILTERS.contains(&filter.trim())
+            {
+                trailing.push(filter.trim());
+                default = rest;
+            }
+            filters.extend(trailing.into_iter().rev());
+            default
+        });
+        Self {
+            path,
+            default,
+            filters,
+        }
+    }
+
+    /// 校验路径形态、时间格式与过滤器名
+    pub fn validate(&self) -> Result<()> {
+        if let Some(format) = self.now_format() {
+            if !is_valid_format(format) {
+                bail!("时间格式无效: ${{now:{format}}}");
+            }
+        } else if !is_known_path(self.path) {
+            bail!(
+                "未知的占位符 ${{{}}}（可用 var / env / secret / run / pipeline.id / steps / now / uuid）",
+                self.path
+            );
+        }
+        if let Some(filter) = self.filters.iter().find(|f| !FILTERS.contains(f)) {
+            bail!("未知的过滤器 '{filter}'（可用 {}）", FILTERS.join(" / "));
+        }
+        Ok(())
+    }
+
+    /// `now` / `now:FORMAT` 的格式
+    pub fn now_format(&self) -> Option<&'a str> {
+        match self.path {
+            "now" => Some(NOW_FORMAT),
+            path => path.strip_prefix("now:"),
+        }
+    }
+
+    /// 依次应用过滤器；未知过滤器返回 None
+    pub fn apply(&self, value: Value) -> Option<String> {
+        let mut value = value;
+        for filter in &self.filters {
+            let text = value_to_string(&value);
+            let path = Path::new(&text);
+            let out = match *filter {
+                "upper" => text.to_uppercase(),
+                "lower" => text.to_lowercase(),
+                "basename" => lossy(path.file_name()),
+                "dirname" => path
+                    .parent()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                "stem" => lossy(path.file_stem()),
+                "json" => serde_json::to_string(&value).ok()?,
+                _ => return None,
+            };
+            value = Value::String(out);
+        }
+        Some(value_to_string(&value))
+    }
+}
+
+/// 文本中的全部占位符（含嵌套在缺省值中的），按由内向外的顺序
+pub fn scan(text: &str) -> Vec<String> {
+    let re = regex();
+    let mut out = Vec::new();
+    let mut rest = text.to_string();
+    while re.is_match(&rest) {
+        out.extend(re.captures_iter(&rest).map(|c| c[1].to_string()));
+        rest = re.replace_all(&rest, "").into_owned();
+    }
+    out
+}
+
+/// 文本中全部占位符的路径（如 `var.out`）
+pub fn paths(text: &str) -> Vec<String> {
+    scan(text)
+        .iter()
+        .map(|raw| Placeholder::parse(raw).path.to_string())
+        .collect()
+}
+
+/// 校验文本中的全部占位符
+pub fn validate_text(text: &str) -> Result<()> {
+    scan(text)
+        .iter()
+        .try_for_each(|raw| Placeholder::parse(raw).validate())
+}
+
+/// 递归校验 JSON 中字符串的占位符
+pub fn validate_value(value: &Value) -> Result<()> {
+    match value {
+        Value::String(s) => validate_text(s),
+        Value::Array(arr) => arr.iter().try_for_each(validate_value),
+        Value::Object(map) => map.values().try_for_each(validate_value),
+        _ => Ok(()),
+    }
+}
+
+fn is_known_path(path: &str) -> bool {
+    let parts: Vec<&str> = path.split('.').collect();
+    match parts.as_slice() {
+        ["uuid"] | ["pipeline", "id"] => true,
+        ["var" | "env" | "secret" | "run", name] => !name.is_empty(),
+        ["steps", id, "status"] | ["steps", id, "artifact", "path"] => !id.is_empty(),
+        ["steps", id, "artifact", "data", keys @ ..] => {
+            !id.is_empty() && !keys.is_empty() && keys.iter().all(|k| !k.is_empty())
+        }
+        _ => false,
+    }
+}
+
+/// strftime 格式是否可用（无效格式在格式化时会 panic）
+pub fn is_valid_format(format: &str) -> bool {
+    use chrono::format::{Item, StrftimeItems};
+    !StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+}
+
+/// 按点分路径取 JSON 值（数组以下标访问）
+pub fn lookup<'v>(value: &'v Value, keys: &[&str]) -> Option<&'v Value> {
+    keys.iter().try_fold(value, |value, key| match value {
+        Value::Object(map) => map.get(*key),
+        Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+pub fn value_to_string(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn lossy(part: Option<&std::ffi::OsStr>) -> String {
+    part.map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_defaults_filters_and_validates() {
+        let ph = Placeholder::parse("var.out:-./dist|basename|upper");
+        assert_eq!(ph.path, "var.out");
+        assert_eq!(ph.default, Some("./dist"));
+        assert_eq!(ph.apply(json!("a/b/file.tar.gz")).unwrap(), "FILE.TAR.GZ");
+        let ph = Placeholder::parse("var.x:-a|b");
+        assert_eq!(ph.default, Some("a|b"));
+        assert!(ph.filters.is_empty());
+        let ph = Placeholder::parse("var.x|lower:-A|B|upper");
+        assert_eq!((ph.path, ph.default), ("var.x", Some("A|B")));
+        assert_eq!(ph.filters, ["lower", "upper"]);
+        assert_eq!(
+            Placeholder::parse("steps.x.artifact.data.cpu|json")
+                .apply(json!({ "cores": 8 }))
+                .unwrap(),
+            r#"{"cores":8}"#
+        );
+
+        assert!(validate_text("${now:%Y%m%d-%H%M}/${uuid}/${run.id}/${pipeline.id}").is_ok());
+        assert!(validate_text("${steps.probe.artifact.data.CPU.cores}").is_ok());
+        assert!(validate_text("${var.a:-${env.HOME}}").is_ok());
+        assert!(validate_text("${HOME}").is_err());
+        assert!(validate_text("${var.a|shout}").is_err());
+        assert!(validate_text("${steps.x.output}").is_err());
+        assert!(validate_text("${now:%Q}").is_err());
+        assert_eq!(
+            paths("${var.a:-${env.B}} ${steps.c.status}"),
+            ["env.B", "steps.c.status", "var.a"]
+        );
+    }
+}
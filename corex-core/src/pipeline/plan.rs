@@ -0,0 +1,409 @@
+//! Dry-run 执行计划：按变量解析参数、按 DAG 分层，标出已可判定的 when 与待运行期解析的占位符
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use anyhow::Result;
+use crossterm::style::Stylize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::utils::redact;
+
+use super::config::{PipelineConfig, StepConfig};
+use super::context::PipelineContext;
+use super::expand::expand_pipeline;
+use super::expr;
+use super::graph::StageGraph;
+use super::inputs;
+use super::placeholder;
+use super::step_params::redact_sensitive_params;
+use super::trigger::label;
+
+/// 视为输入路径、需检查是否存在的 params 字段
+const INPUT_KEYS: [&str; 5] = ["from", "source", "src", "input", "script"];
+
+/// 执行期才能确定的引用根（步骤产物 / 状态、运行状态）
+const RUNTIME_ROOTS: [&str; 2] = ["steps", "run"];
+
+/// Dry-run 计划（`--format json` 原样输出，供 CI diff）
+#[derive(Debug, Serialize)]
+pub struct DryRunPlan {
+    pub pipeline_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub watch: Vec<String>,
+    /// 合并 `-D` / `COREX_VAR_*` 并解析后的变量
+    pub variables: BTreeMap<String, String>,
+    /// 主步骤执行层（同层可并发）
+    pub layers: Vec<Vec<String>>,
+    pub steps: Vec<PlannedStep>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_failure: Vec<PlannedStep>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub finally: Vec<PlannedStep>,
+}
+
+/// 单个步骤的计划
+#[derive(Debug, Serialize)]
+pub struct PlannedStep {
+    pub id: String,
+    pub module: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 所在层（从 0 起，on_failure / finally 各自分层）
+    pub layer: usize,
+    /// 直接依赖（含隐式链）
+    pub depends_on: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub when: Option<WhenPlan>,
+    /// 已解析的参数（密钥值与 password 脱敏）
+    pub params: Value,
+    /// 依赖步骤产物 / 运行状态、执行期才能解析的占位符
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pending: Vec<String>,
+    /// 无法解析的占位符（未定义的变量 / 环境变量 / 密钥）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unresolved: Vec<String>,
+    /// 输入路径检查
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<InputCheck>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WhenPlan {
+    pub expr: String,
+    pub result: WhenResult,
+}
+
+/// when 的预判结果；引用步骤或运行状态时为 pending，表达式无法解析时为 invalid
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WhenResult {
+    True,
+    False,
+    Pending,
+    Invalid(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct InputCheck {
+    pub key: String,
+    pub path: String,
+    pub exists: bool,
+}
+
+/// 以 `variables`（已合并顶层与 Pipeline 级变量）生成计划；inputs 同执行前校验
+pub fn plan(
+    pipeline: &PipelineConfig,
+    mut variables: HashMap<String, String>,
+) -> Result<DryRunPlan> {
+    inputs::resolve(pipeline, &mut variables)?;
+    let mut ctx = PipelineContext::with_variables(variables);
+    ctx.pipeline_id = pipeline.id.clone();
+    let expanded = expand_pipeline(pipeline, &ctx)?;
+    let (layers, steps) = plan_phase(&expanded, &ctx)?;
+    let (_, on_failure) = plan_phase(&expanded.handler(&expanded.on_failure), &ctx)?;
+    let (_, finally) = plan_phase(&expanded.handler(&expanded.finally), &ctx)?;
+
+    let variables = ctx
+        .variables
+        .keys()
+        .map(|k| (k.clone(), redact::mask(&ctx.parse(&ctx.variables[k]))))
+        .collect();
+    Ok(DryRunPlan {
+        pipeline_id: pipeline.id.clone(),
+        description: pipeline.description.clone(),
+        schedule: pipeline.schedule.clone(),
+        watch: pipeline
+            .watch
+            .as_ref()
+            .map(|w| w.paths.iter().map(|p| ctx.parse(p)).collect())
+            .unwrap_or_default(),
+        variables,
+        layers,
+        steps,
+        on_failure,
+        finally,
+    })
+}
+
+fn plan_phase(
+    pipeline: &PipelineConfig,
+    ctx: &PipelineContext,
+) -> Result<(Vec<Vec<String>>, Vec<PlannedStep>)> {
+    if pipeline.steps.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    let graph = StageGraph::from_pipeline(pipeline)?;
+    let mut layers = graph.execution_layers()?;
+    let mut steps = Vec::new();
+    for (layer, ids) in layers.iter_mut().enumerate() {
+        // 同层按 yaml 顺序，保证输出稳定
+        ids.sort_by_key(|id| pipeline.steps.iter().position(|s| s.id == *id));
+        for id in ids.iter() {
+            let Some(step) = graph.step_by_id(pipeline, id) else {
+                continue;
+            };
+            let mut depends_on = graph.dependencies(id);
+            depends_on.sort();
+            steps.push(plan_step(step, layer, depends_on, ctx));
+        }
+    }
+    Ok((layers, steps))
+}
+
+fn plan_step(
+    step: &StepConfig,
+    layer: usize,
+    depends_on: Vec<String>,
+    ctx: &PipelineContext,
+) -> PlannedStep {
+    let resolved = ctx.parse_value(&step.params);
+    let mut placeholders = Vec::new();
+    collect_placeholders(&resolved, &mut placeholders);
+    let (pending, unresolved) = placeholders
+        .into_iter()
+        .partition(|reference| is_runtime(reference));
+
+    let inputs = match &resolved {
+        Value::Object(map) => INPUT_KEYS
+            .iter()
+            .filter_map(|key| Some((*key, map.get(*key)?.as_str()?)))
+            .filter(|(_, path)| !path.is_empty() && !path.contains("${"))
+            .map(|(key, path)| InputCheck {
+                key: key.to_string(),
+                path: redact::mask(path),
+                exists: Path::new(path).exists(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let mut params = redact_sensitive_params(&resolved);
+    redact::mask_value(&mut params);
+    PlannedStep {
+        id: step.id.clone(),
+        module: step.module.clone(),
+        action: step.action.clone(),
+        description: step.description.clone(),
+        layer,
+        depends_on,
+        when: step.when.as_ref().map(|when| WhenPlan {
+            expr: when.clone(),
+            result: eval_when(when, ctx),
+        }),
+        params,
+        pending,
+        unresolved,
+        inputs,
+    }
+}
+
+fn eval_when(when: &str, ctx: &PipelineContext) -> WhenResult {
+    let expr = match expr::parse_when(when) {
+        Ok(expr) => expr,
+        Err(e) => return WhenResult::Invalid(e.to_string()),
+    };
+    if expr.references().iter().any(|r| is_runtime(r)) {
+        WhenResult::Pending
+    } else if expr.eval(ctx).truthy() {
+        WhenResult::True
+    } else {
+        WhenResult::False
+    }
+}
+
+fn is_runtime(reference: &str) -> bool {
+    reference
+        .split('.')
+        .next()
+        .is_some_and(|root| RUNTIME_ROOTS.contains(&root))
+}
+
+/// 收集解析后仍残留的 `${...}`（去重，保持出现顺序）
+fn collect_placeholders(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(s) => {
+            for path in placeholder::paths(s) {
+                if !out.contains(&path) {
+                    out.push(path);
+                }
+            }
+        }
+        Value::Array(arr) => arr.iter().for_each(|v| collect_placeholders(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_placeholders(v, out)),
+        _ => {}
+    }
+}
+
+impl DryRunPlan {
+    /// 人类可读输出
+    pub fn print(&self, pipeline: &PipelineConfig) {
+        println!(
+            "\n  {} Dry-run：{}\n",
+            "◇".yellow().bold(),
+            self.description
+                .as_deref()
+                .unwrap_or(&self.pipeline_id)
+                .bold()
+        );
+        let badge = label(pipeline);
+        if !badge.is_empty() {
+            println!("  触发器: {badge}");
+        }
+        if let Some(ref sched) = self.schedule {
+            println!("  定时调度: {sched}");
+        }
+        if !self.watch.is_empty() {
+            println!("  文件监听: {}", self.watch.join(", "));
+        }
+        if !self.variables.is_empty() {
+            println!("  变量:");
+            for (k, v) in &self.variables {
+                println!("     {k} = {v}");
+            }
+        }
+        println!("  步骤数: {}（{} 层）", self.steps.len(), self.layers.len());
+        print_steps(&self.steps);
+        for (title, steps) in [("on_failure", &self.on_failure), ("finally", &self.finally)] {
+            if !steps.is_empty() {
+                println!("\n  {} {title}", "▸".cyan());
+                print_steps(steps);
+            }
+        }
+        println!();
+    }
+}
+
+fn print_steps(steps: &[PlannedStep]) {
+    let mut layer = None;
+    for step in steps {
+        if layer != Some(step.layer) {
+            layer = Some(step.layer);
+            println!("\n  {} 第 {} 层", "─".dark_grey(), step.layer + 1);
+        }
+        let skipped = step
+            .when
+            .as_ref()
+            .is_some_and(|w| w.result == WhenResult::False);
+        let title = format!("{} — module={}", step.id, step.module);
+        if skipped {
+            println!("  {} {}（when 为假，将跳过）", "⊘".yellow(), title.dim());
+        } else {
+            println!("  {} {}", "▸".cyan(), title.bold());
+        }
+        if let Some(ref desc) = step.description {
+            println!("       描述: {desc}");
+        }
+        if !step.depends_on.is_empty() {
+            println!("       depends_on: {}", step.depends_on.join(", "));
+        }
+        if let Some(ref when) = step.when {
+            let result = match when.result {
+                WhenResult::True => "true".green().to_string(),
+                WhenResult::False => "false".yellow().to_string(),
+                WhenResult::Pending => "待运行期判定".dark_grey().to_string(),
+                WhenResult::Invalid(ref e) => format!("无效: {e}").red().to_string(),
+            };
+            println!("       when: {} → {}", when.expr, result);
+        }
+        println!(
+            "       参数: {}",
+            serde_json::to_string_pretty(&step.params)
+                .unwrap_or_default()
+                .replace('\n', "\n       ")
+        );
+        for input in &step.inputs {
+            if input.exists {
+                println!("       {} {}: {}", "✓".green(), input.key, input.path);
+            } else {
+                println!(
+                    "       {} {}: {}（不存在）",
+                    "×".red(),
+                    input.key,
+                    input.path
+                );
+            }
+        }
+        if !step.pending.is_empty() {
+            println!("       待运行期解析: {}", step.pending.join(", "));
+        }
+        if !step.unresolved.is_empty() {
+            println!(
+                "       {} 无法解析: {}",
+                "⚠".yellow(),
+                step.unresolved.join(", ")
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn step(id: &str, params: Value) -> StepConfig {
+        StepConfig {
+            id: id.into(),
+            module: "copy".into(),
+            params,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plan_resolves_params_and_marks_pending() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut build = step(
+            "build",
+            json!({ "from": "${var.root}", "to": "${var.root}/out" }),
+        );
+        build.when = Some("var.mode == 'debug'".into());
+        let mut pack = step(
+            "pack",
+            json!({ "from": "${steps.build.artifact.path}", "to": "${var.missing}/x" }),
+        );
+        pack.when = Some("steps.build.status == success".into());
+        let mut lint = step("lint", json!({ "from": "${var.root}/nope", "to": "x" }));
+        lint.depends_on = vec!["build".into()];
+        lint.when = Some("var.mode ==".into());
+        let pipeline = PipelineConfig {
+            id: "demo".into(),
+            steps: vec![build, pack, lint],
+            ..Default::default()
+        };
+        let variables = HashMap::from([
+            ("root".to_string(), dir.path().display().to_string()),
+            ("mode".to_string(), "release".to_string()),
+        ]);
+
+        let plan = plan(&pipeline, variables).unwrap();
+        assert_eq!(plan.layers, [vec!["build"], vec!["pack", "lint"]]);
+
+        let build = &plan.steps[0];
+        assert_eq!(build.params["to"], format!("{}/out", dir.path().display()));
+        assert_eq!(build.when.as_ref().unwrap().result, WhenResult::False);
+        assert!(build.inputs[0].exists);
+
+        let pack = &plan.steps[1];
+        assert_eq!(pack.when.as_ref().unwrap().result, WhenResult::Pending);
+        assert_eq!(pack.pending, ["steps.build.artifact.path"]);
+        assert_eq!(pack.unresolved, ["var.missing"]);
+        assert!(pack.inputs.is_empty());
+
+        let lint = &plan.steps[2];
+        assert_eq!(lint.depends_on, ["build"]);
+        assert!(matches!(
+            lint.when.as_ref().unwrap().result,
+            WhenResult::Invalid(ref e) if !e.is_empty()
+        ));
+        assert!(!lint.inputs[0].exists);
+    }
+}
@@ -1,6 +1,7 @@
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use thiserror::Error;
 
@@ -15,63 +16,183 @@ pub struct StepFail {
 }
 
 /// 单步执行报告
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StepReport {
     pub id: String,
     pub module: String,
     pub status: StepStatus,
+    #[serde(default, skip_serializing_if = "StepPhase::is_main")]
+    pub phase: StepPhase,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub artifact: Option<Artifact>,
     pub items: u64,
     pub duration_ms: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// 各次尝试（发生重试时记录）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<Attempt>,
+    /// 子 Pipeline 报告（`module: pipeline`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline: Option<Box<RunReport>>,
+    /// 相对运行开始的启动时刻（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_ms: Option<u64>,
+    /// 所在 DAG 层（各阶段分别从 0 起）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<usize>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
+/// 单次尝试记录
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Attempt {
+    /// 第几次执行（从 1 起）
+    pub attempt: u32,
+    pub status: StepStatus,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// 下一次执行前的等待时长
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
 pub enum StepStatus {
     Success,
     Skipped,
     Failed,
+    TimedOut,
+    /// Ctrl+C 中断
+    Cancelled,
+    /// inputs / outputs 未变化，复用上次产物
+    Cached,
+    /// 失败但 continue_on_error 容忍，不影响 Pipeline 状态
+    FailedTolerated,
+}
+
+impl StepStatus {
+    /// 与 JSON 一致的名称
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StepStatus::Success => "success",
+            StepStatus::Skipped => "skipped",
+            StepStatus::Failed => "failed",
+            StepStatus::TimedOut => "timed_out",
+            StepStatus::Cancelled => "cancelled",
+            StepStatus::Cached => "cached",
+            StepStatus::FailedTolerated => "failed_tolerated",
+        }
+    }
+
+    /// 失败、超时或被取消
+    pub fn is_failure(self) -> bool {
+        matches!(
+            self,
+            StepStatus::Failed | StepStatus::TimedOut | StepStatus::Cancelled
+        )
+    }
+
+    /// 可按 retry 策略重试：失败或超时（取消不重试）
+    pub fn is_retryable(self) -> bool {
+        matches!(self, StepStatus::Failed | StepStatus::TimedOut)
+    }
+}
+
+/// 步骤所属阶段
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepPhase {
+    #[default]
+    Main,
+    OnFailure,
+    Finally,
+}
+
+impl StepPhase {
+    pub fn is_main(&self) -> bool {
+        *self == StepPhase::Main
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StepPhase::Main => "main",
+            StepPhase::OnFailure => "on_failure",
+            StepPhase::Finally => "finally",
+        }
+    }
 }
 
 /// Pipeline 执行报告
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunReport {
     pub pipeline_id: String,
+    /// 运行 ID（`corex pipeline --resume <run-id>`）
+    pub run_id: String,
+    /// 由哪次失败运行恢复而来
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resumed_from: Option<String>,
     pub status: RunStatus,
     pub started_at: String,
     pub duration_ms: u64,
     pub steps: Vec<StepReport>,
+    /// Pipeline `outputs`（仅成功时）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub outputs: BTreeMap<String, String>,
+    /// 不属于任何步骤的运行级错误（如调度中断）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, clap::ValueEnum)]
 #[serde(rename_all = "lowercase")]
 pub enum RunStatus {
     Success,
     Failed,
+    Cancelled,
+}
+
+impl RunStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RunStatus::Success => "success",
+            RunStatus::Failed => "failed",
+            RunStatus::Cancelled => "cancelled",
+        }
+    }
 }
 
 impl RunReport {
     pub fn new(pipeline_id: impl Into<String>) -> Self {
         Self {
             pipeline_id: pipeline_id.into(),
+            run_id: new_run_id(),
+            resumed_from: None,
             status: RunStatus::Success,
             started_at: iso_now(),
             duration_ms: 0,
             steps: Vec::new(),
+            outputs: BTreeMap::new(),
+            error: None,
         }
     }
 
+    /// 标记失败（已取消时保持 Cancelled）
     pub fn fail(&mut self) {
-        self.status = RunStatus::Failed;
+        if self.status != RunStatus::Cancelled {
+            self.status = RunStatus::Failed;
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        self.status = RunStatus::Cancelled;
     }
 
     /// 首个失败步骤 `(step_id, detail)`
     pub fn first_fail(&self) -> Option<(&str, &str)> {
         self.steps.iter().find_map(|step| {
-            if step.status == StepStatus::Failed {
+            if step.status.is_failure() {
                 step.error.as_deref().map(|err| (step.id.as_str(), err))
             } else {
                 None
@@ -81,12 +202,16 @@ impl RunReport {
 
     /// CLI bail 用错误
     pub fn into_err(&self) -> anyhow::Error {
-        if let Some((step, detail)) = self.first_fail() {
+        if self.status == RunStatus::Cancelled {
+            anyhow::anyhow!("Pipeline 已取消")
+        } else if let Some((step, detail)) = self.first_fail() {
             StepFail {
                 step: step.into(),
                 detail: detail.into(),
             }
             .into()
+        } else if let Some(err) = &self.error {
+            anyhow::anyhow!("Pipeline 执行失败: {err}")
         } else {
             anyhow::anyhow!("Pipeline 执行失败")
         }
@@ -94,14 +219,28 @@ impl RunReport {
 
     /// CLI bail 用错误摘要（人类可读）
     pub fn message(&self) -> String {
-        if let Some((id, err)) = self.first_fail() {
+        if self.status == RunStatus::Cancelled {
+            "Pipeline 已取消".into()
+        } else if let Some((id, err)) = self.first_fail() {
             format!("步骤 {id} 失败: {err}")
+        } else if let Some(err) = &self.error {
+            format!("Pipeline 执行失败: {err}")
         } else {
             "Pipeline 执行失败".into()
         }
     }
 }
 
+/// 按时间生成的运行 ID（可排序），带随机后缀以免同一毫秒启动的运行互相覆盖，如 `20260710-150000-123-3f9a1c`
+pub(crate) fn new_run_id() -> String {
+    let suffix = uuid::Uuid::new_v4().simple().to_string();
+    format!(
+        "{}-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S-%3f"),
+        &suffix[..6]
+    )
+}
+
 pub fn iso_now() -> String {
     use chrono::Utc;
     Utc::now().to_rfc3339()
@@ -128,16 +267,83 @@ mod tests {
             id: "copy_cache".into(),
             module: "copy".into(),
             status: StepStatus::Failed,
+            phase: StepPhase::Main,
             artifact: None,
             items: 0,
             duration_ms: 1,
             error: Some("源路径不存在".into()),
+            attempts: Vec::new(),
+            pipeline: None,
+            started_ms: None,
+            layer: None,
         });
         assert_eq!(
             report.message(),
             "步骤 copy_cache 失败: 源路径不存在"
         );
     }
+
+    #[test]
+    fn timed_out_counts_as_failure() {
+        let mut report = RunReport::new("demo");
+        report.steps.push(StepReport {
+            id: "slow".into(),
+            module: "exec".into(),
+            status: StepStatus::TimedOut,
+            phase: StepPhase::Main,
+            artifact: None,
+            items: 0,
+            duration_ms: 10,
+            error: Some("步骤执行超时（10 ms）".into()),
+            attempts: Vec::new(),
+            pipeline: None,
+            started_ms: None,
+            layer: None,
+        });
+        assert_eq!(report.first_fail().map(|(id, _)| id), Some("slow"));
+        assert_eq!(
+            serde_json::to_value(StepStatus::TimedOut).unwrap(),
+            "timed_out"
+        );
+    }
+
+    #[test]
+    fn cancel_is_not_overridden_by_fail() {
+        let mut report = RunReport::new("demo");
+        report.cancel();
+        report.fail();
+        assert_eq!(report.status, RunStatus::Cancelled);
+        assert_eq!(report.message(), "Pipeline 已取消");
+        assert!(StepStatus::Cancelled.is_failure());
+        assert_eq!(
+            serde_json::to_value(RunStatus::Cancelled).unwrap(),
+            "cancelled"
+        );
+    }
+
+    #[test]
+    fn tolerated_failure_is_not_first_fail() {
+        let mut report = RunReport::new("demo");
+        report.steps.push(StepReport {
+            id: "lint".into(),
+            module: "exec".into(),
+            status: StepStatus::FailedTolerated,
+            phase: StepPhase::Main,
+            artifact: None,
+            items: 0,
+            duration_ms: 1,
+            error: Some("exit 1".into()),
+            attempts: Vec::new(),
+            pipeline: None,
+            started_ms: None,
+            layer: None,
+        });
+        assert_eq!(report.first_fail(), None);
+        assert_eq!(report.status, RunStatus::Success);
+        let json = serde_json::to_value(&report.steps[0]).unwrap();
+        assert_eq!(json["status"], "failed_tolerated");
+        assert!(json.get("phase").is_none());
+    }
 }
 
 pub fn validate_errors_json(errors: &[String]) -> Value {
@@ -0,0 +1,502 @@
+//! `--report-file` 的输出格式：JSON（RunReport 原样）、JUnit XML（步骤 → testcase，供 CI 面板）、
+//! HTML（自包含页面：按层的步骤时间线、产物与错误）
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use super::report::{RunReport, StepPhase, StepReport, StepStatus, write_report};
+
+/// 报告格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    Json,
+    Junit,
+    Html,
+}
+
+impl ReportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Json => "json",
+            ReportFormat::Junit => "xml",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+/// 按格式写出报告：未指定时为 JSON；重复的格式只写一次；单一格式写入 `path`，
+/// 多种格式时按扩展名分别写入（如 report.xml / report.html）
+pub fn write(path: &Path, formats: &[ReportFormat], report: &RunReport) -> Result<()> {
+    let mut unique = Vec::new();
+    for &format in formats {
+        if !unique.contains(&format) {
+            unique.push(format);
+        }
+    }
+    if unique.is_empty() {
+        unique.push(ReportFormat::Json);
+    }
+    for &format in &unique {
+        let target = if unique.len() == 1 {
+            path.to_path_buf()
+        } else {
+            path.with_extension(format.extension())
+        };
+        match format {
+            ReportFormat::Json => write_report(&target, report)?,
+            ReportFormat::Junit => write_text(&target, &junit(report))?,
+            ReportFormat::Html => write_text(&target, &html(report))?,
+        }
+    }
+    Ok(())
+}
+
+fn write_text(path: &Path, text: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, text).with_context(|| format!("写入报告失败: {}", path.display()))
+}
+
+/// JUnit XML：每个 RunReport（含嵌套子 Pipeline）为一个 testsuite，步骤为 testcase
+pub fn junit(report: &RunReport) -> String {
+    let mut suites = Vec::new();
+    collect_suites(&report.pipeline_id, report, &mut suites);
+    let count = |pred: fn(StepStatus) -> bool| {
+        suites
+            .iter()
+            .flat_map(|(_, r)| &r.steps)
+            .filter(|s| pred(s.status))
+            .count()
+    };
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        r#"<testsuites name="{}" tests="{}" failures="{}" skipped="{}" time="{}">"#,
+        escape(&report.pipeline_id),
+        count(|_| true),
+        count(StepStatus::is_failure),
+        count(|s| s == StepStatus::Skipped),
+        seconds(report.duration_ms)
+    );
+    for (name, run) in &suites {
+        let failures = run.steps.iter().filter(|s| s.status.is_failure()).count();
+        let skipped = run
+            .steps
+            .iter()
+            .filter(|s| s.status == StepStatus::Skipped)
+            .count();
+        let _ = writeln!(
+            out,
+            r#"  <testsuite name="{}" id="{}" tests="{}" failures="{}" skipped="{}" time="{}" timestamp="{}">"#,
+            escape(name),
+            escape(&run.run_id),
+            run.steps.len(),
+            failures,
+            skipped,
+            seconds(run.duration_ms),
+            escape(&run.started_at)
+        );
+        let _ = writeln!(out, "    <properties>");
+        let _ = writeln!(
+            out,
+            r#"      <property name="status" value="{}"/>"#,
+            run.status.as_str()
+        );
+        if let Some(ref error) = run.error {
+            let _ = writeln!(
+                out,
+                r#"      <property name="error" value="{}"/>"#,
+                escape(error)
+            );
+        }
+        for (key, value) in &run.outputs {
+            let _ = writeln!(
+                out,
+                r#"      <property name="outputs.{}" value="{}"/>"#,
+                escape(key),
+                escape(value)
+            );
+        }
+        let _ = writeln!(out, "    </properties>");
+        for step in &run.steps {
+            junit_case(&mut out, name, step);
+        }
+        let _ = writeln!(out, "  </testsuite>");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+fn collect_suites<'a>(name: &str, report: &'a RunReport, out: &mut Vec<(String, &'a RunReport)>) {
+    out.push((name.to_string(), report));
+    for step in &report.steps {
+        if let Some(ref child) = step.pipeline {
+            collect_suites(&format!("{name}.{}", step.id), child, out);
+        }
+    }
+}
+
+fn junit_case(out: &mut String, suite: &str, step: &StepReport) {
+    let _ = write!(
+        out,
+        r#"    <testcase name="{}" classname="{}.{}" time="{}""#,
+        escape(&step.id),
+        escape(suite),
+        step.phase.as_str(),
+        seconds(step.duration_ms)
+    );
+    let error = step.error.as_deref().unwrap_or_default();
+    let mut body = String::new();
+    match step.status {
+        StepStatus::Failed | StepStatus::TimedOut | StepStatus::Cancelled => {
+            let _ = writeln!(
+                body,
+                r#"      <failure type="{}" message="{}">{}</failure>"#,
+                step.status.as_str(),
+                escape(first_line(error)),
+                escape(error)
+            );
+        }
+        StepStatus::Skipped => body.push_str("      <skipped/>\n"),
+        // 被容忍的失败不计为失败，错误记入 system-err
+        StepStatus::FailedTolerated if !error.is_empty() => {
+            let _ = writeln!(body, "      <system-err>{}</system-err>", escape(error));
+        }
+        _ => {}
+    }
+    let mut lines = vec![format!("module: {}", step.module)];
+    if step.status == StepStatus::Cached {
+        lines.push("cached: true".into());
+    }
+    if let Some(path) = step.artifact.as_ref().and_then(|a| a.path.as_ref()) {
+        lines.push(format!("artifact: {}", path.display()));
+    }
+    for attempt in &step.attempts {
+        lines.push(format!(
+            "attempt {}: {} ({} ms){}",
+            attempt.attempt,
+            attempt.status.as_str(),
+            attempt.duration_ms,
+            attempt
+                .error
+                .as_deref()
+                .map(|e| format!(" {e}"))
+                .unwrap_or_default()
+        ));
+    }
+    let _ = writeln!(
+        body,
+        "      <system-out>{}</system-out>",
+        escape(&lines.join("\n"))
+    );
+    let _ = writeln!(out, ">\n{body}    </testcase>");
+}
+
+/// 自包含 HTML：按阶段与层排列的步骤时间线（甘特图）及步骤明细
+pub fn html(report: &RunReport) -> String {
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="utf-8">
+<title>{title} · {run_id}</title>
+<style>{STYLE}</style>
+</head>
+<body>
+"#,
+        title = escape(&report.pipeline_id),
+        run_id = escape(&report.run_id),
+    );
+    html_run(&mut out, report, 2);
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+const STYLE: &str = "
+body{font-family:system-ui,-apple-system,'Segoe UI',sans-serif;margin:24px;color:#1f2328;background:#fff}
+h2,h3,h4{margin:16px 0 8px}
+.meta{color:#59636e;font-size:13px}
+.badge{display:inline-block;padding:1px 8px;border-radius:10px;font-size:12px;color:#fff;vertical-align:middle}
+.success,.cached{background:#1a7f37}.failed,.timed_out{background:#cf222e}
+.cancelled,.failed_tolerated{background:#bf8700}.skipped{background:#8c959f}
+.timeline{border:1px solid #d1d9e0;border-radius:6px;padding:8px 12px;margin:8px 0}
+.layer{font-size:12px;color:#59636e;margin:8px 0 2px}
+.row{display:flex;align-items:center;height:22px}
+.label{width:220px;flex:none;font-size:13px;overflow:hidden;text-overflow:ellipsis;white-space:nowrap}
+.track{position:relative;flex:1;height:14px;background:#f6f8fa;border-radius:3px}
+.bar{position:absolute;top:0;height:14px;min-width:2px;border-radius:3px}
+table{border-collapse:collapse;width:100%;font-size:13px;margin:8px 0}
+th,td{border:1px solid #d1d9e0;padding:4px 8px;text-align:left;vertical-align:top}
+th{background:#f6f8fa}
+td.num{text-align:right;white-space:nowrap}
+pre{margin:0;white-space:pre-wrap;word-break:break-all;color:#cf222e}
+.nested{margin-left:16px;padding-left:12px;border-left:3px solid #d1d9e0}
+";
+
+fn html_run(out: &mut String, report: &RunReport, level: usize) {
+    let status = report.status.as_str();
+    let _ = writeln!(
+        out,
+        r#"<h{level}>{} <span class="badge {status}">{status}</span></h{level}>"#,
+        escape(&report.pipeline_id)
+    );
+    let mut meta = format!(
+        "运行 ID {} · 开始 {} · 耗时 {} ms · {} 步",
+        escape(&report.run_id),
+        escape(&report.started_at),
+        report.duration_ms,
+        report.steps.len()
+    );
+    if let Some(ref from) = report.resumed_from {
+        let _ = write!(meta, " · 恢复自 {}", escape(from));
+    }
+    let _ = writeln!(out, r#"<p class="meta">{meta}</p>"#);
+    if let Some(ref error) = report.error {
+        let _ = writeln!(out, "<pre>{}</pre>", escape(error));
+    }
+
+    if !report.outputs.is_empty() {
+        out.push_str("<table><tr><th>输出</th><th>值</th></tr>\n");
+        for (key, value) in &report.outputs {
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td>{}</td></tr>",
+                escape(key),
+                escape(value)
+            );
+        }
+        out.push_str("</table>\n");
+    }
+
+    html_timeline(out, report);
+    html_steps(out, report);
+
+    for step in &report.steps {
+        if let Some(ref child) = step.pipeline {
+            let _ = writeln!(
+                out,
+                r#"<div class="nested"><p class="meta">步骤 {} 调用的子 Pipeline</p>"#,
+                escape(&step.id)
+            );
+            html_run(out, child, (level + 1).min(4));
+            out.push_str("</div>\n");
+        }
+    }
+}
+
+fn html_timeline(out: &mut String, report: &RunReport) {
+    if report.steps.is_empty() {
+        return;
+    }
+    // 旧报告缺少启动时刻时按报告顺序依次排列
+    let mut cursor = 0;
+    let spans: Vec<(u64, u64)> = report
+        .steps
+        .iter()
+        .map(|s| {
+            let start = s.started_ms.unwrap_or(cursor);
+            cursor = start + s.duration_ms;
+            (start, s.duration_ms)
+        })
+        .collect();
+    let total = spans
+        .iter()
+        .map(|(start, ms)| start + ms)
+        .max()
+        .unwrap_or(0)
+        .max(report.duration_ms)
+        .max(1) as f64;
+
+    out.push_str(r#"<div class="timeline">"#);
+    out.push('\n');
+    for phase in [StepPhase::Main, StepPhase::OnFailure, StepPhase::Finally] {
+        let mut rows: Vec<(usize, &StepReport, (u64, u64))> = report
+            .steps
+            .iter()
+            .zip(&spans)
+            .filter(|(s, _)| s.phase == phase)
+            .map(|(s, span)| (s.layer.unwrap_or(0), s, *span))
+            .collect();
+        if rows.is_empty() {
+            continue;
+        }
+        rows.sort_by_key(|(layer, _, (start, _))| (*layer, *start));
+        if phase != StepPhase::Main {
+            let _ = writeln!(out, "<h4>{}</h4>", phase.as_str());
+        }
+        let mut current = None;
+        for (layer, step, (start, ms)) in rows {
+            if current != Some(layer) {
+                let _ = writeln!(out, r#"<div class="layer">第 {} 层</div>"#, layer + 1);
+                current = Some(layer);
+            }
+            let _ = writeln!(
+                out,
+                r#"<div class="row"><div class="label" title="{id}">{id}</div><div class="track"><div class="bar {status}" style="left:{left:.2}%;width:{width:.2}%" title="{id} · {status} · {start}–{end} ms"></div></div></div>"#,
+                id = escape(&step.id),
+                status = step.status.as_str(),
+                left = start as f64 / total * 100.0,
+                width = ms as f64 / total * 100.0,
+                end = start + ms,
+            );
+        }
+    }
+    out.push_str("</div>\n");
+}
+
+fn html_steps(out: &mut String, report: &RunReport) {
+    out.push_str(
+        "<table><tr><th>步骤</th><th>模块</th><th>阶段</th><th>状态</th><th>耗时</th><th>产物</th><th>错误</th></tr>\n",
+    );
+    for step in &report.steps {
+        let status = step.status.as_str();
+        let artifact = step
+            .artifact
+            .as_ref()
+            .and_then(|a| a.path.as_ref())
+            .map(|p| escape(&p.display().to_string()))
+            .unwrap_or_default();
+        let mut error = step
+            .error
+            .as_deref()
+            .map(|e| format!("<pre>{}</pre>", escape(e)))
+            .unwrap_or_default();
+        if step.attempts.len() > 1 {
+            let _ = write!(
+                error,
+                r#"<span class="meta">共 {} 次尝试</span>"#,
+                step.attempts.len()
+            );
+        }
+        let _ = writeln!(
+            out,
+            r#"<tr><td>{}</td><td>{}</td><td>{}</td><td><span class="badge {status}">{status}</span></td><td class="num">{} ms</td><td>{artifact}</td><td>{error}</td></tr>"#,
+            escape(&step.id),
+            escape(&step.module),
+            step.phase.as_str(),
+            step.duration_ms,
+        );
+    }
+    out.push_str("</table>\n");
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+/// XML / HTML 转义；整段去除 ANSI 转义序列（CSI `ESC [ … m` 等），其余 XML 不允许的控制字符直接丢弃
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => {
+                if chars.next_if_eq(&'[').is_some() {
+                    // 跳过参数与中间字节（0x20–0x3F），连同终止字节一并丢弃
+                    chars.find(|c| !('\u{20}'..='\u{3f}').contains(c));
+                } else {
+                    chars.next_if(|c| ('\u{40}'..='\u{5f}').contains(c));
+                }
+            }
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> RunReport {
+        serde_json::from_value(json!({
+            "pipeline_id": "build",
+            "run_id": "20261018-100000-001",
+            "status": "failed",
+            "started_at": "2026-10-18T02:00:00Z",
+            "duration_ms": 1500,
+            "steps": [
+                { "id": "copy", "module": "copy", "status": "success", "items": 3,
+                  "duration_ms": 400, "started_ms": 0, "layer": 0,
+                  "artifact": { "path": "/tmp/out" } },
+                { "id": "lint", "module": "exec", "status": "skipped", "items": 0,
+                  "duration_ms": 0, "started_ms": 400, "layer": 1 },
+                { "id": "pack", "module": "compression", "status": "failed", "items": 0,
+                  "duration_ms": 1000, "started_ms": 400, "layer": 1,
+                  "error": "exit <1> & \u{1b}[31mboom\u{1b}[0m" },
+                { "id": "clean", "module": "scrub", "status": "success", "items": 0,
+                  "phase": "finally", "duration_ms": 100, "started_ms": 1400, "layer": 0 }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn junit_maps_steps_to_testcases() {
+        let xml = junit(&sample());
+        assert!(xml.contains(r#"<testsuite name="build" id="20261018-100000-001" tests="4" failures="1" skipped="1" time="1.500""#), "{xml}");
+        assert!(xml.contains(r#"<testcase name="pack" classname="build.main" time="1.000">"#));
+        assert!(xml.contains(r#"<failure type="failed" message="exit &lt;1&gt; &amp; boom">"#));
+        assert!(xml.contains(r#"<testcase name="clean" classname="build.finally""#));
+        assert!(xml.contains("<skipped/>"));
+        assert!(!xml.contains('\u{1b}'));
+    }
+
+    #[test]
+    fn html_lays_out_timeline_by_layer() {
+        let page = html(&sample());
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("第 2 层"));
+        assert!(
+            page.contains(r#"class="bar failed" style="left:26.67%;width:66.67%""#),
+            "{page}"
+        );
+        assert!(page.contains("<h4>finally</h4>"));
+        assert!(page.contains("/tmp/out"));
+        assert!(page.contains("exit &lt;1&gt; &amp; boom"));
+        assert!(!page.contains("[31m"));
+    }
+
+    #[test]
+    fn multiple_formats_are_written_by_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+        write(
+            &path,
+            &[ReportFormat::Json, ReportFormat::Junit, ReportFormat::Html],
+            &sample(),
+        )
+        .unwrap();
+        for name in ["report.json", "report.xml", "report.html"] {
+            assert!(dir.path().join(name).exists(), "{name}");
+        }
+        let dup = dir.path().join("dup.json");
+        write(&dup, &[ReportFormat::Json, ReportFormat::Json], &sample()).unwrap();
+        assert!(dup.exists());
+        let single = dir.path().join("ci.txt");
+        write(&single, &[ReportFormat::Junit], &sample()).unwrap();
+        assert!(
+            std::fs::read_to_string(single)
+                .unwrap()
+                .starts_with("<?xml")
+        );
+    }
+}
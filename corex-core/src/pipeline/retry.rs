@@ -0,0 +1,127 @@
+//! 重试策略：退避时长（fixed / exponential、上限与抖动）与按错误类别 / 正则判断是否重试
+
+use anyhow::{Result, bail};
+use regex::Regex;
+
+use super::config::{RetryConfig, RetryStrategy};
+use super::report::StepStatus;
+
+impl RetryConfig {
+    /// 第 `failures` 次失败后的等待时长
+    pub fn delay_ms(&self, failures: u32) -> u64 {
+        let base = match self.strategy {
+            RetryStrategy::Fixed => self.backoff_ms,
+            RetryStrategy::Exponential => {
+                let factor = 1u64
+                    .checked_shl(failures.saturating_sub(1))
+                    .unwrap_or(u64::MAX);
+                self.backoff_ms.saturating_mul(factor)
+            }
+        };
+        let delay = self.max_backoff_ms.map_or(base, |max| base.min(max));
+        if self.jitter && delay > 1 {
+            rand::random_range(delay / 2..=delay)
+        } else {
+            delay
+        }
+    }
+
+    /// 编译 retry_on / no_retry_on 规则（正则仅在此编译一次）
+    pub fn policy(&self) -> Result<RetryPolicy> {
+        let compile = |rules: &[String]| -> Result<Vec<Rule>> {
+            rules.iter().map(|r| Rule::parse(r)).collect()
+        };
+        Ok(RetryPolicy {
+            retry_on: compile(&self.retry_on)?,
+            no_retry_on: compile(&self.no_retry_on)?,
+        })
+    }
+
+    /// 校验 retry_on / no_retry_on 中的正则
+    pub fn validate(&self) -> Result<()> {
+        self.policy().map(|_| ())
+    }
+}
+
+/// 已编译的重试条件
+#[derive(Debug)]
+pub struct RetryPolicy {
+    retry_on: Vec<Rule>,
+    no_retry_on: Vec<Rule>,
+}
+
+impl RetryPolicy {
+    /// 失败是否可重试：命中 retry_on（为空视为全部命中）且不命中 no_retry_on
+    pub fn should_retry(&self, status: StepStatus, error: Option<&str>) -> bool {
+        let hit = |rules: &[Rule]| rules.iter().any(|rule| rule.matches(status, error));
+        (self.retry_on.is_empty() || hit(&self.retry_on)) && !hit(&self.no_retry_on)
+    }
+}
+
+/// 单条重试条件：错误类别或匹配错误信息的正则
+#[derive(Debug)]
+enum Rule {
+    Failed,
+    TimedOut,
+    Pattern(Regex),
+}
+
+impl Rule {
+    fn parse(rule: &str) -> Result<Self> {
+        Ok(match rule {
+            "failed" => Rule::Failed,
+            "timed_out" => Rule::TimedOut,
+            pattern => match Regex::new(pattern) {
+                Ok(re) => Rule::Pattern(re),
+                Err(e) => bail!("retry 条件 '{rule}' 不是有效的正则: {e}"),
+            },
+        })
+    }
+
+    fn matches(&self, status: StepStatus, error: Option<&str>) -> bool {
+        match self {
+            Rule::Failed => status == StepStatus::Failed,
+            Rule::TimedOut => status == StepStatus::TimedOut,
+            Rule::Pattern(re) => error.is_some_and(|e| re.is_match(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(value: serde_json::Value) -> RetryConfig {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn exponential_backoff_is_capped_and_jittered() {
+        let retry =
+            config(json!({ "backoff_ms": 100, "strategy": "exponential", "max_backoff_ms": 350 }));
+        let delays: Vec<u64> = (1..=4).map(|n| retry.delay_ms(n)).collect();
+        assert_eq!(delays, [100, 200, 350, 350]);
+        assert_eq!(config(json!({ "backoff_ms": 100 })).delay_ms(3), 100);
+
+        let jittered = config(json!({ "backoff_ms": 1000, "jitter": true }));
+        assert!((500..=1000).contains(&jittered.delay_ms(1)));
+    }
+
+    #[test]
+    fn retry_on_filters_by_category_and_pattern() {
+        let retry = config(json!({
+            "retry_on": ["timed_out", "(?i)503|connection reset"],
+            "no_retry_on": ["401"]
+        }))
+        .policy()
+        .unwrap();
+        assert!(retry.should_retry(StepStatus::TimedOut, Some("步骤执行超时（10 ms）")));
+        assert!(retry.should_retry(StepStatus::Failed, Some("HTTP 503 Service Unavailable")));
+        assert!(!retry.should_retry(StepStatus::Failed, Some("HTTP 503 401")));
+        assert!(!retry.should_retry(StepStatus::Failed, Some("exit 1")));
+        let any = config(json!({})).policy().unwrap();
+        assert!(any.should_retry(StepStatus::Failed, None));
+        assert!(config(json!({ "retry_on": ["("] })).validate().is_err());
+    }
+}
@@ -1,32 +1,63 @@
-use anyhow::Result;
+use std::io::IsTerminal;
+
+use anyhow::{Context, Result};
 use crossterm::style::Stylize;
 use dialoguer::theme::ColorfulTheme;
+use serde::Deserialize;
+use serde_json::{Map, Value};
 
 use crate::runtime::{self, merge_variables};
 
 use super::config::{
-    PipelineArgs, PipelineConfig, ValidateReport, find_config_path, load_config, validate_config,
+    PipelineArgs, PipelineCommand, PipelineConfig, ValidateReport, find_config_path, load_configs,
+    validate_config,
 };
-use super::step_params::redact_sensitive_params;
+use super::context::PipelineContext;
+use super::diagram::{self, GraphFormat};
+use super::events::{self, Event};
+use super::expand::expand_pipeline;
+use super::history::{self, Trigger};
+use super::inputs;
+use super::lint;
+use super::lock;
+use super::plan;
+use super::report::{RunReport, new_run_id};
+use super::state;
 use super::trigger::{self, label};
 
 /// `corex pipeline` 命令处理
 pub fn run(args: &PipelineArgs) -> Result<()> {
+    match args.command {
+        Some(PipelineCommand::History {
+            ref pipeline,
+            status,
+            limit,
+        }) => return history::list(pipeline.as_deref(), status, limit),
+        Some(PipelineCommand::Show { ref run_id }) => return history::show(run_id),
+        _ => {}
+    }
+
     let config_path = args
         .config
         .as_ref()
         .map(std::path::PathBuf::from)
         .unwrap_or_else(find_config_path);
-
-    if !config_path.exists() {
-        anyhow::bail!(
-            "配置文件未找到：{}，请先运行 `corex schedule generate` 生成配置",
-            config_path.display()
-        );
+    let mut config = load_configs(args.config.as_deref(), args.profile.as_deref())?;
+    if let Some(PipelineCommand::Lint {
+        ref pipeline,
+        ref deny,
+    }) = args.command
+    {
+        return lint::run(&config, pipeline.as_deref(), deny, &args.define);
     }
-
-    let mut config = load_config(&config_path)?;
     config.variables = merge_variables(config.variables.clone(), &args.define);
+    // -D / COREX_VAR_* 同样覆盖 Pipeline 级 variables
+    for pipeline in &mut config.pipelines {
+        if !pipeline.variables.is_empty() {
+            pipeline.variables =
+                merge_variables(std::mem::take(&mut pipeline.variables), &args.define);
+        }
+    }
 
     if let Err(e) = validate_config(&config) {
         if runtime::is_json_output() {
@@ -54,38 +85,118 @@ pub fn run(args: &PipelineArgs) -> Result<()> {
         return Ok(());
     }
 
-    let pipeline = select_pipeline(&config, args)?;
+    if let Some(ref run_id) = args.resume {
+        let previous = state::load(run_id)?;
+        let pipeline_id = &previous.report.pipeline_id;
+        if args.id.as_ref().is_some_and(|id| id != pipeline_id) {
+            anyhow::bail!("运行 {run_id} 属于 Pipeline '{pipeline_id}'，与 --id 不一致");
+        }
+        let pipeline = config
+            .pipelines
+            .iter()
+            .find(|p| p.id == *pipeline_id)
+            .ok_or_else(|| anyhow::anyhow!("未找到 Pipeline: {pipeline_id}"))?;
+        return trigger::resume(pipeline, &config, previous, args);
+    }
+
+    if let Some(PipelineCommand::Graph {
+        ref pipeline,
+        output,
+    }) = args.command
+    {
+        let pipeline = match pipeline {
+            Some(id) => find_pipeline(&config, id)?,
+            None => select_pipeline(&config, args)?,
+        };
+        return print_graph(&config, pipeline, output);
+    }
+
+    let id = select_pipeline(&config, args)?.id.clone();
+    prompt_inputs(&mut config, &id, args)?;
+    let pipeline = find_pipeline(&config, &id)?;
 
     if args.dry_run {
-        dry_run_pipeline(pipeline);
-        return Ok(());
+        return dry_run_pipeline(&config, pipeline);
     }
 
-    if runtime::is_json_output() && !args.once && pipeline.triggers().any() {
+    if runtime::is_json_output()
+        && !runtime::is_event_stream()
+        && !args.once
+        && pipeline.triggers().any()
+    {
         anyhow::bail!("守护模式不支持 --format json，请使用 --once");
     }
 
     trigger::run(pipeline, &config, &config_path, args)
 }
 
-pub fn run_pipeline(pipeline: &PipelineConfig, ctx: &mut super::context::PipelineContext) -> Result<()> {
-    let report = super::orchestrator::run_pipeline(pipeline, ctx)?;
-    if report.status == super::report::RunStatus::Failed {
+/// 执行并记入运行历史；失败时返回首个失败步骤的错误
+pub fn run_pipeline(
+    pipeline: &PipelineConfig,
+    ctx: &mut PipelineContext,
+    trigger: Trigger,
+) -> Result<()> {
+    let run_id = new_run_id();
+    if trigger != Trigger::Manual {
+        events::emit(
+            &run_id,
+            Event::Triggered {
+                pipeline_id: &pipeline.id,
+                trigger,
+            },
+        );
+    }
+    let report = super::orchestrator::run_pipeline_with_id(pipeline, ctx, run_id)?;
+    state::record(trigger, ctx, &report);
+    if report.status != super::report::RunStatus::Success {
         return Err(report.into_err());
     }
     Ok(())
 }
 
+/// IPC `module: pipeline` 的 args
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IpcArgs {
+    /// Pipeline ID
+    pipeline: String,
+    /// 配置文件路径（缺省同 `corex pipeline`）
+    #[serde(default)]
+    config: Option<String>,
+    #[serde(default)]
+    profile: Option<String>,
+    /// 带类型的 inputs，如 `{ "env": "prod", "dry": true }`
+    #[serde(default)]
+    inputs: Map<String, Value>,
+}
+
+/// 经 IPC 执行 Pipeline：inputs 按声明校验（不交互询问），记入运行历史并返回 RunReport
+pub fn run_ipc(args: Value) -> Result<RunReport> {
+    let args: IpcArgs = serde_json::from_value(args).context("pipeline args 无效")?;
+    let mut config = load_configs(args.config.as_deref(), args.profile.as_deref())?;
+    validate_config(&config)?;
+    let pipeline = config
+        .pipelines
+        .iter_mut()
+        .find(|p| p.id == args.pipeline)
+        .ok_or_else(|| anyhow::anyhow!("未找到 Pipeline: {}", args.pipeline))?;
+    let typed = inputs::from_json(pipeline, &args.inputs)?;
+    pipeline.variables.extend(typed);
+
+    let pipeline = find_pipeline(&config, &args.pipeline)?;
+    let _lock = lock::hold(pipeline)?;
+    let mut ctx = PipelineContext::from_config(&config);
+    let report = super::orchestrator::run_pipeline_with_id(pipeline, &mut ctx, new_run_id())?;
+    state::record(Trigger::Ipc, &ctx, &report);
+    Ok(report)
+}
+
 fn select_pipeline<'a>(
     config: &'a super::config::PipelinesConfig,
     args: &PipelineArgs,
 ) -> Result<&'a PipelineConfig> {
     if let Some(ref id) = args.id {
-        return config
-            .pipelines
-            .iter()
-            .find(|p| p.id == *id)
-            .ok_or_else(|| anyhow::anyhow!("未找到 Pipeline: {id}"));
+        return find_pipeline(config, id);
     }
     if config.pipelines.len() == 1 {
         return Ok(&config.pipelines[0]);
@@ -111,52 +222,66 @@ fn select_pipeline<'a>(
     Ok(&config.pipelines[idx])
 }
 
-fn dry_run_pipeline(pipeline: &PipelineConfig) {
-    if runtime::is_quiet() || runtime::is_json_output() {
-        return;
-    }
-    println!(
-        "\n  {} Dry-run：{}\n",
-        "◇".yellow().bold(),
-        pipeline
-            .description
-            .as_deref()
-            .unwrap_or(&pipeline.id)
-            .bold()
-    );
-    let badge = label(pipeline);
-    if !badge.is_empty() {
-        println!("  触发器: {badge}");
-    }
-    if let Some(ref sched) = pipeline.schedule {
-        println!("  定时调度: {sched}");
-    }
-    if let Some(ref watch) = pipeline.watch {
-        println!(
-            "  文件监听: {} (debounce {}ms)",
-            watch.paths.join(", "),
-            watch.debounce_ms
-        );
+/// 交互询问未提供的 inputs，写入该 Pipeline 的 variables；`--no-input`、非终端或 JSON 输出时跳过
+fn prompt_inputs(
+    config: &mut super::config::PipelinesConfig,
+    id: &str,
+    args: &PipelineArgs,
+) -> Result<()> {
+    if args.no_input || runtime::is_json_output() || !std::io::stdin().is_terminal() {
+        return Ok(());
     }
-    println!("  步骤数: {}\n", pipeline.steps.len());
-    for (i, step) in pipeline.steps.iter().enumerate() {
-        println!(
-            "  [{}] {} — module={}",
-            (i + 1).to_string().bold(),
-            step.id,
-            step.module,
-        );
-        if let Some(ref desc) = step.description {
-            println!("       描述: {desc}");
-        }
-        if !step.depends_on.is_empty() {
-            println!("       depends_on: {:?}", step.depends_on);
-        }
-        let display_params = redact_sensitive_params(&step.params);
-        println!(
-            "       参数: {}",
-            serde_json::to_string_pretty(&display_params).unwrap_or_default()
-        );
-        println!();
+    let Some(pipeline) = config.pipelines.iter_mut().find(|p| p.id == id) else {
+        return Ok(());
+    };
+    let answers = inputs::prompt(pipeline, &pipeline.scoped_variables(&config.variables))?;
+    pipeline.variables.extend(answers);
+    Ok(())
+}
+
+fn find_pipeline<'a>(
+    config: &'a super::config::PipelinesConfig,
+    id: &str,
+) -> Result<&'a PipelineConfig> {
+    config
+        .pipelines
+        .iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| anyhow::anyhow!("未找到 Pipeline: {id}"))
+}
+
+/// `corex pipeline graph`：按 Pipeline 变量展开 foreach / matrix 后导出 DAG
+fn print_graph(
+    config: &super::config::PipelinesConfig,
+    pipeline: &PipelineConfig,
+    format: GraphFormat,
+) -> Result<()> {
+    let mut variables = pipeline.scoped_variables(&config.variables);
+    inputs::fill_defaults(pipeline, &mut variables);
+    let ctx = PipelineContext::with_variables(variables);
+    let expanded = expand_pipeline(pipeline, &ctx)?;
+    let rendered = diagram::render(&expanded, format)?;
+    if runtime::is_json_output() {
+        runtime::state().emitter.json(&serde_json::json!({
+            "pipeline_id": pipeline.id,
+            "graph": rendered,
+        }))?;
+    } else {
+        print!("{rendered}");
+    }
+    Ok(())
+}
+
+/// `--dry-run`：输出解析变量后的执行计划（`--format json` 输出 [`DryRunPlan`](super::plan::DryRunPlan)）
+fn dry_run_pipeline(
+    config: &super::config::PipelinesConfig,
+    pipeline: &PipelineConfig,
+) -> Result<()> {
+    let plan = plan::plan(pipeline, pipeline.scoped_variables(&config.variables))?;
+    if runtime::is_json_output() {
+        runtime::state().emitter.json(&plan)?;
+    } else if !runtime::is_quiet() {
+        plan.print(pipeline);
     }
+    Ok(())
 }
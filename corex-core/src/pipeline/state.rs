@@ -0,0 +1,265 @@
+//! 运行记录持久化：每次运行（手动 / cron / watch / IPC）按运行 ID 保存触发来源、变量与 RunReport，
+//! 供 `--resume` 恢复与 `corex pipeline history` / `show` 查询
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crossterm::style::Stylize;
+
+use crate::runtime;
+use crate::utils::redact;
+
+use super::context::PipelineContext;
+use super::history::Trigger;
+use super::report::{RunReport, RunStatus, StepReport, StepStatus};
+
+/// 单次运行记录（`<runs>/<pipeline>/<run-id>.json`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunState {
+    #[serde(default)]
+    pub trigger: Trigger,
+    /// 已解析的 variables（引用 `${secret.*}` 的变量保留原始表达式，不落盘明文）
+    pub variables: HashMap<String, String>,
+    pub report: RunReport,
+}
+
+impl RunState {
+    pub fn new(trigger: Trigger, ctx: &PipelineContext, report: &RunReport) -> Self {
+        Self {
+            trigger,
+            variables: ctx
+                .variables
+                .iter()
+                .map(|(k, raw)| {
+                    let resolved = ctx.parse(&format!("${{var.{k}}}"));
+                    if redact::contains_secret(&resolved) {
+                        (k.clone(), raw.clone())
+                    } else {
+                        (k.clone(), resolved)
+                    }
+                })
+                .collect(),
+            report: report.clone(),
+        }
+    }
+
+    /// 恢复时可沿用的主步骤（成功 / 缓存 / 跳过 / 已容忍）；失败步骤及其后续重新执行
+    pub fn completed_steps(&self) -> Result<Vec<StepReport>> {
+        if self.report.status == RunStatus::Success {
+            bail!("运行 {} 已成功，无需恢复", self.report.run_id);
+        }
+        let failed: HashSet<&str> = self
+            .report
+            .steps
+            .iter()
+            .filter(|s| s.status.is_failure())
+            .map(|s| s.id.as_str())
+            .collect();
+        Ok(self
+            .report
+            .steps
+            .iter()
+            .filter(|s| s.phase.is_main() && !failed.contains(s.id.as_str()))
+            .filter(|s| {
+                matches!(
+                    s.status,
+                    StepStatus::Success
+                        | StepStatus::Cached
+                        | StepStatus::Skipped
+                        | StepStatus::FailedTolerated
+                )
+            })
+            .cloned()
+            .collect())
+    }
+}
+
+/// 运行历史目录（同时保存 `--resume` 所需状态）：`COREX_HISTORY_DIR` 或 `~/.corex/history`
+pub fn history_dir() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("COREX_HISTORY_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    Ok(dirs::home_dir()
+        .context("无法获取用户目录")?
+        .join(".corex")
+        .join("history"))
+}
+
+/// 每条 Pipeline 最多保留的运行记录数
+pub const KEEP_RUNS: usize = 100;
+
+/// 运行记录最长保留天数
+pub const KEEP_DAYS: i64 = 30;
+
+/// 校验运行 ID（`YYYYMMDD-HHMMSS-fff`，可带 6 位十六进制后缀），避免拼接路径时越出运行目录
+pub fn check_run_id(run_id: &str) -> Result<()> {
+    let digits = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+    let hex = |s: &str| s.len() == 6 && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+    let valid = match run_id.split('-').collect::<Vec<_>>()[..] {
+        [date, time, millis] => digits(date, 8) && digits(time, 6) && digits(millis, 3),
+        [date, time, millis, suffix] => {
+            digits(date, 8) && digits(time, 6) && digits(millis, 3) && hex(suffix)
+        }
+        _ => false,
+    };
+    if !valid {
+        bail!("无效的运行 ID: {run_id}（应为 YYYYMMDD-HHMMSS-fff 格式）");
+    }
+    Ok(())
+}
+
+/// Pipeline 运行记录的子目录名：ASCII 字母、数字、`-`、`_` 原样保留，其余字节编码为 `%XX`，
+/// 不同 ID 不会映射到同一目录，也不会越出运行目录
+pub(crate) fn encode_id(id: &str) -> String {
+    id.bytes()
+        .map(|b| match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// 保存运行记录；失败仅提示，不影响运行结果。返回是否保存成功
+pub fn record(trigger: Trigger, ctx: &PipelineContext, report: &RunReport) -> bool {
+    let saved = save(&RunState::new(trigger, ctx, report));
+    if let Err(ref e) = saved
+        && !runtime::is_quiet()
+    {
+        eprintln!("  {} 保存运行记录失败: {}", "⚠".yellow(), e);
+    }
+    saved.is_ok()
+}
+
+/// 保存运行记录，记为最近一次运行（`last`），并按保留策略清理
+pub fn save(state: &RunState) -> Result<PathBuf> {
+    save_in(&history_dir()?, state)
+}
+
+pub(crate) fn save_in(root: &Path, state: &RunState) -> Result<PathBuf> {
+    let run_id = &state.report.run_id;
+    check_run_id(run_id)?;
+    let dir = root.join(encode_id(&state.report.pipeline_id));
+    fs::create_dir_all(&dir).with_context(|| format!("创建运行记录目录失败: {}", dir.display()))?;
+    let path = dir.join(format!("{run_id}.json"));
+    fs::write(&path, serde_json::to_string_pretty(state)?)
+        .with_context(|| format!("写入运行记录失败: {}", path.display()))?;
+    fs::write(root.join("last"), run_id)?;
+    prune(&dir, KEEP_RUNS, &cutoff())?;
+    Ok(path)
+}
+
+/// 读取运行记录；`last` 表示最近一次运行
+pub fn load(run_id: &str) -> Result<RunState> {
+    let root = history_dir()?;
+    let run_id = if run_id == "last" {
+        fs::read_to_string(root.join("last"))
+            .context("尚无运行记录，无法使用 --resume last")?
+            .trim()
+            .to_string()
+    } else {
+        run_id.to_string()
+    };
+    read(&find(&root, &run_id)?)
+}
+
+/// 按运行 ID 查找记录文件（ID 须通过 [`check_run_id`]）
+pub(crate) fn find(root: &Path, run_id: &str) -> Result<PathBuf> {
+    check_run_id(run_id)?;
+    fs::read_dir(root)
+        .ok()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join(format!("{run_id}.json")))
+        .find(|path| path.is_file())
+        .with_context(|| format!("未找到运行记录: {run_id}"))
+}
+
+pub(crate) fn read(path: &Path) -> Result<RunState> {
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content).with_context(|| format!("运行记录已损坏: {}", path.display()))
+}
+
+/// 早于此运行 ID 前缀的记录过期（[`KEEP_DAYS`] 天前）
+pub(crate) fn cutoff() -> String {
+    (Local::now() - chrono::Duration::days(KEEP_DAYS))
+        .format("%Y%m%d-%H%M%S")
+        .to_string()
+}
+
+/// 删除超出条数上限或早于 `cutoff`（运行 ID 前缀）的记录
+pub(crate) fn prune(dir: &Path, keep: usize, cutoff: &str) -> Result<()> {
+    let runs = run_ids(dir)?;
+    let excess = runs.len().saturating_sub(keep);
+    for (i, run_id) in runs.iter().enumerate() {
+        if i < excess || run_id.as_str() < cutoff {
+            fs::remove_file(dir.join(format!("{run_id}.json")))?;
+        }
+    }
+    Ok(())
+}
+
+/// 目录中的运行 ID（按时间升序）；文件名不是有效运行 ID 的忽略
+pub(crate) fn run_ids(dir: &Path) -> Result<Vec<String>> {
+    let mut ids: Vec<String> = fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == "json").then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .filter(|id| check_run_id(id).is_ok())
+        .collect();
+    ids.sort();
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_ids_are_unique_and_validated() {
+        let a = super::super::report::new_run_id();
+        let b = super::super::report::new_run_id();
+        assert_ne!(a, b);
+        check_run_id(&a).unwrap();
+        check_run_id("20260710-150000-123").unwrap();
+        for bad in [
+            "../../x",
+            "20260710-150000",
+            "20260710-150000-123-../x",
+            "last",
+        ] {
+            assert!(check_run_id(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn encoded_ids_stay_distinct() {
+        assert_eq!(encode_id("build-h5"), "build-h5");
+        assert_ne!(encode_id("build/h5"), encode_id("build_h5"));
+        assert_eq!(encode_id("../x"), "%2E%2E%2Fx");
+    }
+
+    #[test]
+    fn prune_keeps_recent_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        for run_id in [
+            "20200101-090000-000",
+            "20260710-090000-000",
+            "20260711-090000-000",
+            "20260712-090000-000",
+        ] {
+            fs::write(dir.path().join(format!("{run_id}.json")), "{}").unwrap();
+        }
+        prune(dir.path(), 2, "20250101-000000").unwrap();
+        assert_eq!(
+            run_ids(dir.path()).unwrap(),
+            ["20260711-090000-000", "20260712-090000-000"]
+        );
+    }
+}
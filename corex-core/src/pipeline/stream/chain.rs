@@ -0,0 +1,445 @@
+//! stream 链：相邻 `kind: stream` 步骤以有界通道逐项传递 PipelineItem
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Result, bail};
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::item::PipelineItem;
+use super::stages::{
+    CHANNEL_CAPACITY, CopySource, DrainSink, HashTransform, ShadeTransform, ZipSink,
+};
+use super::traits::{StageKind, StageSink, StageSource, StageTransform};
+use crate::invoke::{Artifact, WireArgs, assemble_typed};
+use crate::pipeline::config::{PipelineConfig, StepConfig};
+use crate::pipeline::context::PipelineContext;
+use crate::pipeline::graph::StageGraph;
+use crate::utils::cancel::{self, CancelToken};
+
+/// 划分 stream 链：`kind: stream` 步骤仅依赖一个 stream 步骤、且该上游仅有它一个下游时归入同一链。
+/// 返回各链的步骤 ID（链首为 source）。
+pub fn plan_chains(pipeline: &PipelineConfig, graph: &StageGraph) -> Result<Vec<Vec<String>>> {
+    let mut chains: Vec<Vec<String>> = Vec::new();
+    let mut chain_of: HashMap<String, usize> = HashMap::new();
+
+    for id in graph.execution_order()? {
+        let Some(step) = graph.step_by_id(pipeline, &id) else {
+            continue;
+        };
+        if step.kind != StageKind::Stream {
+            continue;
+        }
+        let deps = graph.dependencies(&id);
+        let upstream = match deps.as_slice() {
+            [dep] if graph.dependents(dep).len() == 1 => chain_of.get(dep).copied(),
+            _ => None,
+        };
+        let index = match upstream {
+            Some(index) => index,
+            None => {
+                chains.push(Vec::new());
+                chains.len() - 1
+            }
+        };
+        chains[index].push(id.clone());
+        chain_of.insert(id, index);
+    }
+
+    for chain in &chains {
+        validate_chain(pipeline, graph, chain)?;
+    }
+    Ok(chains)
+}
+
+fn validate_chain(pipeline: &PipelineConfig, graph: &StageGraph, chain: &[String]) -> Result<()> {
+    let steps: Vec<&StepConfig> = chain
+        .iter()
+        .filter_map(|id| graph.step_by_id(pipeline, id))
+        .collect();
+    if steps.len() < 2 {
+        bail!(
+            "Pipeline '{}' stream 步骤 '{}' 需与相邻 stream 步骤组成链（至少两步）",
+            pipeline.id,
+            chain[0]
+        );
+    }
+    let last = steps.len() - 1;
+    for (i, step) in steps.iter().enumerate() {
+        if step.retry.is_some() || !step.inputs.is_empty() || step.group.is_some() {
+            bail!(
+                "Pipeline '{}' stream 步骤 '{}' 不支持 retry / inputs 缓存 / foreach / matrix",
+                pipeline.id,
+                step.id
+            );
+        }
+        if i > 0 && (step.when.is_some() || step.timeout_ms.is_some() || step.continue_on_error)
+        {
+            bail!(
+                "Pipeline '{}' stream 步骤 '{}' 不支持 when / timeout_ms / continue_on_error（仅链首步骤可设置）",
+                pipeline.id,
+                step.id
+            );
+        }
+        let ok = if i == 0 {
+            step.module == "copy"
+        } else if i == last && step.module == "compression" {
+            step.action.as_deref() == Some("compress") && step.format.as_deref() == Some("zip")
+        } else {
+            is_transform(step)
+        };
+        if !ok {
+            let role = if i == 0 {
+                "source（支持: copy）"
+            } else if i == last {
+                "sink（支持: compression compress zip、shade、codec hash）"
+            } else {
+                "transform（支持: shade、codec hash）"
+            };
+            bail!(
+                "Pipeline '{}' stream 步骤 '{}' 的 module {} 不能作为 {}",
+                pipeline.id,
+                step.id,
+                step.module,
+                role
+            );
+        }
+    }
+    Ok(())
+}
+
+fn is_transform(step: &StepConfig) -> bool {
+    match step.module.as_str() {
+        "shade" => true,
+        "codec" => step.action.as_deref() == Some("hash"),
+        _ => false,
+    }
+}
+
+/// 链中下游步骤的 params：`from` 由上游 item 提供，缺省时补空串以通过 Args 反序列化；
+/// 显式设置时作为计算相对路径的根目录。
+pub fn fed_params(step: &StepConfig) -> Value {
+    let mut params = match &step.params {
+        Value::Object(map) => map.clone(),
+        _ => Map::new(),
+    };
+    if matches!(step.module.as_str(), "shade" | "compression") {
+        params
+            .entry("from")
+            .or_insert_with(|| Value::String(String::new()));
+    }
+    Value::Object(params)
+}
+
+fn decode<T: DeserializeOwned>(
+    step: &StepConfig,
+    ctx: &PipelineContext,
+    params: &Value,
+) -> Result<T> {
+    let wire = WireArgs {
+        action: step.action.clone(),
+        format: step.format.clone(),
+        algorithm: step.algorithm.clone(),
+        flags: ctx.parse_value(params),
+    };
+    let typed = assemble_typed(&step.module, &wire)?;
+    serde_json::from_value(typed).map_err(|e| anyhow::anyhow!("{} params 无效: {e}", step.module))
+}
+
+/// 下游步骤可见的根目录：上游各根目录，外加显式 `from`
+fn roots_for(from: &str, upstream: &[PathBuf]) -> Vec<PathBuf> {
+    let mut roots = upstream.to_vec();
+    if !from.is_empty() {
+        roots.push(PathBuf::from(from));
+    }
+    roots
+}
+
+/// 链执行结果（与 steps 顺序一致）
+pub struct ChainOutcome {
+    pub stages: Vec<(Artifact, u64)>,
+    /// 首个失败步骤下标及错误
+    pub failed: Option<(usize, anyhow::Error)>,
+}
+
+enum ChainSink {
+    Zip(Box<ZipSink>),
+    Drain(DrainSink),
+}
+
+impl StageSink for ChainSink {
+    fn consume(&mut self, item: PipelineItem) -> Result<()> {
+        match self {
+            ChainSink::Zip(sink) => sink.consume(item),
+            ChainSink::Drain(sink) => sink.consume(item),
+        }
+    }
+
+    fn finish(self) -> Result<Artifact> {
+        match self {
+            ChainSink::Zip(sink) => (*sink).finish(),
+            ChainSink::Drain(sink) => sink.finish(),
+        }
+    }
+}
+
+struct Chain {
+    source: CopySource,
+    source_artifact: Artifact,
+    transforms: Vec<Arc<dyn StageTransform>>,
+    sink: ChainSink,
+}
+
+fn build_chain(
+    steps: &[StepConfig],
+    ctx: &PipelineContext,
+) -> Result<Chain, (usize, anyhow::Error)> {
+    let copy: crate::copy::schema::Args =
+        decode(&steps[0], ctx, &steps[0].params).map_err(|e| (0, e))?;
+    let source_artifact = Artifact::from_path(copy.to.clone());
+    let source = CopySource::new(copy);
+    // 透传的 item 仍留在上游目录下，故保留链上出现过的全部根目录
+    let mut roots: Vec<PathBuf> = source.root().into_iter().collect();
+
+    let mut transforms: Vec<Arc<dyn StageTransform>> = Vec::new();
+    let mut sink = ChainSink::Drain(DrainSink);
+    for (i, step) in steps.iter().enumerate().skip(1) {
+        let params = fed_params(step);
+        let built: Result<()> = (|| {
+            match step.module.as_str() {
+                "shade" => {
+                    let args: crate::shade::schema::Args = decode(step, ctx, &params)?;
+                    let stage = ShadeTransform::new(&args, roots_for(&args.from, &roots))?;
+                    roots.push(stage.root());
+                    transforms.push(Arc::new(stage));
+                }
+                "codec" => {
+                    let args: crate::codec::schema::Args = decode(step, ctx, &params)?;
+                    transforms.push(Arc::new(HashTransform::new(&args)?));
+                }
+                "compression" => {
+                    let args: crate::compression::schema::Args = decode(step, ctx, &params)?;
+                    let crate::compression::schema::Args::Compress(compress) = args else {
+                        bail!("stream sink 需要 compression action: compress");
+                    };
+                    let crate::compression::schema::CompressFormat::Zip(zip) = compress.format
+                    else {
+                        bail!("stream sink 仅支持 format: zip");
+                    };
+                    sink =
+                        ChainSink::Zip(Box::new(ZipSink::new(&zip, roots_for(&zip.from, &roots))?));
+                }
+                other => bail!("module {other} 不支持 stream"),
+            }
+            Ok(())
+        })();
+        built.map_err(|e| (i, e))?;
+    }
+
+    Ok(Chain {
+        source,
+        source_artifact,
+        transforms,
+        sink,
+    })
+}
+
+/// 执行一条 stream 链：source → transform* → sink 各自运行，以有界通道相连；
+/// `token` 取消后各 stage 在处理下一项前退出
+pub async fn run_stream_chain(
+    steps: &[StepConfig],
+    ctx: &PipelineContext,
+    token: &CancelToken,
+) -> ChainOutcome {
+    let count = steps.len();
+    let chain = match build_chain(steps, ctx) {
+        Ok(chain) => chain,
+        Err(failed) => {
+            return ChainOutcome {
+                stages: vec![(Artifact::default(), 0); count],
+                failed: Some(failed),
+            };
+        }
+    };
+    let has_zip = matches!(chain.sink, ChainSink::Zip(_));
+    let counters: Vec<Arc<AtomicU64>> = (0..count).map(|_| Arc::new(AtomicU64::new(0))).collect();
+    let mut tasks: Vec<(usize, JoinHandle<Result<()>>)> = Vec::new();
+
+    let (tx, mut rx) = mpsc::channel::<PipelineItem>(CHANNEL_CAPACITY);
+    let mut stream = cancel::scope(token, || chain.source.into_stream());
+    let emitted = counters[0].clone();
+    tasks.push((
+        0,
+        tokio::spawn(async move {
+            while let Some(item) = stream.next().await {
+                let item = item?;
+                emitted.fetch_add(1, Ordering::Relaxed);
+                if tx.send(item).await.is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        }),
+    ));
+
+    for (offset, transform) in chain.transforms.iter().enumerate() {
+        let index = offset + 1;
+        let (next_tx, next_rx) = mpsc::channel::<PipelineItem>(CHANNEL_CAPACITY);
+        let mut upstream = std::mem::replace(&mut rx, next_rx);
+        let transform = transform.clone();
+        let received = counters[index].clone();
+        let token = token.clone();
+        tasks.push((
+            index,
+            tokio::task::spawn_blocking(move || {
+                while let Some(item) = upstream.blocking_recv() {
+                    token.check()?;
+                    received.fetch_add(1, Ordering::Relaxed);
+                    if let Some(out) = transform.transform(item)?
+                        && next_tx.blocking_send(out).is_err()
+                    {
+                        break;
+                    }
+                }
+                Ok(())
+            }),
+        ));
+    }
+
+    let sink_index = count - 1;
+    let consumed = counters[sink_index].clone();
+    let mut sink = chain.sink;
+    let sink_token = token.clone();
+    let sink_task = tokio::task::spawn_blocking(move || -> Result<ChainSink> {
+        while let Some(item) = rx.blocking_recv() {
+            sink_token.check()?;
+            if has_zip {
+                consumed.fetch_add(1, Ordering::Relaxed);
+            }
+            sink.consume(item)?;
+        }
+        Ok(sink)
+    });
+
+    let mut failed: Option<(usize, anyhow::Error)> = None;
+    for (index, task) in tasks {
+        match task.await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => record(&mut failed, index, e),
+            Err(e) => record(&mut failed, index, e.into()),
+        }
+    }
+    let sink = match sink_task.await {
+        Ok(Ok(sink)) => Some(sink),
+        Ok(Err(e)) => {
+            record(&mut failed, sink_index, e);
+            None
+        }
+        Err(e) => {
+            record(&mut failed, sink_index, e.into());
+            None
+        }
+    };
+
+    let mut stages = Vec::with_capacity(count);
+    stages.push((chain.source_artifact, counters[0].load(Ordering::Relaxed)));
+    for (offset, transform) in chain.transforms.iter().enumerate() {
+        stages.push((
+            transform.artifact(),
+            counters[offset + 1].load(Ordering::Relaxed),
+        ));
+    }
+
+    if failed.is_none()
+        && let Some(sink) = sink
+    {
+        match tokio::task::spawn_blocking(move || sink.finish()).await {
+            Ok(Ok(artifact)) if has_zip => {
+                stages.push((artifact, counters[sink_index].load(Ordering::Relaxed)))
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => record(&mut failed, sink_index, e),
+            Err(e) => record(&mut failed, sink_index, e.into()),
+        }
+    }
+    stages.resize_with(count, || (Artifact::default(), 0));
+
+    ChainOutcome { stages, failed }
+}
+
+/// 记录失败；多个 stage 同时失败时保留最靠前的
+fn record(failed: &mut Option<(usize, anyhow::Error)>, index: usize, err: anyhow::Error) {
+    if failed.as_ref().is_none_or(|(i, _)| index < *i) {
+        *failed = Some((index, err));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn stream_step(id: &str, module: &str) -> StepConfig {
+        StepConfig {
+            id: id.into(),
+            module: module.into(),
+            kind: StageKind::Stream,
+            params: json!({}),
+            ..Default::default()
+        }
+    }
+
+    fn pipeline(steps: Vec<StepConfig>) -> PipelineConfig {
+        PipelineConfig {
+            id: "p".into(),
+            steps,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn adjacent_stream_steps_form_one_chain() {
+        let mut zip = stream_step("pack", "compression");
+        zip.action = Some("compress".into());
+        zip.format = Some("zip".into());
+        let p = pipeline(vec![
+            stream_step("copy", "copy"),
+            stream_step("img", "shade"),
+            zip,
+            StepConfig {
+                id: "after".into(),
+                module: "generate".into(),
+                action: Some("uuid".into()),
+                ..Default::default()
+            },
+        ]);
+        let graph = StageGraph::from_pipeline(&p).unwrap();
+        let chains = plan_chains(&p, &graph).unwrap();
+        assert_eq!(chains, vec![vec!["copy", "img", "pack"]]);
+    }
+
+    #[test]
+    fn single_stream_step_is_rejected() {
+        let p = pipeline(vec![stream_step("copy", "copy")]);
+        let graph = StageGraph::from_pipeline(&p).unwrap();
+        let err = plan_chains(&p, &graph).unwrap_err();
+        assert!(err.to_string().contains("至少两步"));
+    }
+
+    #[test]
+    fn unsupported_source_is_rejected() {
+        let p = pipeline(vec![
+            stream_step("img", "shade"),
+            stream_step("copy", "copy"),
+        ]);
+        let graph = StageGraph::from_pipeline(&p).unwrap();
+        let err = plan_chains(&p, &graph).unwrap_err();
+        assert!(err.to_string().contains("source"));
+    }
+}
@@ -1,11 +1,15 @@
 //! 流式 Pipeline 类型与 trait
 
 mod batch;
+mod chain;
 mod item;
 mod path_stream;
+mod stages;
 mod traits;
 
 pub use batch::run_batch_stage;
+pub use chain::{ChainOutcome, fed_params, plan_chains, run_stream_chain};
 pub use item::PipelineItem;
 pub use path_stream::{run_path_stream, run_path_stream_blocking};
+pub use stages::{CHANNEL_CAPACITY, CopySource, DrainSink, HashTransform, ShadeTransform, ZipSink};
 pub use traits::{StageKind, StageSink, StageSource, StageTransform};
@@ -0,0 +1,226 @@
+//! 流式 stage 实现：copy source → shade / codec hash transform → compression zip sink
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Mutex;
+
+use anyhow::{Result, bail};
+use futures::Stream;
+use image::ImageFormat;
+use serde_json::{Map, Value};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+use super::item::PipelineItem;
+use super::traits::{StageSink, StageSource, StageTransform};
+use crate::codec::schema::HashAlgorithm;
+use crate::compression::formats::ZipAppender;
+use crate::compression::schema::ZipFormatArgs;
+use crate::invoke::Artifact;
+use crate::utils::Filter;
+use crate::utils::cancel;
+
+/// stage 间有界通道容量（背压：下游处理不过来时上游阻塞）
+pub const CHANNEL_CAPACITY: usize = 16;
+
+/// 相对 `roots` 中最近一层所在根目录的路径；均不包含该路径时取文件名。
+/// 透传的 item 仍位于上游根目录下，因此需要按各自的根目录解析
+fn relative_to(path: &Path, roots: &[PathBuf]) -> PathBuf {
+    roots
+        .iter()
+        .filter_map(|r| path.strip_prefix(r).ok())
+        .min_by_key(|rel| rel.components().count())
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from(path.file_name().unwrap_or_default()))
+}
+
+/// copy source：每复制完一个文件即向下游发出 `PipelineItem::Path`
+pub struct CopySource {
+    args: crate::copy::schema::Args,
+}
+
+impl CopySource {
+    pub fn new(args: crate::copy::schema::Args) -> Self {
+        Self { args }
+    }
+
+    /// 下游计算相对路径使用的根目录
+    pub fn root(&self) -> Option<PathBuf> {
+        Path::new(&self.args.from)
+            .is_dir()
+            .then(|| PathBuf::from(&self.args.to))
+    }
+}
+
+impl StageSource for CopySource {
+    fn into_stream(self) -> Pin<Box<dyn Stream<Item = Result<PipelineItem>> + Send>> {
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        // 沿用调用线程绑定的取消令牌
+        let token = cancel::current().unwrap_or_default();
+        std::thread::spawn(move || {
+            let result = cancel::scope(&token, || {
+                crate::copy::service::copy_each(&self.args, |target| {
+                    tx.blocking_send(Ok(PipelineItem::Path(target)))
+                        .map_err(|_| anyhow::anyhow!("下游 stage 已关闭"))
+                })
+            });
+            if let Err(e) = result {
+                let _ = tx.blocking_send(Err(e));
+            }
+        });
+        Box::pin(ReceiverStream::new(rx))
+    }
+}
+
+/// shade transform：逐张转换图片，非图片 item 原样透传
+pub struct ShadeTransform {
+    roots: Vec<PathBuf>,
+    to: PathBuf,
+    format: ImageFormat,
+    quality: u8,
+}
+
+impl ShadeTransform {
+    pub fn new(args: &crate::shade::schema::Args, roots: Vec<PathBuf>) -> Result<Self> {
+        // 未指定 format 时按 to 的扩展名推断，无法推断时为 png
+        let format = match args.format {
+            Some(ref format) => crate::shade::service::parse_format(format)?,
+            None => Path::new(&args.to)
+                .extension()
+                .and_then(|e| e.to_str())
+                .and_then(|e| crate::shade::service::parse_format(e).ok())
+                .unwrap_or(ImageFormat::Png),
+        };
+        Ok(Self {
+            roots,
+            to: PathBuf::from(&args.to),
+            format,
+            quality: args.quality,
+        })
+    }
+
+    pub fn root(&self) -> PathBuf {
+        self.to.clone()
+    }
+}
+
+impl StageTransform for ShadeTransform {
+    fn transform(&self, item: PipelineItem) -> Result<Option<PipelineItem>> {
+        match item {
+            PipelineItem::Path(path) if crate::shade::service::is_image(&path) => {
+                let rel = relative_to(&path, &self.roots);
+                let out = self
+                    .to
+                    .join(rel.with_extension(crate::shade::service::format_ext(&self.format)));
+                if let Some(parent) = out.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                crate::shade::service::convert_file(&path, &out, &self.format, self.quality)?;
+                Ok(Some(PipelineItem::Path(out)))
+            }
+            other => Ok(Some(other)),
+        }
+    }
+
+    fn artifact(&self) -> Artifact {
+        Artifact::from_path(self.to.clone())
+    }
+}
+
+/// codec hash transform：按配置的算法计算文件摘要后透传 item，结果汇总到 artifact.data.hashes
+pub struct HashTransform {
+    algorithm: HashAlgorithm,
+    hashes: Mutex<Map<String, Value>>,
+}
+
+impl HashTransform {
+    pub fn new(args: &crate::codec::schema::Args) -> Result<Self> {
+        let crate::codec::schema::Args::Hash(hash) = args else {
+            bail!("stream transform 仅支持 codec action: hash");
+        };
+        Ok(Self {
+            algorithm: hash.algorithm.clone(),
+            hashes: Mutex::default(),
+        })
+    }
+
+    fn digest(&self, bytes: &[u8]) -> String {
+        match self.algorithm {
+            HashAlgorithm::Md5(_) => crate::codec::service::md5_hex(bytes),
+        }
+    }
+}
+
+impl StageTransform for HashTransform {
+    fn transform(&self, item: PipelineItem) -> Result<Option<PipelineItem>> {
+        if let PipelineItem::Path(ref path) = item {
+            let bytes = std::fs::read(path)
+                .map_err(|e| anyhow::anyhow!("读取文件失败: {}: {}", path.display(), e))?;
+            let digest = self.digest(&bytes);
+            self.hashes
+                .lock()
+                .unwrap()
+                .insert(path.display().to_string(), Value::String(digest));
+        }
+        Ok(Some(item))
+    }
+
+    fn artifact(&self) -> Artifact {
+        let hashes = self.hashes.lock().unwrap().clone();
+        Artifact::default().with_data("hashes", Value::Object(hashes))
+    }
+}
+
+/// compression zip sink：逐文件写入归档
+pub struct ZipSink {
+    writer: ZipAppender,
+    roots: Vec<PathBuf>,
+    filter: Filter,
+    seen: HashSet<PathBuf>,
+}
+
+impl ZipSink {
+    pub fn new(args: &ZipFormatArgs, roots: Vec<PathBuf>) -> Result<Self> {
+        Ok(Self {
+            writer: ZipAppender::create(args)?,
+            roots,
+            filter: Filter::new(&args.io.includes, &args.io.excludes),
+            seen: HashSet::new(),
+        })
+    }
+}
+
+impl StageSink for ZipSink {
+    fn consume(&mut self, item: PipelineItem) -> Result<()> {
+        let PipelineItem::Path(path) = item else {
+            return Ok(());
+        };
+        let rel = relative_to(&path, &self.roots);
+        if self.filter.is_filtered(&rel) {
+            return Ok(());
+        }
+        if !self.seen.insert(rel.clone()) {
+            bail!("ZIP 条目重复: {}", rel.display());
+        }
+        self.writer.append(&path, &rel)
+    }
+
+    fn finish(self) -> Result<Artifact> {
+        Ok(Artifact::from_path(self.writer.finish()?))
+    }
+}
+
+/// 链末步骤为 transform 时使用：仅消费 item
+#[derive(Default)]
+pub struct DrainSink;
+
+impl StageSink for DrainSink {
+    fn consume(&mut self, _item: PipelineItem) -> Result<()> {
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Artifact> {
+        Ok(Artifact::default())
+    }
+}
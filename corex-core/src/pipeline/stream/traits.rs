@@ -1,24 +1,39 @@
 use anyhow::Result;
 use futures::Stream;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::pin::Pin;
 
 use super::item::PipelineItem;
 use crate::invoke::Artifact;
 
-/// Stage 种类
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Stage 种类（步骤级 `kind`，缺省 batch）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
 pub enum StageKind {
+    #[default]
     Batch,
     Stream,
     Signal,
 }
 
+impl StageKind {
+    pub fn is_batch(&self) -> bool {
+        *self == StageKind::Batch
+    }
+}
+
 pub trait StageSource: Send {
     fn into_stream(self) -> Pin<Box<dyn Stream<Item = Result<PipelineItem>> + Send>>;
 }
 
 pub trait StageTransform: Send + Sync {
     fn transform(&self, item: PipelineItem) -> Result<Option<PipelineItem>>;
+
+    /// 链结束后该步骤的产物（默认空）
+    fn artifact(&self) -> Artifact {
+        Artifact::default()
+    }
 }
 
 pub trait StageSink: Send {
@@ -0,0 +1,217 @@
+//! 子 Pipeline 步骤（`module: pipeline`）：按 ID 调用同一配置中的另一条 Pipeline
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result, bail};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+
+use crate::invoke::Artifact;
+
+use super::config::{PipelineConfig, PipelinesConfig, StepConfig};
+use super::context::PipelineContext;
+use super::inputs;
+use super::orchestrator::run_pipeline;
+use super::report::{RunReport, StepStatus};
+
+/// 步骤模块名
+pub const MODULE: &str = "pipeline";
+
+/// `module: pipeline` 的 params
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct Params {
+    /// 被调用的 Pipeline ID
+    pipeline: String,
+    /// 传入子 Pipeline 的 variables（覆盖其同名变量）
+    #[serde(default)]
+    variables: HashMap<String, String>,
+    /// 传入子 Pipeline 的 inputs（带类型，按其 inputs 声明校验）
+    #[serde(default)]
+    inputs: Map<String, Value>,
+}
+
+/// 执行子 Pipeline；子 Pipeline 沿用当前 variables，返回其 RunReport
+pub fn run(step: &StepConfig, ctx: &PipelineContext) -> Result<RunReport> {
+    let params: Params = serde_json::from_value(ctx.parse_value(&step.params))
+        .with_context(|| format!("步骤 '{}' params 无效", step.id))?;
+    let mut child = ctx
+        .pipelines
+        .get(&params.pipeline)
+        .with_context(|| format!("未知 Pipeline: {}", params.pipeline))?
+        .clone();
+    child.variables.extend(params.variables);
+    let typed = inputs::from_json(&child, &params.inputs)?;
+    child.variables.extend(typed);
+
+    let mut child_ctx = PipelineContext {
+        variables: ctx.variables.clone(),
+        pipelines: ctx.pipelines.clone(),
+        ..Default::default()
+    };
+    run_pipeline(&child, &mut child_ctx)
+}
+
+/// `module: pipeline` 的 params Schema（`corex schema`）
+pub(crate) fn params_schema() -> Value {
+    super::jsonschema::schema::<Params>()
+}
+
+/// 子 Pipeline 摘要与最终产物 → 步骤 Artifact：
+/// `path` 为最后一个产出路径的主步骤，`data` 含 pipeline / run_id / status / duration_ms / steps / artifacts / outputs
+pub fn artifact(report: &RunReport) -> Artifact {
+    let produced: Vec<(&str, &Artifact)> = report
+        .steps
+        .iter()
+        .filter(|s| {
+            s.phase.is_main() && matches!(s.status, StepStatus::Success | StepStatus::Cached)
+        })
+        .filter_map(|s| s.artifact.as_ref().map(|a| (s.id.as_str(), a)))
+        .collect();
+    let artifacts: Map<String, Value> = produced
+        .iter()
+        .filter_map(|(id, a)| a.path.as_ref().map(|p| (id.to_string(), json!(p))))
+        .collect();
+
+    let mut artifact = Artifact {
+        path: produced.iter().rev().find_map(|(_, a)| a.path.clone()),
+        ..Default::default()
+    };
+    artifact.data.extend([
+        ("pipeline".to_string(), json!(report.pipeline_id)),
+        ("run_id".to_string(), json!(report.run_id)),
+        ("status".to_string(), json!(report.status)),
+        ("duration_ms".to_string(), json!(report.duration_ms)),
+        ("steps".to_string(), json!(report.steps.len())),
+        ("artifacts".to_string(), Value::Object(artifacts)),
+        ("outputs".to_string(), json!(report.outputs)),
+    ]);
+    artifact
+}
+
+/// 被调用的 Pipeline ID；`params.pipeline` 须为字面量
+fn target(step: &StepConfig) -> Result<&str> {
+    match step.params.get("pipeline").and_then(Value::as_str) {
+        Some(id) if !id.contains("${") => Ok(id),
+        Some(_) => bail!("步骤 '{}' params.pipeline 不支持占位符", step.id),
+        None => bail!("步骤 '{}' 缺少 params.pipeline", step.id),
+    }
+}
+
+/// 校验子 Pipeline 引用：目标存在，且 Pipeline 间调用无环
+pub fn validate(config: &PipelinesConfig) -> Result<()> {
+    let mut calls: HashMap<&str, Vec<&str>> = HashMap::new();
+    for pipeline in &config.pipelines {
+        let targets = calls.entry(&pipeline.id).or_default();
+        for step in steps_of(pipeline).filter(|s| s.module == MODULE) {
+            let id =
+                target(step).map_err(|e| anyhow::anyhow!("Pipeline '{}' {}", pipeline.id, e))?;
+            if !config.pipelines.iter().any(|p| p.id == id) {
+                bail!(
+                    "Pipeline '{}' 步骤 '{}' 调用未知 Pipeline '{}'",
+                    pipeline.id,
+                    step.id,
+                    id
+                );
+            }
+            targets.push(id);
+        }
+    }
+
+    let mut done = HashSet::new();
+    for pipeline in &config.pipelines {
+        let mut path = Vec::new();
+        visit(&pipeline.id, &calls, &mut path, &mut done)?;
+    }
+    Ok(())
+}
+
+fn visit<'a>(
+    id: &'a str,
+    calls: &HashMap<&'a str, Vec<&'a str>>,
+    path: &mut Vec<&'a str>,
+    done: &mut HashSet<&'a str>,
+) -> Result<()> {
+    if let Some(start) = path.iter().position(|p| *p == id) {
+        let mut cycle = path[start..].to_vec();
+        cycle.push(id);
+        bail!("Pipeline 之间存在循环调用: {}", cycle.join(" → "));
+    }
+    if done.contains(id) {
+        return Ok(());
+    }
+    path.push(id);
+    for next in calls.get(id).into_iter().flatten() {
+        visit(next, calls, path, done)?;
+    }
+    path.pop();
+    done.insert(id);
+    Ok(())
+}
+
+fn steps_of(pipeline: &PipelineConfig) -> impl Iterator<Item = &StepConfig> {
+    pipeline
+        .steps
+        .iter()
+        .chain(&pipeline.on_failure)
+        .chain(&pipeline.finally)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calling(id: &str, targets: &[&str]) -> PipelineConfig {
+        PipelineConfig {
+            id: id.into(),
+            steps: targets
+                .iter()
+                .map(|t| StepConfig {
+                    id: format!("call_{t}"),
+                    module: MODULE.into(),
+                    params: json!({ "pipeline": t }),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn config(pipelines: Vec<PipelineConfig>) -> PipelinesConfig {
+        PipelinesConfig {
+            version: 3,
+            variables: HashMap::new(),
+            pipelines,
+        }
+    }
+
+    #[test]
+    fn shared_child_is_not_a_cycle() {
+        let config = config(vec![
+            calling("a", &["pack"]),
+            calling("b", &["pack", "a"]),
+            calling("pack", &[]),
+        ]);
+        validate(&config).unwrap();
+    }
+
+    #[test]
+    fn cycle_and_unknown_target_are_rejected() {
+        let cyclic = config(vec![
+            calling("a", &["b"]),
+            calling("b", &["c"]),
+            calling("c", &["a"]),
+        ]);
+        let err = validate(&cyclic).unwrap_err().to_string();
+        assert!(err.contains("a → b → c → a"), "{err}");
+
+        let unknown = config(vec![calling("a", &["missing"])]);
+        assert!(
+            validate(&unknown)
+                .unwrap_err()
+                .to_string()
+                .contains("missing")
+        );
+    }
+}
@@ -1,16 +1,20 @@
 use std::path::Path;
-use std::sync::Arc;
 
 use anyhow::Result;
-use crossterm::style::Stylize;
 
 use crate::pipeline::config::{PipelineArgs, PipelineConfig, PipelinesConfig};
 use crate::pipeline::context::PipelineContext;
-use crate::pipeline::guard::{self, RunningSet};
-use crate::pipeline::orchestrator::run_pipeline as orchestrate;
-use crate::pipeline::report::{RunStatus, write_report};
+use crate::pipeline::history::Trigger;
+use crate::pipeline::interrupt;
+use crate::pipeline::lock;
+use crate::pipeline::orchestrator::{resume_pipeline, run_pipeline as orchestrate};
+use crate::pipeline::report::{RunReport, RunStatus};
+use crate::pipeline::report_format;
+use crate::pipeline::state::{self, RunState};
 use crate::runtime;
+#[cfg(feature = "schedule")]
 use crate::schedule;
+#[cfg(feature = "watch")]
 use crate::watch::{self, WatchOpts, WatchTarget};
 
 /// yaml 中声明的触发源
@@ -97,36 +101,55 @@ pub fn run(
     config_path: &Path,
     args: &PipelineArgs,
 ) -> Result<()> {
+    interrupt::install();
     let mode = run_mode(pipeline, args.once);
-    let watch_targets = if mode != RunMode::Once {
-        Some(check(pipeline, config, mode)?)
-    } else {
-        None
-    };
+    if mode == RunMode::Once {
+        return run_once(pipeline, config, args);
+    }
+    serve(pipeline, config, config_path, mode)
+}
 
+/// 未启用 watch / schedule 时无法常驻，提示改为单次执行
+#[cfg(not(all(feature = "schedule", feature = "watch")))]
+fn serve(
+    pipeline: &PipelineConfig,
+    _config: &PipelinesConfig,
+    _config_path: &Path,
+    _mode: RunMode,
+) -> Result<()> {
+    anyhow::bail!(
+        "Pipeline '{}' 配置了 watch / schedule 触发，当前构建未启用 watch / schedule feature\n\
+         提示: 使用 --once 单次执行",
+        pipeline.id
+    )
+}
+
+/// 常驻执行 watch / cron 触发
+#[cfg(all(feature = "schedule", feature = "watch"))]
+fn serve(
+    pipeline: &PipelineConfig,
+    config: &PipelinesConfig,
+    config_path: &Path,
+    mode: RunMode,
+) -> Result<()> {
+    let watch_targets = check(pipeline, config, mode)?;
     match mode {
-        RunMode::Once => run_once(pipeline, config, args),
-        RunMode::Watch => {
-            watch::serve(
-                config,
-                config_path,
-                &[pipeline.id.clone()],
-                &WatchOpts {
-                    immediate: true,
-                    ..WatchOpts::default()
-                },
-                watch_targets,
-            )
-        }
+        RunMode::Once => unreachable!("单次执行已在 run 中处理"),
+        RunMode::Watch => watch::serve(
+            config,
+            config_path,
+            &[pipeline.id.clone()],
+            &WatchOpts {
+                immediate: true,
+                ..WatchOpts::default()
+            },
+            Some(watch_targets),
+        ),
         RunMode::Cron => {
             let ids = vec![pipeline.id.clone()];
             schedule::serve(config, Some(&ids))
         }
-        RunMode::Dual => serve_dual(
-            pipeline,
-            config,
-            watch_targets.expect("dual mode checked"),
-        ),
+        RunMode::Dual => serve_dual(pipeline, config, watch_targets),
     }
 }
 
@@ -135,18 +158,53 @@ fn run_once(
     config: &PipelinesConfig,
     args: &PipelineArgs,
 ) -> Result<()> {
-    let mut ctx = PipelineContext::with_variables(config.variables.clone());
+    let _lock = lock::hold(pipeline)?;
+    let mut ctx = PipelineContext::from_config(config);
     let report = orchestrate(pipeline, &mut ctx)?;
+    finish_once(&ctx, &report, args)
+}
+
+/// `--resume`：沿用上次运行已完成的步骤，单次执行其余步骤
+pub fn resume(
+    pipeline: &PipelineConfig,
+    config: &PipelinesConfig,
+    previous: RunState,
+    args: &PipelineArgs,
+) -> Result<()> {
+    interrupt::install();
+    let completed = previous.completed_steps()?;
+    let _lock = lock::hold(pipeline)?;
+    let mut ctx = PipelineContext {
+        variables: runtime::merge_variables(previous.variables, &args.define),
+        ..PipelineContext::from_config(config)
+    };
+    let report = resume_pipeline(pipeline, &mut ctx, &previous.report.run_id, completed)?;
+    finish_once(&ctx, &report, args)
+}
 
-    if runtime::is_json_output() {
-        runtime::state().emitter.json(&report)?;
+/// 单次执行收尾：保存运行记录（供 history / --resume）、输出报告
+fn finish_once(ctx: &PipelineContext, report: &RunReport, args: &PipelineArgs) -> Result<()> {
+    if state::record(Trigger::Manual, ctx, report)
+        && report.status != RunStatus::Success
+        && !runtime::is_quiet()
+        && !runtime::is_json_output()
+    {
+        eprintln!(
+            "  可使用 corex pipeline --resume {} 从失败 / 取消的步骤恢复",
+            report.run_id
+        );
+    }
+
+    // 事件流中完整报告随 run_finished 输出
+    if runtime::is_json_output() && !runtime::is_event_stream() {
+        runtime::state().emitter.json(report)?;
     }
 
     if let Some(ref path) = args.report_file {
-        write_report(path, &report)?;
+        report_format::write(path, &args.report_format, report)?;
     }
 
-    if report.status == RunStatus::Failed {
+    if report.status != RunStatus::Success {
         return Err(report.into_err());
     }
 
@@ -154,6 +212,7 @@ fn run_once(
 }
 
 /// 校验触发配置；Dual 模式返回已解析的 watch 目标
+#[cfg(all(feature = "schedule", feature = "watch"))]
 fn check(
     pipeline: &PipelineConfig,
     config: &PipelinesConfig,
@@ -189,39 +248,48 @@ fn check(
     }
 }
 
+#[cfg(all(feature = "schedule", feature = "watch"))]
 fn serve_dual(
     pipeline: &PipelineConfig,
     config: &PipelinesConfig,
     targets: Vec<WatchTarget>,
 ) -> Result<()> {
+    use std::sync::Arc;
+
+    use crossterm::style::Stylize;
+
+    use crate::pipeline::guard::{self, RunningSet};
+
     let id = pipeline.id.clone();
 
-    println!(
-        "\n  {} 并行守护：{}（watch + cron）\n",
-        "▶".green().bold(),
-        pipeline
-            .description
-            .as_deref()
-            .unwrap_or(&pipeline.id)
-            .bold()
-    );
-
-    for target in &targets {
-        let paths = target
-            .paths
-            .iter()
-            .map(|p| p.display().to_string())
-            .collect::<Vec<_>>()
-            .join(", ");
+    if !runtime::is_json_output() {
         println!(
-            "  {} watch — debounce: {}ms — cooldown: {}ms — {}",
-            "▸".cyan(),
-            target.debounce_ms,
-            target.cooldown_ms,
-            paths.dim()
+            "\n  {} 并行守护：{}（watch + cron）\n",
+            "▶".green().bold(),
+            pipeline
+                .description
+                .as_deref()
+                .unwrap_or(&pipeline.id)
+                .bold()
         );
+
+        for target in &targets {
+            let paths = target
+                .paths
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            println!(
+                "  {} watch — debounce: {}ms — cooldown: {}ms — {}",
+                "▸".cyan(),
+                target.debounce_ms,
+                target.cooldown_ms,
+                paths.dim()
+            );
+        }
+        println!();
     }
-    println!();
 
     let running: RunningSet = guard::new_set();
     let cfg = config.clone();
@@ -256,7 +324,6 @@ mod tests {
     fn pipeline_with(watch: bool, cron: bool) -> PipelineConfig {
         PipelineConfig {
             id: "demo".into(),
-            description: None,
             schedule: cron.then(|| "0 * * * * *".into()),
             watch: watch.then(|| WatchConfig {
                 paths: vec![".".into()],
@@ -266,6 +333,7 @@ mod tests {
                 cooldown_ms: None,
             }),
             steps: vec![],
+            ..Default::default()
         }
     }
 
@@ -283,6 +351,7 @@ mod tests {
         assert_eq!(label(&pipeline_with(false, false)), "");
     }
 
+    #[cfg(feature = "schedule")]
     #[test]
     fn check_cron_rejects_bad_expression() {
         let mut cfg = PipelinesConfig {
@@ -290,10 +359,9 @@ mod tests {
             variables: Default::default(),
             pipelines: vec![PipelineConfig {
                 id: "bad".into(),
-                description: None,
                 schedule: Some("not a cron".into()),
-                watch: None,
                 steps: vec![],
+                ..Default::default()
             }],
         };
         let err = schedule::check_cron(&cfg, Some(&["bad".into()]))
@@ -305,6 +373,7 @@ mod tests {
         schedule::check_cron(&cfg, Some(&["bad".into()])).unwrap();
     }
 
+    #[cfg(all(feature = "schedule", feature = "watch"))]
     #[test]
     fn check_rejects_missing_watch_path() {
         let cfg = PipelinesConfig {
@@ -312,8 +381,6 @@ mod tests {
             variables: Default::default(),
             pipelines: vec![PipelineConfig {
                 id: "missing".into(),
-                description: None,
-                schedule: None,
                 watch: Some(WatchConfig {
                     paths: vec!["/nonexistent/corex-watch-test".into()],
                     includes: vec![],
@@ -322,6 +389,7 @@ mod tests {
                     cooldown_ms: None,
                 }),
                 steps: vec![],
+                ..Default::default()
             }],
         };
         let p = &cfg.pipelines[0];
@@ -3,6 +3,8 @@ use std::io::{self, Write};
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::utils::redact;
+
 use super::opts::ColorChoice;
 
 /// 输出格式
@@ -13,9 +15,18 @@ use super::opts::ColorChoice;
 pub enum OutputFormat {
     Human,
     Json,
+    /// 逐行 JSON 生命周期事件（pipeline / watch run / schedule cron）
+    Ndjson,
 }
 
-/// 统一输出出口
+impl OutputFormat {
+    /// 机器可读输出（json / ndjson）
+    pub fn is_machine(self) -> bool {
+        self != OutputFormat::Human
+    }
+}
+
+/// 统一输出出口（输出前对已登记的密钥值脱敏）
 #[derive(Debug, Clone)]
 pub struct Emitter {
     format: OutputFormat,
@@ -50,16 +61,16 @@ impl Emitter {
 
     /// 人类可读消息（stderr，除非 quiet）
     pub fn message(&self, msg: impl AsRef<str>) {
-        if self.quiet || self.format == OutputFormat::Json {
+        if self.quiet || self.format.is_machine() {
             return;
         }
-        eprintln!("{}", msg.as_ref());
+        eprintln!("{}", redact::mask(msg.as_ref()));
     }
 
     /// 结构化 JSON 输出到 stdout
     pub fn json<T: Serialize>(&self, value: &T) -> io::Result<()> {
-        if self.format == OutputFormat::Json {
-            let line = serde_json::to_string(value)?;
+        if self.format.is_machine() {
+            let line = redact::mask_json(&serde_json::to_string(value)?);
             writeln!(io::stdout(), "{line}")?;
         }
         Ok(())
@@ -67,8 +78,9 @@ impl Emitter {
 
     /// 结构化 JSON Value 到 stdout
     pub fn json_value(&self, value: &Value) -> io::Result<()> {
-        if self.format == OutputFormat::Json {
-            writeln!(io::stdout(), "{}", serde_json::to_string(value)?)?;
+        if self.format.is_machine() {
+            let line = redact::mask_json(&serde_json::to_string(value)?);
+            writeln!(io::stdout(), "{line}")?;
         }
         Ok(())
     }
@@ -80,7 +92,7 @@ impl Emitter {
         message: impl AsRef<str>,
         step_id: Option<&str>,
     ) -> io::Result<()> {
-        if self.format == OutputFormat::Json {
+        if self.format.is_machine() {
             let mut err = serde_json::json!({
                 "error": {
                     "code": code,
@@ -92,7 +104,7 @@ impl Emitter {
             }
             self.json_value(&err)?;
         } else if !self.quiet {
-            eprintln!("错误 [{code}]: {}", message.as_ref());
+            eprintln!("错误 [{code}]: {}", redact::mask(message.as_ref()));
         }
         Ok(())
     }
@@ -39,11 +39,16 @@ pub fn state() -> &'static RuntimeState {
         .expect("runtime 未初始化，请先调用 runtime::init")
 }
 
-/// 当前是否为 JSON 输出模式
+/// 当前是否为 JSON 输出模式（含 ndjson 事件流）
 pub fn is_json_output() -> bool {
+    RUNTIME.get().is_some_and(|s| s.opts.format.is_machine())
+}
+
+/// 当前是否为 ndjson 事件流模式
+pub fn is_event_stream() -> bool {
     RUNTIME
         .get()
-        .is_some_and(|s| s.opts.format == OutputFormat::Json)
+        .is_some_and(|s| s.opts.format == OutputFormat::Ndjson)
 }
 
 /// 是否静默模式
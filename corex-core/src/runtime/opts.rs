@@ -16,7 +16,7 @@ pub enum ColorChoice {
 /// 全局运行时选项（clap global args）
 #[derive(Debug, Clone, Parser, Serialize, Deserialize)]
 pub struct RuntimeOpts {
-    /// 输出格式：human（默认）| json
+    /// 输出格式：human（默认）| json | ndjson（事件流）
     #[arg(long, global = true, default_value = "human")]
     pub format: OutputFormat,
     /// 仅输出结果，抑制进度与 banner
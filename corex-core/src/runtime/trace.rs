@@ -1,6 +1,10 @@
+use std::io;
+
+use crate::utils::redact::MaskedWriter;
+
 use super::exit::AppError;
 
-/// 初始化 tracing subscriber
+/// 初始化 tracing subscriber（输出前对已登记的密钥值脱敏）
 pub fn init_tracing(verbose: u8, quiet: bool) -> Result<(), AppError> {
     use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 
@@ -19,12 +23,20 @@ pub fn init_tracing(verbose: u8, quiet: bool) -> Result<(), AppError> {
     if std::env::var("COREX_LOG_FORMAT").as_deref() == Ok("json") {
         tracing_subscriber::registry()
             .with(filter)
-            .with(fmt::layer().json())
+            .with(
+                fmt::layer()
+                    .json()
+                    .with_writer(|| MaskedWriter(io::stdout())),
+            )
             .init();
     } else {
         tracing_subscriber::registry()
             .with(filter)
-            .with(fmt::layer().with_target(false))
+            .with(
+                fmt::layer()
+                    .with_target(false)
+                    .with_writer(|| MaskedWriter(io::stdout())),
+            )
             .init();
     }
 
@@ -1,4 +1,6 @@
 use clap::Parser;
+#[cfg(feature = "pipeline")]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// scan 子命令
@@ -9,4 +11,5 @@ pub enum Args {
 }
 
 #[derive(Debug, Parser, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct OsArgs {}
@@ -8,13 +8,18 @@ use crossterm::style::Stylize;
 use dialoguer::theme::ColorfulTheme;
 
 use crate::pipeline::config::{
-    CONFIG_VERSION, PipelineConfig, PipelinesConfig, StepConfig, find_config_path, load_config,
+    CONFIG_VERSION, PipelineConfig, PipelinesConfig, StepConfig, find_config_path, load_configs,
     validate_config,
 };
 use crate::pipeline::context::PipelineContext;
 use crate::pipeline::guard::{self, RunningSet};
+use crate::pipeline::history::Trigger;
+use crate::pipeline::interrupt;
+use crate::pipeline::lock;
 use crate::pipeline::runner::run_pipeline;
+use crate::runtime;
 use crate::schedule::schema::Args;
+use crate::utils::cancel;
 
 /// `corex schedule` 命令入口
 pub fn run(args: &Args) -> Result<()> {
@@ -110,6 +115,7 @@ fn scheduled(config: &PipelinesConfig, ids: Option<&[String]>) -> Vec<Scheduled>
 
 /// 常驻 cron 循环
 pub fn serve(config: &PipelinesConfig, ids: Option<&[String]>) -> Result<()> {
+    interrupt::install();
     check_cron(config, ids)?;
     let items = scheduled(config, ids);
 
@@ -120,34 +126,36 @@ pub fn serve(config: &PipelinesConfig, ids: Option<&[String]>) -> Result<()> {
         );
     }
 
-    print_banner("Corex · 定时调度器");
+    if !runtime::is_json_output() {
+        print_banner("Corex · 定时调度器");
 
-    println!(
-        "  {} 已加载 {} 条定时 Pipeline（共 {} 条）\n",
-        "✓".green().bold(),
-        items.len(),
-        config.pipelines.len()
-    );
-
-    for item in &items {
-        let next = item.sched.upcoming(Local).next();
-        let next_str = next
-            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
-            .unwrap_or_else(|| "无".to_string());
         println!(
-            "  {} {} — schedule: {:?} — 下次执行: {}",
-            "▸".cyan(),
-            item.pipeline
-                .description
-                .as_deref()
-                .unwrap_or(&item.pipeline.id)
-                .bold(),
-            item.pipeline.schedule.as_deref().unwrap_or(""),
-            next_str.dim()
+            "  {} 已加载 {} 条定时 Pipeline（共 {} 条）\n",
+            "✓".green().bold(),
+            items.len(),
+            config.pipelines.len()
         );
+
+        for item in &items {
+            let next = item.sched.upcoming(Local).next();
+            let next_str = next
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| "无".to_string());
+            println!(
+                "  {} {} — schedule: {:?} — 下次执行: {}",
+                "▸".cyan(),
+                item.pipeline
+                    .description
+                    .as_deref()
+                    .unwrap_or(&item.pipeline.id)
+                    .bold(),
+                item.pipeline.schedule.as_deref().unwrap_or(""),
+                next_str.dim()
+            );
+        }
+        println!();
+        println!("  {} 等待定时任务触发...（Ctrl+C 退出）\n", "⏳".yellow());
     }
-    println!();
-    println!("  {} 等待定时任务触发...（Ctrl+C 退出）\n", "⏳".yellow());
 
     serve_loop(config, items, guard::new_set())
 }
@@ -165,7 +173,7 @@ pub(crate) fn loop_for(
     serve_loop(config, items, running)
 }
 
-/// cron 主循环（无 banner）
+/// cron 主循环（无 banner）；每次触发在独立线程执行，Ctrl+C 后等待运行中的 Pipeline 结束再返回
 fn serve_loop(
     config: &PipelinesConfig,
     items: Vec<Scheduled>,
@@ -173,6 +181,7 @@ fn serve_loop(
 ) -> Result<()> {
     let mut next_runs: std::collections::HashMap<String, chrono::DateTime<Local>> =
         std::collections::HashMap::new();
+    let base = PipelineContext::from_config(config);
 
     for item in &items {
         if let Some(next) = item.sched.upcoming(Local).next() {
@@ -180,7 +189,7 @@ fn serve_loop(
         }
     }
 
-    loop {
+    while !cancel::shutdown().is_cancelled() {
         let now = Local::now();
 
         for item in &items {
@@ -190,54 +199,48 @@ fn serve_loop(
                 .unwrap_or(false);
 
             if should_run {
-                println!(
-                    "\n  {} [{}] 定时触发: {}",
-                    "⏰".yellow().bold(),
-                    Local::now().format("%H:%M:%S").to_string().bold(),
-                    item.pipeline
-                        .description
-                        .as_deref()
-                        .unwrap_or(&item.pipeline.id)
-                        .bold()
-                );
-
-                guard::run_sync(
-                    &running,
-                    &item.pipeline,
-                    &config.variables,
-                    "定时",
-                );
+                let human = !runtime::is_json_output();
+                if human {
+                    println!(
+                        "\n  {} [{}] 定时触发: {}",
+                        "⏰".yellow().bold(),
+                        Local::now().format("%H:%M:%S").to_string().bold(),
+                        item.pipeline
+                            .description
+                            .as_deref()
+                            .unwrap_or(&item.pipeline.id)
+                            .bold()
+                    );
+                }
+
+                guard::spawn_cron(&running, &item.pipeline, &base, "定时");
 
                 if let Some(next) = item.sched.upcoming(Local).next() {
                     next_runs.insert(item.pipeline.id.clone(), next);
-                    println!(
-                        "  {} 下次执行: {}",
-                        "⏳".yellow(),
-                        next.format("%Y-%m-%d %H:%M:%S").to_string().dim()
-                    );
+                    if human {
+                        println!(
+                            "  {} 下次执行: {}",
+                            "⏳".yellow(),
+                            next.format("%Y-%m-%d %H:%M:%S").to_string().dim()
+                        );
+                    }
+                }
+                if human {
+                    println!();
                 }
-                println!();
             }
         }
 
         std::thread::sleep(std::time::Duration::from_secs(1));
     }
+
+    guard::wait_idle(&running);
+    Ok(())
 }
 
 /// 以守护进程模式运行，按 cron 表达式定时执行 Pipeline
 fn run_cron(config_path: Option<&str>, pipeline: &[String]) -> Result<()> {
-    let config_path = config_path
-        .map(std::path::PathBuf::from)
-        .unwrap_or_else(find_config_path);
-
-    if !config_path.exists() {
-        anyhow::bail!(
-            "配置文件未找到：{}，请先运行 `corex schedule generate` 生成配置",
-            config_path.display()
-        );
-    }
-
-    let config = load_config(&config_path)?;
+    let config = load_configs(config_path, None)?;
     validate_config(&config)?;
 
     let ids = if pipeline.is_empty() {
@@ -249,16 +252,7 @@ fn run_cron(config_path: Option<&str>, pipeline: &[String]) -> Result<()> {
 }
 
 fn run_interactive() -> Result<()> {
-    let path = find_config_path();
-
-    if !path.exists() {
-        anyhow::bail!(
-            "配置文件未找到：{}，请先运行 `corex schedule generate` 生成配置",
-            path.display()
-        );
-    }
-
-    let config = load_config(&path)?;
+    let config = load_configs(None, None)?;
     validate_config(&config)?;
 
     if config.pipelines.is_empty() {
@@ -293,8 +287,10 @@ fn run_interactive() -> Result<()> {
     }
 
     let pipeline = &config.pipelines[pipeline_idx];
-    let mut ctx = PipelineContext::with_variables(config.variables.clone());
-    run_pipeline(pipeline, &mut ctx)
+    let mut ctx = PipelineContext::from_config(&config);
+    interrupt::install();
+    let _lock = lock::hold(pipeline)?;
+    run_pipeline(pipeline, &mut ctx, Trigger::Manual)
 }
 
 fn new_step(
@@ -313,10 +309,8 @@ fn new_step(
         format: format.map(str::to_string),
         algorithm: algorithm.map(str::to_string),
         description,
-        depends_on: vec![],
-        when: None,
-        retry: None,
         params,
+        ..Default::default()
     }
 }
 
@@ -607,8 +601,8 @@ fn generate_config_template() -> Result<()> {
                 Some(pipeline_desc)
             },
             schedule,
-            watch: None,
             steps,
+            ..Default::default()
         }],
     };
 
@@ -663,17 +657,15 @@ mod filter_tests {
             pipelines: vec![
                 PipelineConfig {
                     id: "a".into(),
-                    description: None,
                     schedule: Some("0 * * * * *".into()),
-                    watch: None,
                     steps: vec![],
+                    ..Default::default()
                 },
                 PipelineConfig {
                     id: "b".into(),
-                    description: None,
                     schedule: Some("0 0 9 * * *".into()),
-                    watch: None,
                     steps: vec![],
+                    ..Default::default()
                 },
             ],
         }
@@ -699,10 +691,9 @@ mod filter_tests {
             variables: Default::default(),
             pipelines: vec![PipelineConfig {
                 id: "bad".into(),
-                description: None,
                 schedule: Some("not a cron".into()),
-                watch: None,
                 steps: vec![],
+                ..Default::default()
             }],
         };
         let err = check_cron(&cfg, Some(&["bad".into()]))
@@ -1,9 +1,12 @@
 use clap::{ArgAction, Parser};
+#[cfg(feature = "pipeline")]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::utils::verifier;
 
 #[derive(Debug, Clone, Parser, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct Args {
     #[arg(short, long, value_parser = verifier::path, help = "目标路径")]
     // 源路径（根目录）
@@ -10,6 +10,7 @@ use tokio::sync::Semaphore;
 use walkdir::WalkDir;
 
 use crate::scrub::schema::Args;
+use crate::utils::cancel::{self, CancelToken};
 use crate::utils::{file, progress};
 
 /*
@@ -52,13 +53,15 @@ pub fn execute(args: &Args) -> anyhow::Result<Output> {
     let source = args.source.clone();
     let recursive = args.recursive;
     let target = args.target.clone();
+    // 调用线程绑定的取消令牌需显式传入异步任务
+    let token = cancel::current().unwrap_or_default();
 
     match tokio::runtime::Handle::try_current() {
         Ok(handle) => {
             let (tx, rx) = mpsc::channel::<Result<(), String>>();
             let tgt = source.clone();
             handle.spawn(async move {
-                let res = launcher(&tgt, recursive, &target).await;
+                let res = launcher(&tgt, recursive, &target, &token).await;
                 let _ = tx.send(match res {
                     Ok(()) => Ok(()),
                     Err(e) => Err(e.to_string()),
@@ -80,7 +83,7 @@ pub fn execute(args: &Args) -> anyhow::Result<Output> {
                 .build()
                 .expect("failed to create tokio runtime");
 
-            rt.block_on(launcher(&source, recursive, &target))?;
+            rt.block_on(launcher(&source, recursive, &target, &token))?;
             Ok(Output {
                 path: PathBuf::from(&source),
             })
@@ -203,7 +206,13 @@ fn collect_descendants(paths: Vec<(PathBuf, bool)>) -> (Vec<(PathBuf, bool)>, u6
 /// - `source`：起始目录路径字符串
 /// - `recursive`：是否递归查找子目录
 /// - `target`：要删除的名称（文件或目录名）
-async fn launcher(source: &str, recursive: bool, target: &str) -> anyhow::Result<()> {
+/// - `token`：取消令牌；取消后停止遍历并不再派发新的删除任务
+async fn launcher(
+    source: &str,
+    recursive: bool,
+    target: &str,
+    token: &CancelToken,
+) -> anyhow::Result<()> {
     // 根路径 PathBuf
     let root = PathBuf::from(source);
     // 验证根路径
@@ -222,10 +231,12 @@ async fn launcher(source: &str, recursive: bool, target: &str) -> anyhow::Result
         let matches: Vec<(PathBuf, bool)> = tokio::task::spawn_blocking({
             let root = root.clone();
             let fname = fname.clone();
+            let token = token.clone();
             move || {
                 WalkDir::new(&root)
                     .follow_links(false)
                     .into_iter()
+                    .take_while(|_| !token.is_cancelled())
                     .filter_map(|entry| entry.ok())
                     .filter_map(|entry| {
                         if entry.file_name().to_string_lossy() == fname {
@@ -243,6 +254,7 @@ async fn launcher(source: &str, recursive: bool, target: &str) -> anyhow::Result
         .map_err(|e| anyhow::anyhow!("traverse join error: {}", e))?;
 
         spinner.finish_and_clear();
+        token.check()?;
 
         if matches.is_empty() {
             println!("未找到匹配项: {}", target);
@@ -288,6 +300,7 @@ async fn launcher(source: &str, recursive: bool, target: &str) -> anyhow::Result
         // 从深到浅逐层并发删除
         let mut tasks = Vec::with_capacity(all_items.len());
         for (path, is_dir) in all_items {
+            token.check()?;
             let task = tokio::spawn(delete_item(path, is_dir, semaphore.clone()));
             tasks.push(task);
         }
@@ -370,6 +383,7 @@ async fn launcher(source: &str, recursive: bool, target: &str) -> anyhow::Result
         let semaphore = Arc::new(Semaphore::new(32));
         let mut tasks = Vec::with_capacity(children.len());
         for child in children {
+            token.check()?;
             let is_dir = child.is_dir();
             let task = tokio::spawn(delete_item(child, is_dir, semaphore.clone()));
             tasks.push(task);
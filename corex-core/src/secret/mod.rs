@@ -0,0 +1,6 @@
+pub mod schema;
+pub mod service;
+pub mod store;
+
+pub use service::run;
+pub use store::resolve;
@@ -0,0 +1,25 @@
+use clap::Parser;
+
+/// `corex secret` 子命令参数
+#[derive(Debug, Clone, Parser)]
+pub enum Args {
+    /// 写入（或覆盖）密钥；省略 value 时从终端输入或 stdin 读取
+    Set {
+        /// 密钥名（字母、数字、`_`、`-`），Pipeline 中以 `${secret.<name>}` 引用
+        name: String,
+        /// 密钥值（会留在 shell 历史中，建议省略）
+        value: Option<String>,
+    },
+    /// 输出密钥明文
+    Get {
+        /// 密钥名
+        name: String,
+    },
+    /// 列出密钥名
+    List,
+    /// 删除密钥
+    Rm {
+        /// 密钥名
+        name: String,
+    },
+}
@@ -0,0 +1,81 @@
+use std::io::{self, BufRead, IsTerminal};
+
+use anyhow::{Context, Result, bail};
+use crossterm::style::Stylize;
+
+use crate::runtime;
+use crate::secret::schema::Args;
+use crate::secret::store::{SecretStore, secrets_dir, validate_name};
+
+/// `corex secret` 命令处理
+pub fn run(args: &Args) -> Result<()> {
+    let dir = secrets_dir()?;
+    match args {
+        Args::Set { name, value } => {
+            validate_name(name)?;
+            let value = match value {
+                Some(v) => v.clone(),
+                None => read_value(name)?,
+            };
+            let mut store = SecretStore::open(&dir)?;
+            store.set(name, value)?;
+            store.save()?;
+            if !runtime::is_quiet() && !runtime::is_json_output() {
+                println!("  {} 已保存密钥 {}", "✓".green(), name.as_str().bold());
+            }
+        }
+        Args::Get { name } => {
+            let store = SecretStore::open(&dir)?;
+            let value = store
+                .get(name)
+                .with_context(|| format!("未找到密钥: {name}"))?;
+            if runtime::is_json_output() {
+                runtime::state()
+                    .emitter
+                    .json(&serde_json::json!({ "name": name, "value": value }))?;
+            } else {
+                println!("{value}");
+            }
+        }
+        Args::List => {
+            let store = SecretStore::open(&dir)?;
+            let names: Vec<&str> = store.names().collect();
+            if runtime::is_json_output() {
+                runtime::state().emitter.json(&names)?;
+            } else if names.is_empty() {
+                println!("  暂无密钥");
+            } else {
+                for name in names {
+                    println!("  {name}");
+                }
+            }
+        }
+        Args::Rm { name } => {
+            let mut store = SecretStore::open(&dir)?;
+            if !store.remove(name) {
+                bail!("未找到密钥: {name}");
+            }
+            store.save()?;
+            if !runtime::is_quiet() && !runtime::is_json_output() {
+                println!("  {} 已删除密钥 {}", "✓".green(), name.as_str().bold());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 终端中隐藏输入；否则读取 stdin 第一行
+fn read_value(name: &str) -> Result<String> {
+    if io::stdin().is_terminal() {
+        return dialoguer::Password::new()
+            .with_prompt(format!("{name} 的值"))
+            .interact()
+            .context("读取密钥值失败");
+    }
+    let mut line = String::new();
+    io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .context("读取 stdin 失败")?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
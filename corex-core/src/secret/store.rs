@@ -0,0 +1,285 @@
+//! 本地加密密钥库：`~/.corex/secrets.enc`（ChaCha20-Poly1305），主密钥 `~/.corex/secret.key`
+//!
+//! 威胁模型：主密钥以明文保存在密钥库旁，仅靠文件权限（Unix 0600，Windows 为用户目录 ACL）保护。
+//! 加密只防止 `secrets.enc` 单独泄露（误提交、备份、同步到别处）时被读取，
+//! 不防同一用户下的其他进程或能读取该目录的人——拿到 `secret.key` 即可解密全部密钥。
+
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use anyhow::{Context, Result, anyhow, bail};
+use chacha20poly1305::aead::{Aead, Generate, Key, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use serde_json::Value;
+
+use crate::utils::redact;
+
+/// 密钥库文件头
+const MAGIC: &[u8; 4] = b"CXS1";
+const NONCE_LEN: usize = 12;
+
+/// 密钥引用前缀
+const PREFIX: &str = "${secret.";
+
+/// 密钥库目录：`COREX_SECRETS_DIR` 或 `~/.corex`
+pub fn secrets_dir() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("COREX_SECRETS_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    Ok(dirs::home_dir().context("无法获取用户目录")?.join(".corex"))
+}
+
+/// 密钥名：字母、数字、`_`、`-`（`.` 会与引用路径冲突）
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        bail!("密钥名无效: '{name}'（仅允许字母、数字、_ 与 -）");
+    }
+    Ok(())
+}
+
+/// 已解密的密钥库
+#[derive(Debug, Default)]
+pub struct SecretStore {
+    dir: PathBuf,
+    entries: BTreeMap<String, String>,
+}
+
+impl SecretStore {
+    /// 打开目录中的密钥库；文件不存在时为空库
+    pub fn open(dir: &Path) -> Result<Self> {
+        let path = dir.join("secrets.enc");
+        let entries = if path.exists() {
+            let bytes =
+                fs::read(&path).with_context(|| format!("读取密钥库失败: {}", path.display()))?;
+            let plain = decrypt(&read_key(dir)?, &bytes)
+                .with_context(|| format!("密钥库无法解密: {}", path.display()))?;
+            serde_json::from_slice(&plain).context("密钥库已损坏")?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            entries,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries.get(name).map(String::as_str)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    pub fn set(&mut self, name: &str, value: String) -> Result<()> {
+        validate_name(name)?;
+        self.entries.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    /// 删除密钥；不存在时返回 false
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.entries.remove(name).is_some()
+    }
+
+    /// 加密写回；首次写入时生成主密钥
+    pub fn save(&self) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("创建密钥库目录失败: {}", self.dir.display()))?;
+        let key_path = self.dir.join("secret.key");
+        let key = if key_path.exists() {
+            read_key(&self.dir)?
+        } else {
+            let key = Key::<ChaCha20Poly1305>::generate();
+            create_private(&key_path, key.as_slice())
+                .with_context(|| format!("写入主密钥失败: {}", key_path.display()))?;
+            key
+        };
+        let plain = serde_json::to_vec(&self.entries)?;
+        write_private(&self.dir.join("secrets.enc"), &encrypt(&key, &plain)?)
+    }
+}
+
+fn read_key(dir: &Path) -> Result<Key<ChaCha20Poly1305>> {
+    let path = dir.join("secret.key");
+    let bytes = fs::read(&path).with_context(|| format!("读取主密钥失败: {}", path.display()))?;
+    Key::<ChaCha20Poly1305>::try_from(bytes.as_slice())
+        .map_err(|_| anyhow!("主密钥长度无效: {}", path.display()))
+}
+
+fn encrypt(key: &Key<ChaCha20Poly1305>, plain: &[u8]) -> Result<Vec<u8>> {
+    let nonce = Nonce::generate();
+    let sealed = ChaCha20Poly1305::new(key)
+        .encrypt(&nonce, plain)
+        .map_err(|_| anyhow!("加密失败"))?;
+    Ok([MAGIC.as_slice(), nonce.as_slice(), &sealed].concat())
+}
+
+fn decrypt(key: &Key<ChaCha20Poly1305>, bytes: &[u8]) -> Result<Vec<u8>> {
+    let rest = bytes.strip_prefix(MAGIC).context("文件头无效")?;
+    if rest.len() < NONCE_LEN {
+        bail!("文件过短");
+    }
+    let (nonce, sealed) = rest.split_at(NONCE_LEN);
+    let nonce = Nonce::try_from(nonce).map_err(|_| anyhow!("nonce 无效"))?;
+    ChaCha20Poly1305::new(key)
+        .decrypt(&nonce, sealed)
+        .map_err(|_| anyhow!("主密钥不匹配或文件被篡改"))
+}
+
+/// 新建仅当前用户可读写的文件（Unix 创建时即为 0600）；文件已存在时报错
+fn create_private(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(bytes)?;
+    file.sync_all()
+}
+
+/// 写入同目录的临时文件后替换目标，文件在任何时刻都不会以更宽的权限存在
+fn write_private(path: &Path, bytes: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    let _ = fs::remove_file(&tmp);
+    create_private(&tmp, bytes)
+        .and_then(|()| fs::rename(&tmp, path))
+        .with_context(|| format!("写入失败: {}", path.display()))
+}
+
+/// 进程内缓存的密钥库，及加载时 `secrets.enc` 的修改时间与大小
+struct Cached {
+    stamp: Option<(SystemTime, u64)>,
+    store: SecretStore,
+}
+
+/// 解析 `${secret.<name>}`：密钥库文件变化（修改时间 / 大小）后重新加载，加载失败不缓存；
+/// 解析出的值登记脱敏
+pub fn resolve(name: &str) -> Option<String> {
+    static CACHE: Mutex<Option<Cached>> = Mutex::new(None);
+    let dir = secrets_dir().ok()?;
+    let stamp = fs::metadata(dir.join("secrets.enc"))
+        .and_then(|m| Ok((m.modified()?, m.len())))
+        .ok();
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let fresh = cache
+        .as_ref()
+        .is_some_and(|c| c.store.dir == dir && c.stamp == stamp);
+    if !fresh {
+        *cache = match SecretStore::open(&dir) {
+            Ok(store) => Some(Cached { stamp, store }),
+            Err(e) => {
+                tracing::warn!("加载密钥库失败: {e:#}");
+                None
+            }
+        };
+    }
+    let value = cache.as_ref()?.store.get(name)?;
+    redact::register(value);
+    Some(value.to_string())
+}
+
+/// 替换 JSON 字符串中的 `${secret.<name>}`（IPC 参数用），未知密钥原样保留
+pub fn expand_value(value: &mut Value) {
+    match value {
+        Value::String(s) if s.contains(PREFIX) => *s = expand(s),
+        Value::Array(arr) => arr.iter_mut().for_each(expand_value),
+        Value::Object(map) => map.values_mut().for_each(expand_value),
+        _ => {}
+    }
+}
+
+fn expand(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(PREFIX) {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + PREFIX.len()..];
+        let resolved = after
+            .find('}')
+            .and_then(|end| Some((end, resolve(&after[..end])?)));
+        match resolved {
+            Some((end, value)) => {
+                out.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(PREFIX);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_round_trips_encrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = SecretStore::open(dir.path()).unwrap();
+        store.set("ARCHIVE_PASSWORD", "p@ss w0rd".into()).unwrap();
+        store.set("api-token", "tok".into()).unwrap();
+        assert!(store.set("a.b", "x".into()).is_err());
+        store.save().unwrap();
+
+        let raw = fs::read(dir.path().join("secrets.enc")).unwrap();
+        assert!(raw.starts_with(MAGIC));
+        assert!(!String::from_utf8_lossy(&raw).contains("p@ss w0rd"));
+
+        let mut store = SecretStore::open(dir.path()).unwrap();
+        assert_eq!(store.get("ARCHIVE_PASSWORD"), Some("p@ss w0rd"));
+        assert_eq!(
+            store.names().collect::<Vec<_>>(),
+            ["ARCHIVE_PASSWORD", "api-token"]
+        );
+        assert!(store.remove("api-token"));
+        assert!(!store.remove("api-token"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn files_are_created_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = SecretStore::open(dir.path()).unwrap();
+        store.set("TOKEN", "v".into()).unwrap();
+        store.save().unwrap();
+        store.save().unwrap();
+        for name in ["secret.key", "secrets.enc"] {
+            let mode = fs::metadata(dir.path().join(name))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600, "{name}");
+        }
+        assert!(!dir.path().join("secrets.tmp").exists());
+    }
+
+    #[test]
+    fn wrong_key_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = SecretStore::open(dir.path()).unwrap();
+        store.set("TOKEN", "v".into()).unwrap();
+        store.save().unwrap();
+
+        fs::write(dir.path().join("secret.key"), [7u8; 32]).unwrap();
+        let err = SecretStore::open(dir.path()).unwrap_err();
+        assert!(format!("{err:#}").contains("主密钥不匹配"), "{err:#}");
+    }
+}
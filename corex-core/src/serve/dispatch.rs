@@ -5,7 +5,9 @@ use anyhow::Result;
 use serde_json::Value;
 
 use crate::invoke::{InvokeContext, WireArgs, invoke, ipc_data};
+use crate::secret::store::expand_value;
 use crate::serve::state::DaemonState;
+use crate::utils::redact;
 
 /// 模块 dispatch 结果
 pub struct DispatchResult {
@@ -29,6 +31,11 @@ pub fn dispatch(
         }
     }
 
+    #[cfg(feature = "pipeline")]
+    if module == crate::pipeline::subpipeline::MODULE {
+        return run_pipeline(wire.flags);
+    }
+
     let ctx = InvokeContext::daemon(state);
     let result = invoke(module, wire, &ctx)?;
     Ok(DispatchResult {
@@ -37,7 +44,24 @@ pub fn dispatch(
     })
 }
 
-/// 处理单条 invoke 请求
+/// `module: pipeline`：执行整条 Pipeline，args 为 `{ pipeline, config?, profile?, inputs? }`
+#[cfg(feature = "pipeline")]
+fn run_pipeline(args: Value) -> Result<DispatchResult> {
+    use crate::pipeline::report::RunStatus;
+    use crate::pipeline::{runner, subpipeline};
+
+    let report = runner::run_ipc(args)?;
+    if report.status != RunStatus::Success {
+        return Err(report.into_err());
+    }
+    let artifact = subpipeline::artifact(&report);
+    Ok(DispatchResult {
+        path: artifact.path,
+        data: Some(Value::Object(artifact.data.into_iter().collect())),
+    })
+}
+
+/// 处理单条 invoke 请求：args 中的 `${secret.*}` 在此解析，响应中的密钥值脱敏
 pub fn handle_invoke(
     state: &mut DaemonState,
     id: u64,
@@ -45,25 +69,48 @@ pub fn handle_invoke(
     action: Option<String>,
     format: Option<String>,
     algorithm: Option<String>,
-    args: Value,
+    mut args: Value,
 ) -> crate::serve::protocol::Response {
     let start = Instant::now();
+    expand_value(&mut args);
     let wire = WireArgs {
         action,
         format,
         algorithm,
         flags: args,
     };
-    match dispatch(state, module, wire) {
-        Ok(result) => crate::serve::protocol::Response::success(
-            id,
-            result.path.map(|p| p.to_string_lossy().into_owned()),
-            result.data,
-            start.elapsed().as_millis() as u64,
-        ),
+    respond(id, start, dispatch(state, module, wire))
+}
+
+/// 处理 `module: pipeline` 请求：不访问 DaemonState，由连接线程直接执行，长时间运行不阻塞 dispatch 线程
+#[cfg(feature = "pipeline")]
+pub fn handle_pipeline(id: u64, mut args: Value) -> crate::serve::protocol::Response {
+    let start = Instant::now();
+    expand_value(&mut args);
+    respond(id, start, run_pipeline(args))
+}
+
+/// 组装响应：响应中的密钥值脱敏
+fn respond(
+    id: u64,
+    start: Instant,
+    result: Result<DispatchResult>,
+) -> crate::serve::protocol::Response {
+    match result {
+        Ok(mut result) => {
+            if let Some(data) = result.data.as_mut() {
+                redact::mask_value(data);
+            }
+            crate::serve::protocol::Response::success(
+                id,
+                result.path.map(|p| redact::mask(&p.to_string_lossy())),
+                result.data,
+                start.elapsed().as_millis() as u64,
+            )
+        }
         Err(err) => crate::serve::protocol::Response::failure(
             id,
-            err.to_string(),
+            redact::mask(&err.to_string()),
             start.elapsed().as_millis() as u64,
         ),
     }
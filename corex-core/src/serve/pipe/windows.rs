@@ -3,6 +3,8 @@ use std::fs::File;
 use std::io::{self, BufRead, BufReader, Write};
 use std::os::windows::ffi::OsStrExt;
 use std::os::windows::io::{FromRawHandle, IntoRawHandle};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
 
 use windows::Win32::Foundation::{CloseHandle, HANDLE};
 use windows::Win32::Storage::FileSystem::{
@@ -17,12 +19,25 @@ use windows::core::PCWSTR;
 
 use crate::serve::ServeOptions;
 use crate::serve::dispatch::handle_invoke;
+#[cfg(feature = "pipeline")]
+use crate::serve::dispatch::handle_pipeline;
 use crate::serve::protocol::{self, Request};
 use crate::serve::state::DaemonState;
 
 const PIPE_BUFFER_SIZE: u32 = 65_536;
 const MAX_LINE_BYTES: usize = 64 * 1024;
 
+/// 连接线程交给 dispatch 线程的消息
+enum Job {
+    /// 需独占 DaemonState 的请求，结果经 Sender 回传连接线程
+    Invoke(
+        Box<dyn FnOnce(&mut DaemonState) -> protocol::Response + Send>,
+        Sender<protocol::Response>,
+    ),
+    Shutdown,
+}
+
+/// 每个客户端连接独立线程；DaemonState 仅由当前（dispatch）线程访问
 pub fn run_server(options: &ServeOptions, state: &mut DaemonState) -> anyhow::Result<()> {
     let pipe_name = to_wide(&options.pipe_name);
 
@@ -31,6 +46,24 @@ pub fn run_server(options: &ServeOptions, state: &mut DaemonState) -> anyhow::Re
         options.pipe_name
     );
 
+    let (jobs, queue) = mpsc::channel();
+    thread::spawn(move || accept_loop(&pipe_name, &jobs));
+
+    for job in queue {
+        match job {
+            Job::Invoke(run, reply) => {
+                let _ = reply.send(run(&mut *state));
+            }
+            Job::Shutdown => break,
+        }
+    }
+
+    eprintln!("corex-serve: 收到 shutdown，退出");
+    Ok(())
+}
+
+/// 循环接受连接，每个客户端交给独立线程处理
+fn accept_loop(pipe_name: &[u16], jobs: &Sender<Job>) {
     loop {
         let handle = unsafe {
             CreateNamedPipeW(
@@ -67,37 +100,33 @@ pub fn run_server(options: &ServeOptions, state: &mut DaemonState) -> anyhow::Re
             }
         }
 
-        let should_continue = match handle_client(handle, state) {
-            Ok(continue_running) => continue_running,
-            Err(err) => {
+        let file = pipe_file(handle);
+        let jobs = jobs.clone();
+        thread::spawn(move || {
+            if let Err(err) = handle_client(file, &jobs) {
                 eprintln!("corex-serve: 客户端处理错误: {err}");
-                true
             }
-        };
-
-        if !should_continue {
-            eprintln!("corex-serve: 收到 shutdown，退出");
-            break;
-        }
+        });
     }
-
-    Ok(())
 }
 
-/// 处理单个客户端连接，返回 false 表示 Daemon 应退出
-fn handle_client(handle: HANDLE, state: &mut DaemonState) -> anyhow::Result<bool> {
-    let file = pipe_file(handle);
+/// 处理单个客户端连接：`module: pipeline` 在本线程执行，其余请求交给 dispatch 线程
+fn handle_client(file: File, jobs: &Sender<Job>) -> anyhow::Result<()> {
     let mut reader = BufReader::new(file);
-    let mut result = Ok(true);
+    let mut result = Ok(());
 
     loop {
-        match read_line_limited(&mut reader, MAX_LINE_BYTES) {
+        let response = match read_line_limited(&mut reader, MAX_LINE_BYTES) {
             Ok(None) => break,
             Ok(Some(line)) => match protocol::parse_request(&line) {
                 Ok(Request::Shutdown) => {
-                    result = Ok(false);
+                    let _ = jobs.send(Job::Shutdown);
                     break;
                 }
+                #[cfg(feature = "pipeline")]
+                Ok(Request::Invoke {
+                    id, module, args, ..
+                }) if module == crate::pipeline::subpipeline::MODULE => handle_pipeline(id, args),
                 Ok(Request::Invoke {
                     id,
                     module,
@@ -105,20 +134,19 @@ fn handle_client(handle: HANDLE, state: &mut DaemonState) -> anyhow::Result<bool
                     format,
                     algorithm,
                     args,
-                }) => {
-                    let response =
-                        handle_invoke(state, id, &module, action, format, algorithm, args);
-                    write_response(reader.get_mut(), &response)?;
-                }
-                Err(err) => {
-                    let response = protocol::Response::failure(0, err.to_string(), 0);
-                    write_response(reader.get_mut(), &response)?;
-                }
+                }) => dispatch(jobs, id, move |state| {
+                    handle_invoke(state, id, &module, action, format, algorithm, args)
+                }),
+                Err(err) => protocol::Response::failure(0, err.to_string(), 0),
             },
             Err(err) => {
                 result = Err(err.into());
                 break;
             }
+        };
+        if let Err(err) = write_response(reader.get_mut(), &response) {
+            result = Err(err);
+            break;
         }
     }
 
@@ -126,6 +154,21 @@ fn handle_client(handle: HANDLE, state: &mut DaemonState) -> anyhow::Result<bool
     result
 }
 
+/// 交给 dispatch 线程执行并等待结果
+fn dispatch(
+    jobs: &Sender<Job>,
+    id: u64,
+    run: impl FnOnce(&mut DaemonState) -> protocol::Response + Send + 'static,
+) -> protocol::Response {
+    let (reply, response) = mpsc::channel();
+    if jobs.send(Job::Invoke(Box::new(run), reply)).is_err() {
+        return protocol::Response::failure(id, "Daemon 正在退出", 0);
+    }
+    response
+        .recv()
+        .unwrap_or_else(|_| protocol::Response::failure(id, "Daemon 正在退出", 0))
+}
+
 fn write_response(file: &mut File, response: &protocol::Response) -> anyhow::Result<()> {
     let json = serde_json::to_string(response)?;
     file.write_all(json.as_bytes())?;
@@ -1,10 +1,13 @@
 use clap::Parser;
+#[cfg(feature = "pipeline")]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::utils::verifier;
 
 /// shade 图片处理参数
 #[derive(Debug, Parser, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "pipeline", derive(JsonSchema))]
 pub struct Args {
     /// 输入图片路径或目录
     #[arg(short, long, value_parser = verifier::path)]
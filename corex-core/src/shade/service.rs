@@ -13,7 +13,7 @@ use image::{DynamicImage, ImageEncoder, ImageFormat};
 use walkdir::WalkDir;
 
 use crate::shade::schema::Args;
-use crate::utils::{file, notify, progress};
+use crate::utils::{file, notify, observe, progress};
 
 #[derive(Debug, Clone)]
 pub struct Output {
@@ -60,14 +60,7 @@ pub fn image_task(args: &Args) -> Result<()> {
             .follow_links(false)
             .into_iter()
             .filter_map(|e| e.ok())
-            .filter(|e| {
-                e.path().is_file()
-                    && e.path()
-                        .extension()
-                        .and_then(|ext| ext.to_str())
-                        .map(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
-                        .unwrap_or(false)
-            })
+            .filter(|e| e.path().is_file() && is_image(e.path()))
             .map(|e| e.path().to_path_buf())
             .collect()
     } else {
@@ -75,12 +68,17 @@ pub fn image_task(args: &Args) -> Result<()> {
     };
 
     let file_count = entries.len();
+    let human = !crate::runtime::is_quiet() && !crate::runtime::is_json_output();
     if file_count == 0 {
-        println!("没有找到图片文件");
+        if human {
+            println!("没有找到图片文件");
+        }
         return Ok(());
     }
 
-    println!("找到 {} 个图片文件", file_count);
+    if human {
+        println!("找到 {} 个图片文件", file_count);
+    }
 
     // 确定输出格式
     let out_format = if let Some(ref fmt) = args.format {
@@ -132,14 +130,11 @@ pub fn image_task(args: &Args) -> Result<()> {
             create_dir_all(parent)?;
         }
 
-        let img = image::open(entry_path)
-            .with_context(|| format!("打开图片: {}", entry_path.display()))?;
-
-        save_image(&img, &out_path, &out_format, args.quality)
-            .with_context(|| format!("保存图片: {}", out_path.display()))?;
+        convert_file(entry_path, &out_path, &out_format, args.quality)?;
 
         total_bytes += out_path.metadata().map(|m| m.len()).unwrap_or(0);
         pb.inc(1);
+        observe::report(pb.position(), Some(total_bytes));
     }
 
     let elapsed = start.elapsed();
@@ -155,6 +150,21 @@ pub fn image_task(args: &Args) -> Result<()> {
     Ok(())
 }
 
+/// 按扩展名判断是否为支持的图片
+pub(crate) fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// 转换单张图片：读取 `from` 并按格式写入 `to`
+pub(crate) fn convert_file(from: &Path, to: &Path, format: &ImageFormat, quality: u8) -> Result<()> {
+    let img = image::open(from).with_context(|| format!("打开图片: {}", from.display()))?;
+    save_image(&img, to, format, quality)
+        .with_context(|| format!("保存图片: {}", to.display()))
+}
+
 /// 保存图片到指定路径，根据格式和质量参数编码
 ///
 /// - JPEG: quality 控制压缩质量 (1-100)
@@ -204,7 +214,7 @@ fn save_image(img: &DynamicImage, path: &Path, format: &ImageFormat, quality: u8
 }
 
 /// 解析格式字符串为 ImageFormat
-fn parse_format(s: &str) -> Result<ImageFormat> {
+pub(crate) fn parse_format(s: &str) -> Result<ImageFormat> {
     match s.to_lowercase().as_str() {
         "png" => Ok(ImageFormat::Png),
         "jpg" | "jpeg" => Ok(ImageFormat::Jpeg),
@@ -217,7 +227,7 @@ fn parse_format(s: &str) -> Result<ImageFormat> {
 }
 
 /// ImageFormat -> 文件扩展名
-fn format_ext(format: &ImageFormat) -> &'static str {
+pub(crate) fn format_ext(format: &ImageFormat) -> &'static str {
     match format {
         ImageFormat::Png => "png",
         ImageFormat::Jpeg => "jpg",
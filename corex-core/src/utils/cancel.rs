@@ -0,0 +1,105 @@
+//! 协作式取消：超时 / Ctrl+C 等场景下通知正在执行的模块尽快退出
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use anyhow::{Result, bail};
+
+/// 取消令牌（克隆后共享同一状态；子令牌随父令牌一并取消）
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    flag: Arc<AtomicBool>,
+    parent: Option<Box<CancelToken>>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 子令牌：可单独取消，父令牌取消时同样视为已取消
+    pub fn child(&self) -> Self {
+        Self {
+            flag: Arc::default(),
+            parent: Some(Box::new(self.clone())),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::SeqCst) || self.parent.as_ref().is_some_and(|p| p.is_cancelled())
+    }
+
+    /// 已取消时返回错误
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            bail!("操作已取消");
+        }
+        Ok(())
+    }
+}
+
+/// 进程级关停令牌：Ctrl+C 时取消，Pipeline 与 watch / cron 循环以此为根
+pub fn shutdown() -> &'static CancelToken {
+    static SHUTDOWN: OnceLock<CancelToken> = OnceLock::new();
+    SHUTDOWN.get_or_init(CancelToken::new)
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+}
+
+/// 在当前线程绑定令牌后执行 `f`；模块内通过 [`current`] / [`check`] 感知取消
+pub fn scope<R>(token: &CancelToken, f: impl FnOnce() -> R) -> R {
+    let prev = CURRENT.with(|c| c.replace(Some(token.clone())));
+    let result = f();
+    CURRENT.with(|c| *c.borrow_mut() = prev);
+    result
+}
+
+/// 当前线程绑定的令牌（未绑定时为 None）
+pub fn current() -> Option<CancelToken> {
+    CURRENT.with(|c| c.borrow().clone())
+}
+
+/// 当前线程令牌已取消时返回错误；供遍历循环等调用
+pub fn check() -> Result<()> {
+    CURRENT.with(|c| match c.borrow().as_ref() {
+        Some(token) => token.check(),
+        None => Ok(()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_follows_scoped_token() {
+        let token = CancelToken::new();
+        assert!(check().is_ok());
+        scope(&token, || {
+            assert!(check().is_ok());
+            token.cancel();
+            assert!(check().is_err());
+        });
+        assert!(check().is_ok());
+        assert!(current().is_none());
+    }
+
+    #[test]
+    fn child_follows_parent() {
+        let parent = CancelToken::new();
+        let child = parent.child();
+        child.cancel();
+        assert!(!parent.is_cancelled());
+
+        let child = parent.child();
+        parent.cancel();
+        assert!(child.is_cancelled());
+    }
+}
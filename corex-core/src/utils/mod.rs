@@ -1,3 +1,4 @@
+pub mod cancel;
 pub mod file;
 #[cfg(feature = "glob")]
 pub mod filter;
@@ -5,7 +6,9 @@ pub mod filter;
 pub use filter::Filter;
 #[cfg(feature = "notify")]
 pub mod notify;
+pub mod observe;
 pub mod paths;
 #[cfg(feature = "progress")]
 pub mod progress;
+pub mod redact;
 pub mod verifier;
@@ -0,0 +1,39 @@
+//! 步骤进度上报：Pipeline 执行步骤时在当前线程绑定回调，模块经 [`report`] 上报累计条目数 / 字节数
+
+use std::cell::RefCell;
+use std::sync::Arc;
+
+/// 进度回调，参数 `(items, bytes)` 均为累计值
+#[derive(Clone)]
+pub struct Progress(Arc<dyn Fn(u64, Option<u64>) + Send + Sync>);
+
+impl Progress {
+    pub fn new(f: impl Fn(u64, Option<u64>) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    pub fn report(&self, items: u64, bytes: Option<u64>) {
+        (self.0)(items, bytes)
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Progress>> = const { RefCell::new(None) };
+}
+
+/// 在当前线程绑定回调后执行 `f`
+pub fn scope<R>(progress: &Progress, f: impl FnOnce() -> R) -> R {
+    let prev = CURRENT.with(|c| c.replace(Some(progress.clone())));
+    let result = f();
+    CURRENT.with(|c| *c.borrow_mut() = prev);
+    result
+}
+
+/// 上报进度；未绑定回调时忽略
+pub fn report(items: u64, bytes: Option<u64>) {
+    CURRENT.with(|c| {
+        if let Some(progress) = c.borrow().as_ref() {
+            progress.report(items, bytes);
+        }
+    });
+}
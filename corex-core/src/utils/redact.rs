@@ -0,0 +1,126 @@
+//! 敏感值脱敏：已解析的 `${secret.*}` 值登记于进程级注册表，
+//! 人类输出 / tracing 日志 / RunReport / exec 输出 / IPC 响应统一替换为 `***`
+
+use std::io::{self, Write};
+use std::sync::RwLock;
+
+use serde_json::Value;
+
+/// 脱敏占位
+pub const MASK: &str = "***";
+
+static SECRETS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// 登记需脱敏的值（空串忽略）
+pub fn register(value: &str) {
+    if value.is_empty() {
+        return;
+    }
+    let mut secrets = SECRETS.write().unwrap_or_else(|e| e.into_inner());
+    if !secrets.iter().any(|s| s == value) {
+        secrets.push(value.to_string());
+        // 长值优先替换，避免短值截断其中一部分
+        secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+    }
+}
+
+/// 文本是否含已登记的值
+pub fn contains_secret(text: &str) -> bool {
+    let secrets = SECRETS.read().unwrap_or_else(|e| e.into_inner());
+    secrets.iter().any(|s| text.contains(s.as_str()))
+}
+
+/// 替换文本中的已登记值
+pub fn mask(text: &str) -> String {
+    let secrets = SECRETS.read().unwrap_or_else(|e| e.into_inner());
+    let mut out = text.to_string();
+    for secret in secrets.iter() {
+        if out.contains(secret.as_str()) {
+            out = out.replace(secret.as_str(), MASK);
+        }
+    }
+    out
+}
+
+/// 替换序列化后的 JSON 文本中的已登记值（按 JSON 转义后的形式匹配）
+pub fn mask_json(json: &str) -> String {
+    let secrets = SECRETS.read().unwrap_or_else(|e| e.into_inner());
+    let mut out = json.to_string();
+    for secret in secrets.iter() {
+        let quoted = Value::String(secret.clone()).to_string();
+        let escaped = &quoted[1..quoted.len() - 1];
+        if out.contains(escaped) {
+            out = out.replace(escaped, MASK);
+        }
+    }
+    out
+}
+
+/// 递归替换 JSON 中字符串（含对象键）的已登记值
+pub fn mask_value(value: &mut Value) {
+    match value {
+        Value::String(s) if contains_secret(s) => *s = mask(s),
+        Value::Array(arr) => arr.iter_mut().for_each(mask_value),
+        Value::Object(map) => {
+            if map.keys().any(|k| contains_secret(k)) {
+                *map = std::mem::take(map)
+                    .into_iter()
+                    .map(|(k, v)| (mask(&k), v))
+                    .collect();
+            }
+            map.values_mut().for_each(mask_value);
+        }
+        _ => {}
+    }
+}
+
+/// 写入前脱敏的 Writer（tracing 每条事件整体写入一次）
+pub struct MaskedWriter<W: Write>(pub W);
+
+impl<W: Write> Write for MaskedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        if contains_secret(&text) {
+            self.0.write_all(mask(&text).as_bytes())?;
+        } else {
+            self.0.write_all(buf)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn masks_text_json_and_writer() {
+        register("s3cr3t-token");
+        register("s3cr3t");
+        register("");
+        assert_eq!(
+            mask("auth s3cr3t-token / s3cr3t"),
+            format!("auth {MASK} / {MASK}")
+        );
+
+        let mut value = json!({ "error": "bad s3cr3t", "list": ["s3cr3t"], "n": 1 });
+        mask_value(&mut value);
+        assert_eq!(
+            value,
+            json!({ "error": "bad ***", "list": ["***"], "n": 1 })
+        );
+
+        register("say \"hi\"");
+        let line = serde_json::to_string(&json!({ "msg": "say \"hi\" s3cr3t" })).unwrap();
+        assert_eq!(mask_json(&line), r#"{"msg":"*** ***"}"#);
+
+        let mut out = MaskedWriter(Vec::new());
+        out.write_all(b"token=s3cr3t-token\n").unwrap();
+        assert_eq!(String::from_utf8(out.0).unwrap(), "token=***\n");
+    }
+}
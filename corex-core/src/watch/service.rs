@@ -12,11 +12,14 @@ use notify_debouncer_full::{
 };
 
 use crate::pipeline::config::{
-    find_config_path, load_config, validate_config, PipelineConfig, PipelinesConfig, WatchConfig,
+    find_config_path, load_configs, validate_config, PipelineConfig, PipelinesConfig, WatchConfig,
 };
 use crate::pipeline::context::PipelineContext;
 use crate::pipeline::guard::{self, RunningSet};
+use crate::pipeline::interrupt;
+use crate::runtime;
 use crate::utils::Filter;
+use crate::utils::cancel;
 use crate::watch::schema::Args;
 
 #[derive(Debug)]
@@ -54,13 +57,7 @@ pub fn run(args: &Args) -> Result<()> {
                 .as_deref()
                 .map(PathBuf::from)
                 .unwrap_or_else(find_config_path);
-            if !config_path.exists() {
-                anyhow::bail!(
-                    "配置文件未找到：{}，请先运行 `corex schedule generate` 生成配置",
-                    config_path.display()
-                );
-            }
-            let cfg = load_config(&config_path)?;
+            let cfg = load_configs(config.as_deref(), None)?;
             validate_config(&cfg)?;
             let opts = WatchOpts {
                 debounce_ms: *debounce_ms,
@@ -97,6 +94,7 @@ pub(crate) fn serve(
     opts: &WatchOpts,
     targets: Option<Vec<WatchTarget>>,
 ) -> Result<()> {
+    interrupt::install();
     let targets = match targets {
         Some(t) => t,
         None => resolve(config, ids, opts)?,
@@ -109,51 +107,54 @@ pub(crate) fn serve(
         );
     }
 
-    print_banner("Corex · 文件监听");
+    if !runtime::is_json_output() {
+        print_banner("Corex · 文件监听");
 
-    println!(
-        "  {} 已加载 {} 条监听 Pipeline（共 {} 条）\n",
-        "✓".green().bold(),
-        targets.len(),
-        config.pipelines.len()
-    );
-
-    for target in &targets {
-        let desc = target
-            .pipeline
-            .description
-            .as_deref()
-            .unwrap_or(&target.pipeline.id);
         println!(
-            "  {} {} — debounce: {}ms — cooldown: {}ms — 路径: {}",
-            "▸".cyan(),
-            desc.bold(),
-            target.debounce_ms,
-            target.cooldown_ms,
-            target
-                .paths
-                .iter()
-                .map(|p| p.display().to_string())
-                .collect::<Vec<_>>()
-                .join(", ")
-                .dim()
+            "  {} 已加载 {} 条监听 Pipeline（共 {} 条）\n",
+            "✓".green().bold(),
+            targets.len(),
+            config.pipelines.len()
         );
+
+        for target in &targets {
+            let desc = target
+                .pipeline
+                .description
+                .as_deref()
+                .unwrap_or(&target.pipeline.id);
+            println!(
+                "  {} {} — debounce: {}ms — cooldown: {}ms — 路径: {}",
+                "▸".cyan(),
+                desc.bold(),
+                target.debounce_ms,
+                target.cooldown_ms,
+                target
+                    .paths
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+                    .dim()
+            );
+        }
+        println!();
     }
-    println!();
 
     run_loop(config, targets, opts)
 }
 
-/// 监听循环（假定 targets 已通过 resolve 校验）
+/// 监听循环（假定 targets 已通过 resolve 校验）；Ctrl+C 后等待运行中的 Pipeline 结束再返回
 pub(crate) fn run_loop(
     config: &PipelinesConfig,
     targets: Vec<WatchTarget>,
     opts: &WatchOpts,
 ) -> Result<()> {
     let running = opts.running.clone().unwrap_or_else(guard::new_set);
-    let variables = config.variables.clone();
+    let base = PipelineContext::from_config(config);
 
-    if opts.immediate {
+    let human = !runtime::is_json_output();
+    if opts.immediate && human {
         println!("  {} 启动时执行 Pipeline...\n", "▶".yellow().bold());
     }
 
@@ -164,7 +165,7 @@ pub(crate) fn run_loop(
         let filter = target.filter;
         let roots = target.paths.clone();
         let running = Arc::clone(&running);
-        let variables = variables.clone();
+        let base = base.clone();
         let pipeline_id = pipeline.id.clone();
         let debounce_ms = target.debounce_ms;
         let cooldown = Duration::from_millis(target.cooldown_ms);
@@ -174,7 +175,7 @@ pub(crate) fn run_loop(
             guard::spawn(
                 Arc::clone(&running),
                 &pipeline,
-                &variables,
+                &base,
                 "启动",
                 Some(Arc::clone(&last_finished)),
             );
@@ -192,7 +193,7 @@ pub(crate) fn run_loop(
                     debounce_ms,
                     cooldown,
                     running,
-                    variables,
+                    base,
                     last_finished,
                 ) {
                     eprintln!(
@@ -208,11 +209,14 @@ pub(crate) fn run_loop(
         join_handles.push(handle);
     }
 
-    println!("  {} 等待文件变更...（Ctrl+C 退出）\n", "⏳".yellow());
+    if human {
+        println!("  {} 等待文件变更...（Ctrl+C 退出）\n", "⏳".yellow());
+    }
 
     for handle in join_handles {
         let _ = handle.join();
     }
+    guard::wait_idle(&running);
 
     Ok(())
 }
@@ -227,7 +231,7 @@ fn run_target_loop(
     debounce_ms: u64,
     cooldown: Duration,
     running: RunningSet,
-    variables: std::collections::HashMap<String, String>,
+    base: PipelineContext,
     last_finished: guard::LastFinished,
 ) -> Result<()> {
     let pipeline_id = pipeline.id.clone();
@@ -245,7 +249,12 @@ fn run_target_loop(
     // 同时挂父目录：vue-cli 等会删重建 roots，根目录句柄失效后仍能靠父目录感知重建
     attach_watches(&mut debouncer, &roots, &pipeline_id)?;
 
-    while let Ok(result) = rx.recv() {
+    while !cancel::shutdown().is_cancelled() {
+        let result = match rx.recv_timeout(guard::SHUTDOWN_POLL) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => continue,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
         if let Err(errors) = &result {
             eprintln!(
                 "  {} Pipeline '{}' 监听异常: {:?}（将重新挂载）",
@@ -288,7 +297,7 @@ fn run_target_loop(
         guard::spawn(
             Arc::clone(&running),
             &pipeline,
-            &variables,
+            &base,
             "变更",
             Some(Arc::clone(&last_finished)),
         );
@@ -357,6 +366,9 @@ fn ensure_watches(debouncer: &mut FsDebouncer, roots: &[PathBuf], pipeline_id: &
 
 fn wait_roots_exist(roots: &[PathBuf]) {
     loop {
+        if cancel::shutdown().is_cancelled() {
+            return;
+        }
         if roots.iter().all(|p| p.exists()) {
             std::thread::sleep(Duration::from_millis(200));
             if roots.iter().all(|p| p.exists()) {
@@ -450,7 +462,6 @@ fn collect_targets(
     cli_includes: &[String],
     cli_excludes: &[String],
 ) -> Result<Vec<WatchTarget>> {
-    let parse_ctx = PipelineContext::with_variables(config.variables.clone());
     let filter_set: HashSet<&str> = pipeline_filter.iter().map(String::as_str).collect();
 
     let mut targets = Vec::new();
@@ -464,6 +475,7 @@ fn collect_targets(
             continue;
         }
 
+        let parse_ctx = PipelineContext::with_variables(pipeline.scoped_variables(&config.variables));
         let paths = resolve_watch_paths(&parse_ctx, watch, &pipeline.id)?;
         let filter = build_filter(watch, cli_includes, cli_excludes);
         let debounce_ms = debounce_override.unwrap_or(watch.debounce_ms);
@@ -0,0 +1,77 @@
+//! 步骤增量缓存（inputs / outputs 指纹）测试
+
+use std::fs;
+
+use cx::pipeline::config::{PipelineConfig, StepConfig};
+use cx::pipeline::context::PipelineContext;
+use cx::pipeline::orchestrator::run_pipeline;
+use cx::pipeline::report::{RunReport, RunStatus, StepStatus};
+use serde_json::json;
+
+fn statuses(report: &RunReport) -> Vec<StepStatus> {
+    report.steps.iter().map(|s| s.status).collect()
+}
+
+#[test]
+fn unchanged_inputs_and_outputs_reuse_cached_artifact() {
+    let dir = tempfile::tempdir().unwrap();
+    // 单测试进程内仅此处设置
+    unsafe { std::env::set_var("COREX_CACHE_DIR", dir.path().join("cache")) };
+
+    let src = dir.path().join("src");
+    let out = dir.path().join("out");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("a.txt"), "a").unwrap();
+
+    let pipeline = PipelineConfig {
+        id: "cached".into(),
+        steps: vec![
+            StepConfig {
+                id: "copy".into(),
+                module: "copy".into(),
+                inputs: vec![src.display().to_string()],
+                outputs: vec![out.display().to_string()],
+                params: json!({ "from": src, "to": out, "empty": true }),
+                ..Default::default()
+            },
+            StepConfig {
+                id: "mirror".into(),
+                module: "copy".into(),
+                params: json!({
+                    "from": "${steps.copy.artifact.path}",
+                    "to": dir.path().join("mirror"),
+                    "empty": true
+                }),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    let run = || run_pipeline(&pipeline, &mut PipelineContext::new()).expect("report");
+
+    let first = run();
+    assert_eq!(
+        statuses(&first),
+        vec![StepStatus::Success, StepStatus::Success]
+    );
+
+    // 命中缓存后下游仍能解析产物路径
+    let second = run();
+    assert_eq!(second.status, RunStatus::Success);
+    assert_eq!(
+        statuses(&second),
+        vec![StepStatus::Cached, StepStatus::Success]
+    );
+    assert_eq!(
+        second.steps[0].artifact.as_ref().unwrap().path,
+        Some(out.clone())
+    );
+
+    fs::write(src.join("a.txt"), "changed").unwrap();
+    assert_eq!(run().steps[0].status, StepStatus::Success);
+    assert_eq!(run().steps[0].status, StepStatus::Cached);
+
+    fs::remove_dir_all(&out).unwrap();
+    assert_eq!(run().steps[0].status, StepStatus::Success);
+    assert!(out.join("a.txt").exists());
+}
@@ -37,9 +37,6 @@ Write-Output ($result | ConvertTo-Json -Compress)
 
     let pipeline = PipelineConfig {
         id: "exec-test".into(),
-        description: None,
-        schedule: None,
-        watch: None,
         steps: vec![StepConfig {
             id: "run_script".into(),
             module: "exec".into(),
@@ -51,6 +48,7 @@ Write-Output ($result | ConvertTo-Json -Compress)
             }),
             ..Default::default()
         }],
+        ..Default::default()
     };
 
     let mut ctx = PipelineContext::new();
@@ -10,9 +10,6 @@ use serde_json::json;
 fn failed_pipeline_returns_run_report() {
     let pipeline = PipelineConfig {
         id: "fail-test".into(),
-        description: None,
-        schedule: None,
-        watch: None,
         steps: vec![StepConfig {
             id: "bad".into(),
             module: "copy".into(),
@@ -25,6 +22,7 @@ fn failed_pipeline_returns_run_report() {
             }),
             ..Default::default()
         }],
+        ..Default::default()
     };
 
     let mut ctx = PipelineContext::new();
@@ -33,3 +31,48 @@ fn failed_pipeline_returns_run_report() {
     assert_eq!(report.steps.len(), 1);
     assert_eq!(report.steps[0].id, "bad");
 }
+
+#[test]
+fn scheduling_error_is_folded_into_report() {
+    let pipeline = PipelineConfig {
+        id: "dag-error".into(),
+        steps: vec![StepConfig {
+            id: "cached".into(),
+            module: "copy".into(),
+            inputs: vec!["[".into()],
+            params: json!({ "from": "a", "to": "b", "empty": false }),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+    assert_eq!(report.status, RunStatus::Failed);
+    let error = report.error.as_deref().expect("run error recorded");
+    assert!(error.contains("glob"), "{error}");
+    assert!(report.message().contains("glob"));
+}
+
+#[test]
+fn invalid_when_fails_only_that_step() {
+    let pipeline = PipelineConfig {
+        id: "bad-when".into(),
+        steps: vec![StepConfig {
+            id: "gated".into(),
+            module: "copy".into(),
+            when: Some("var.mode ==".into()),
+            params: json!({ "from": "a", "to": "b", "empty": false }),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+    assert_eq!(report.status, RunStatus::Failed);
+    assert!(report.error.is_none());
+    let (step, error) = report.first_fail().expect("step failure recorded");
+    assert_eq!(step, "gated");
+    assert!(error.contains("when"), "{error}");
+}
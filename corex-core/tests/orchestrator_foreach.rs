@@ -0,0 +1,62 @@
+//! foreach 展开测试
+
+use std::fs;
+
+use cx::pipeline::config::{PipelineConfig, StepConfig};
+use cx::pipeline::context::PipelineContext;
+use cx::pipeline::expand::Foreach;
+use cx::pipeline::orchestrator::run_pipeline;
+use cx::pipeline::report::{RunStatus, StepStatus};
+use serde_json::json;
+
+#[test]
+fn foreach_members_run_and_join_downstream() {
+    let dir = tempfile::tempdir().unwrap();
+    for app in ["h5", "admin"] {
+        fs::write(dir.path().join(format!("{app}.txt")), app).unwrap();
+    }
+    let base = dir.path().display().to_string();
+
+    let pipeline = PipelineConfig {
+        id: "foreach".into(),
+        steps: vec![
+            StepConfig {
+                id: "copy_app".into(),
+                module: "copy".into(),
+                foreach: Some(Foreach::Expr("${var.apps}".into())),
+                params: json!({
+                    "from": "${var.base}/${item}.txt",
+                    "to": "${var.base}/out/${item}.txt",
+                    "empty": false,
+                }),
+                ..Default::default()
+            },
+            StepConfig {
+                id: "collect".into(),
+                module: "copy".into(),
+                params: json!({
+                    "from": "${var.base}/out",
+                    "to": "${var.base}/all",
+                    "empty": false,
+                }),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    ctx.variables.insert("base".into(), base);
+    ctx.variables
+        .insert("apps".into(), r#"["h5", "admin"]"#.into());
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+
+    assert_eq!(report.status, RunStatus::Success);
+    let ids: Vec<&str> = report.steps.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(ids.len(), 3);
+    assert!(ids.contains(&"copy_app[0]") && ids.contains(&"copy_app[1]"));
+    assert_eq!(ids[2], "collect");
+    assert!(report.steps.iter().all(|s| s.status == StepStatus::Success));
+    assert!(dir.path().join("all").join("h5.txt").exists());
+    assert!(dir.path().join("all").join("admin.txt").exists());
+}
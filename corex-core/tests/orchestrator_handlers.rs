@@ -0,0 +1,118 @@
+//! continue_on_error / on_failure / finally 测试
+
+use std::fs;
+use std::path::Path;
+
+use cx::pipeline::config::{PipelineConfig, StepConfig};
+use cx::pipeline::context::PipelineContext;
+use cx::pipeline::orchestrator::run_pipeline;
+use cx::pipeline::report::{RunStatus, StepPhase, StepStatus};
+use serde_json::json;
+
+fn copy_step(id: &str, from: &Path, to: &Path) -> StepConfig {
+    StepConfig {
+        id: id.into(),
+        module: "copy".into(),
+        params: json!({ "from": from, "to": to, "empty": false }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn continue_on_error_tolerates_failure() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("a.txt");
+    fs::write(&src, "a").unwrap();
+
+    let mut bad = copy_step("bad", &dir.path().join("missing"), &dir.path().join("x"));
+    bad.continue_on_error = true;
+    let pipeline = PipelineConfig {
+        id: "tolerate".into(),
+        steps: vec![bad, copy_step("ok", &src, &dir.path().join("b.txt"))],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+
+    assert_eq!(report.status, RunStatus::Success);
+    assert_eq!(report.steps[0].status, StepStatus::FailedTolerated);
+    assert!(report.steps[0].error.is_some());
+    assert_eq!(report.steps[1].status, StepStatus::Success);
+    assert!(dir.path().join("b.txt").exists());
+}
+
+#[test]
+fn on_failure_and_finally_run_after_failed_step() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("a.txt");
+    fs::write(&src, "a").unwrap();
+
+    let pipeline = PipelineConfig {
+        id: "handlers".into(),
+        steps: vec![
+            copy_step("bad", &dir.path().join("missing"), &dir.path().join("x")),
+            copy_step("never", &src, &dir.path().join("never.txt")),
+        ],
+        on_failure: vec![copy_step(
+            "notify",
+            &src,
+            &dir.path().join("${run.status}-${run.failed_step}.txt"),
+        )],
+        finally: vec![copy_step(
+            "cleanup",
+            &src,
+            &dir.path().join("final-${run.status}.txt"),
+        )],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+
+    assert_eq!(report.status, RunStatus::Failed);
+    assert_eq!(report.first_fail().map(|(id, _)| id), Some("bad"));
+    let phases: Vec<(&str, StepPhase, StepStatus)> = report
+        .steps
+        .iter()
+        .map(|s| (s.id.as_str(), s.phase, s.status))
+        .collect();
+    assert_eq!(
+        phases,
+        vec![
+            ("bad", StepPhase::Main, StepStatus::Failed),
+            ("notify", StepPhase::OnFailure, StepStatus::Success),
+            ("cleanup", StepPhase::Finally, StepStatus::Success),
+        ]
+    );
+    assert!(dir.path().join("failed-bad.txt").exists());
+    assert!(dir.path().join("final-failed.txt").exists());
+    assert!(!dir.path().join("never.txt").exists());
+}
+
+#[test]
+fn finally_runs_on_success_and_its_failure_fails_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("a.txt");
+    fs::write(&src, "a").unwrap();
+
+    let pipeline = PipelineConfig {
+        id: "finally".into(),
+        steps: vec![copy_step("ok", &src, &dir.path().join("b.txt"))],
+        on_failure: vec![copy_step("notify", &src, &dir.path().join("notify.txt"))],
+        finally: vec![copy_step(
+            "cleanup",
+            &dir.path().join("missing"),
+            &dir.path().join("x"),
+        )],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+
+    assert_eq!(report.status, RunStatus::Failed);
+    assert_eq!(report.first_fail().map(|(id, _)| id), Some("cleanup"));
+    assert_eq!(report.steps.len(), 2);
+    assert!(!dir.path().join("notify.txt").exists());
+}
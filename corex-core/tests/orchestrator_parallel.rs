@@ -21,14 +21,12 @@ fn uuid_step(id: &str, deps: Vec<&str>) -> StepConfig {
 fn parallel_layer_merges_artifacts_into_context() {
     let pipeline = PipelineConfig {
         id: "fork".into(),
-        description: None,
-        schedule: None,
-        watch: None,
         steps: vec![
             uuid_step("root", vec![]),
             uuid_step("left", vec!["root"]),
             uuid_step("right", vec!["root"]),
         ],
+        ..Default::default()
     };
 
     let mut ctx = PipelineContext::new();
@@ -0,0 +1,138 @@
+//! 失败运行恢复（--resume）测试
+
+use std::fs;
+
+use cx::pipeline::config::{PipelineConfig, StepConfig};
+use cx::pipeline::context::PipelineContext;
+use cx::pipeline::history::Trigger;
+use cx::pipeline::orchestrator::{resume_pipeline, run_pipeline};
+use cx::pipeline::report::{RunStatus, StepStatus};
+use cx::pipeline::state::{self, RunState};
+use serde_json::json;
+
+#[test]
+fn resume_reruns_only_failed_step_and_successors() {
+    let dir = tempfile::tempdir().unwrap();
+    // 单测试进程内仅此处设置
+    unsafe { std::env::set_var("COREX_HISTORY_DIR", dir.path().join("history")) };
+
+    let src = dir.path().join("src");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("a.txt"), "a").unwrap();
+    let late = dir.path().join("late.txt");
+
+    let step = |id: &str, params: serde_json::Value| StepConfig {
+        id: id.into(),
+        module: "copy".into(),
+        params,
+        ..Default::default()
+    };
+    let pipeline = PipelineConfig {
+        id: "resumable".into(),
+        steps: vec![
+            step(
+                "stage",
+                json!({ "from": src, "to": dir.path().join("stage"), "empty": true }),
+            ),
+            step(
+                "late",
+                json!({ "from": late, "to": "${steps.stage.artifact.path}/late.txt", "empty": false }),
+            ),
+            step(
+                "publish",
+                json!({ "from": "${steps.stage.artifact.path}", "to": dir.path().join("publish"), "empty": true }),
+            ),
+        ],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    let failed = run_pipeline(&pipeline, &mut ctx).expect("report");
+    assert_eq!(failed.status, RunStatus::Failed);
+    assert_eq!(failed.first_fail().map(|(id, _)| id), Some("late"));
+    state::save(&RunState::new(Trigger::Manual, &ctx, &failed)).unwrap();
+
+    // 重新执行 stage 会失败：源目录已删除
+    fs::remove_dir_all(&src).unwrap();
+    fs::write(&late, "late").unwrap();
+
+    let previous = state::load("last").unwrap();
+    assert_eq!(previous.report.run_id, failed.run_id);
+    let completed = previous.completed_steps().unwrap();
+    assert_eq!(completed.len(), 1);
+
+    let mut ctx = PipelineContext::with_variables(previous.variables.clone());
+    let report = resume_pipeline(&pipeline, &mut ctx, &failed.run_id, completed).expect("report");
+
+    assert_eq!(report.status, RunStatus::Success);
+    assert_eq!(report.resumed_from.as_deref(), Some(failed.run_id.as_str()));
+    let steps: Vec<(&str, StepStatus)> = report
+        .steps
+        .iter()
+        .map(|s| (s.id.as_str(), s.status))
+        .collect();
+    assert_eq!(
+        steps,
+        vec![
+            ("stage", StepStatus::Success),
+            ("late", StepStatus::Success),
+            ("publish", StepStatus::Success),
+        ]
+    );
+    assert!(dir.path().join("publish").join("late.txt").exists());
+
+    let done = RunState::new(Trigger::Manual, &ctx, &report);
+    assert!(done.completed_steps().is_err());
+}
+
+#[test]
+fn resume_reapplies_exports_of_completed_steps() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("a.txt"), "a").unwrap();
+    let late = dir.path().join("late.txt");
+
+    let mut stage = StepConfig {
+        id: "stage".into(),
+        module: "copy".into(),
+        params: json!({ "from": src, "to": dir.path().join("stage"), "empty": true }),
+        ..Default::default()
+    };
+    stage
+        .exports
+        .insert("staged".into(), "${steps.stage.artifact.path}".into());
+    let pipeline = PipelineConfig {
+        id: "resume-exports".into(),
+        steps: vec![
+            stage,
+            StepConfig {
+                id: "late".into(),
+                module: "copy".into(),
+                params: json!({ "from": late, "to": "${var.staged}/late.txt", "empty": false }),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    let failed = run_pipeline(&pipeline, &mut ctx).expect("report");
+    assert_eq!(failed.first_fail().map(|(id, _)| id), Some("late"));
+    let previous = RunState::new(Trigger::Manual, &ctx, &failed);
+    fs::write(&late, "late").unwrap();
+
+    // exports 按已完成步骤的产物重新求值，不依赖保存的 variables
+    let mut variables = previous.variables.clone();
+    variables.remove("staged");
+    let mut ctx = PipelineContext::with_variables(variables);
+    let completed = previous.completed_steps().unwrap();
+    let report = resume_pipeline(&pipeline, &mut ctx, &failed.run_id, completed).expect("report");
+
+    assert_eq!(report.status, RunStatus::Success);
+    assert_eq!(
+        ctx.variables["staged"],
+        dir.path().join("stage").display().to_string()
+    );
+    assert!(dir.path().join("stage").join("late.txt").exists());
+}
@@ -0,0 +1,124 @@
+//! 依赖就绪调度、max_parallel 与 module 并发上限测试
+#![cfg(unix)]
+
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use cx::pipeline::config::{PipelineConfig, StepConfig};
+use cx::pipeline::context::PipelineContext;
+use cx::pipeline::orchestrator::run_pipeline;
+use cx::pipeline::report::RunStatus;
+use serde_json::json;
+
+/// 写入开始 / 结束标记的脚本，用于观察步骤是否重叠执行
+fn exec_step(dir: &Path, id: &str, sleep: &str, deps: Vec<&str>) -> StepConfig {
+    let script = dir.join(format!("{id}.sh"));
+    let log = dir.join("log.txt");
+    fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\necho start >> '{0}'\nsleep {sleep}\necho end >> '{0}'\necho '{{\"path\":\"{0}\",\"data\":{{}}}}'\n",
+            log.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    StepConfig {
+        id: id.into(),
+        module: "exec".into(),
+        action: Some("run".into()),
+        depends_on: deps.into_iter().map(str::to_string).collect(),
+        params: json!({ "script": script.display().to_string(), "args": [] }),
+        ..Default::default()
+    }
+}
+
+fn uuid_step(id: &str, deps: Vec<&str>) -> StepConfig {
+    StepConfig {
+        id: id.into(),
+        module: "generate".into(),
+        action: Some("uuid".into()),
+        depends_on: deps.into_iter().map(str::to_string).collect(),
+        params: json!({ "count": 1, "uppercase": false }),
+        ..Default::default()
+    }
+}
+
+fn log(dir: &Path) -> Vec<String> {
+    fs::read_to_string(dir.join("log.txt"))
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn slow_branch_does_not_block_unrelated_steps() {
+    let dir = tempfile::tempdir().unwrap();
+    let pipeline = PipelineConfig {
+        id: "ready-queue".into(),
+        steps: vec![
+            uuid_step("start", vec![]),
+            exec_step(dir.path(), "slow", "1", vec!["start"]),
+            uuid_step("fast", vec!["start"]),
+            uuid_step("after_fast", vec!["fast"]),
+            uuid_step("after_slow", vec!["slow"]),
+        ],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+
+    assert_eq!(report.status, RunStatus::Success);
+    let order: Vec<&str> = report.steps.iter().map(|s| s.id.as_str()).collect();
+    let pos = |id: &str| order.iter().position(|s| *s == id).unwrap();
+    assert!(pos("after_fast") < pos("slow"), "{order:?}");
+    assert!(pos("slow") < pos("after_slow"), "{order:?}");
+}
+
+#[test]
+fn module_concurrency_serializes_steps() {
+    let dir = tempfile::tempdir().unwrap();
+    let pipeline = PipelineConfig {
+        id: "limited".into(),
+        concurrency: HashMap::from([("exec".to_string(), 1)]),
+        steps: vec![
+            uuid_step("start", vec![]),
+            exec_step(dir.path(), "a", "0.3", vec!["start"]),
+            exec_step(dir.path(), "b", "0.3", vec!["start"]),
+            uuid_step("c", vec!["start"]),
+        ],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+
+    assert_eq!(report.status, RunStatus::Success);
+    assert_eq!(report.steps.len(), 4);
+    assert_eq!(log(dir.path()), vec!["start", "end", "start", "end"]);
+}
+
+#[test]
+fn max_parallel_limits_running_steps() {
+    let dir = tempfile::tempdir().unwrap();
+    let pipeline = PipelineConfig {
+        id: "max-parallel".into(),
+        max_parallel: Some(1),
+        steps: vec![
+            uuid_step("start", vec![]),
+            exec_step(dir.path(), "a", "0.3", vec!["start"]),
+            exec_step(dir.path(), "b", "0.3", vec!["start"]),
+        ],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+
+    assert_eq!(report.status, RunStatus::Success);
+    assert_eq!(log(dir.path()), vec!["start", "end", "start", "end"]);
+}
@@ -0,0 +1,132 @@
+//! `module: pipeline` 子 Pipeline 步骤测试
+
+use std::collections::HashMap;
+use std::fs;
+
+use cx::pipeline::config::{PipelineConfig, PipelinesConfig, StepConfig, validate_config};
+use cx::pipeline::context::PipelineContext;
+use cx::pipeline::orchestrator::run_pipeline;
+use cx::pipeline::report::{RunStatus, StepStatus};
+use serde_json::json;
+
+fn config(base: &str) -> PipelinesConfig {
+    let package = PipelineConfig {
+        id: "package".into(),
+        steps: vec![StepConfig {
+            id: "copy_app".into(),
+            module: "copy".into(),
+            params: json!({
+                "from": "${var.base}/${var.app}.txt",
+                "to": "${var.base}/out/${var.app}.txt",
+                "empty": false,
+            }),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let parent = PipelineConfig {
+        id: "release".into(),
+        steps: vec![
+            StepConfig {
+                id: "pack_h5".into(),
+                module: "pipeline".into(),
+                params: json!({ "pipeline": "package", "variables": { "app": "h5" } }),
+                ..Default::default()
+            },
+            StepConfig {
+                id: "publish".into(),
+                module: "copy".into(),
+                params: json!({
+                    "from": "${steps.pack_h5.artifact.path}",
+                    "to": "${var.base}/publish/${steps.pack_h5.artifact.data.status}.txt",
+                    "empty": false,
+                }),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    PipelinesConfig {
+        version: 3,
+        variables: HashMap::from([("base".to_string(), base.to_string())]),
+        pipelines: vec![package, parent],
+    }
+}
+
+#[test]
+fn subpipeline_runs_with_variables_and_nests_report() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("h5.txt"), "h5").unwrap();
+    let config = config(&dir.path().display().to_string());
+    validate_config(&config).unwrap();
+
+    let mut ctx = PipelineContext::from_config(&config);
+    let report = run_pipeline(&config.pipelines[1], &mut ctx).expect("should return report");
+
+    assert_eq!(report.status, RunStatus::Success);
+    assert!(dir.path().join("out").join("h5.txt").exists());
+    assert!(dir.path().join("publish").join("success.txt").exists());
+
+    let step = &report.steps[0];
+    assert_eq!(step.status, StepStatus::Success);
+    let child = step.pipeline.as_ref().expect("nested report");
+    assert_eq!(child.pipeline_id, "package");
+    assert_eq!(child.steps[0].id, "copy_app");
+    let data = &step.artifact.as_ref().unwrap().data;
+    assert!(data.get("report").is_none());
+    assert_eq!(data["steps"], 1);
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["steps"][0]["pipeline"]["pipeline_id"], "package");
+}
+
+#[test]
+fn failed_subpipeline_fails_step() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(&dir.path().display().to_string());
+
+    let mut ctx = PipelineContext::from_config(&config);
+    let report = run_pipeline(&config.pipelines[1], &mut ctx).expect("should return report");
+
+    assert_eq!(report.status, RunStatus::Failed);
+    assert_eq!(report.steps.len(), 1);
+    let step = &report.steps[0];
+    assert_eq!(step.status, StepStatus::Failed);
+    assert!(
+        step.error
+            .as_deref()
+            .unwrap()
+            .contains("子 Pipeline 'package'")
+    );
+    assert_eq!(step.pipeline.as_ref().unwrap().status, RunStatus::Failed);
+}
+
+#[test]
+fn exports_and_outputs_flow_to_parent() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("h5.txt"), "h5").unwrap();
+    let mut config = config(&dir.path().display().to_string());
+    let package = &mut config.pipelines[0];
+    package.steps[0].exports = [("copied".into(), "${steps.copy_app.artifact.path}".into())].into();
+    package.outputs = [("file".into(), "${var.copied}".into())].into();
+    let release = &mut config.pipelines[1];
+    release.steps[0].exports = [(
+        "packed".into(),
+        "${steps.pack_h5.artifact.data.outputs.file}".into(),
+    )]
+    .into();
+    release.steps[1].params["from"] = json!("${var.packed}");
+    release.outputs = [("published".into(), "${steps.publish.artifact.path}".into())].into();
+    validate_config(&config).unwrap();
+
+    let mut ctx = PipelineContext::from_config(&config);
+    let report = run_pipeline(&config.pipelines[1], &mut ctx).expect("should return report");
+
+    assert_eq!(report.status, RunStatus::Success);
+    let child = report.steps[0].pipeline.as_ref().unwrap();
+    assert!(child.outputs["file"].ends_with("h5.txt"));
+    assert_eq!(ctx.variables["packed"], child.outputs["file"]);
+    assert!(report.outputs["published"].contains("publish"));
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["outputs"]["published"], report.outputs["published"]);
+}
@@ -0,0 +1,170 @@
+//! 步骤 / Pipeline 超时与协作取消测试（exec 子进程被终止）
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use cx::pipeline::config::{PipelineConfig, StepConfig};
+use cx::pipeline::context::PipelineContext;
+use cx::pipeline::orchestrator::run_pipeline;
+use cx::pipeline::report::{RunStatus, StepStatus};
+use serde_json::json;
+
+fn write_sleep_script(dir: &Path) -> PathBuf {
+    let path = dir.join("slow.sh");
+    fs::write(&path, "#!/bin/sh\nsleep 30\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+fn exec_step(id: &str, script: &Path) -> StepConfig {
+    StepConfig {
+        id: id.into(),
+        module: "exec".into(),
+        action: Some("run".into()),
+        params: json!({ "script": script.display().to_string(), "args": [] }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn step_timeout_kills_running_script() {
+    let dir = tempfile::tempdir().unwrap();
+    let script = write_sleep_script(dir.path());
+    let mut step = exec_step("slow", &script);
+    step.timeout_ms = Some(300);
+
+    let pipeline = PipelineConfig {
+        id: "step-timeout".into(),
+        steps: vec![step],
+        ..Default::default()
+    };
+
+    let started = Instant::now();
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert_eq!(report.status, RunStatus::Failed);
+    assert_eq!(report.steps[0].status, StepStatus::TimedOut);
+    assert_eq!(
+        report.first_fail(),
+        Some(("slow", "步骤执行超时（300 ms）"))
+    );
+}
+
+#[test]
+fn pipeline_timeout_stops_remaining_steps() {
+    let dir = tempfile::tempdir().unwrap();
+    let script = write_sleep_script(dir.path());
+    let mut after = exec_step("after", &script);
+    after.depends_on = vec!["slow".into()];
+
+    let pipeline = PipelineConfig {
+        id: "pipeline-timeout".into(),
+        timeout_ms: Some(300),
+        steps: vec![exec_step("slow", &script), after],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+
+    assert_eq!(report.status, RunStatus::Failed);
+    assert_eq!(report.steps.len(), 1);
+    assert_eq!(report.steps[0].status, StepStatus::TimedOut);
+    assert_eq!(
+        report.first_fail(),
+        Some(("slow", "Pipeline 总超时（300 ms）"))
+    );
+}
+
+#[test]
+fn step_timeout_kills_spawned_grandchildren() {
+    let dir = tempfile::tempdir().unwrap();
+    let pid_file = dir.path().join("grandchild.pid");
+    let script = dir.path().join("spawn.sh");
+    fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\nsleep 30 &\necho $! > {}\nwait\n",
+            pid_file.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    let mut step = exec_step("spawn", &script);
+    step.timeout_ms = Some(500);
+
+    let pipeline = PipelineConfig {
+        id: "grandchild-timeout".into(),
+        steps: vec![step],
+        ..Default::default()
+    };
+
+    let started = Instant::now();
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert_eq!(report.steps[0].status, StepStatus::TimedOut);
+    // 被终止的孙进程可能暂为僵尸进程，按 /proc 状态判断
+    let pid = fs::read_to_string(&pid_file).unwrap();
+    std::thread::sleep(Duration::from_millis(100));
+    let status = fs::read_to_string(format!("/proc/{}/status", pid.trim())).unwrap_or_default();
+    let alive = status
+        .lines()
+        .any(|l| l.starts_with("State:") && !l.contains("zombie"));
+    assert!(
+        !alive,
+        "grandchild {} should be killed with the script",
+        pid.trim()
+    );
+}
+
+#[test]
+fn failed_step_cancels_running_siblings() {
+    let dir = tempfile::tempdir().unwrap();
+    let slow = write_sleep_script(dir.path());
+    let fail = dir.path().join("fail.sh");
+    fs::write(&fail, "#!/bin/sh\nsleep 0.3\nexit 1\n").unwrap();
+    fs::set_permissions(&fail, fs::Permissions::from_mode(0o755)).unwrap();
+
+    // 两步同依赖 root 而并行执行
+    let root = StepConfig {
+        id: "root".into(),
+        module: "generate".into(),
+        action: Some("uuid".into()),
+        params: json!({ "count": 1, "uppercase": false }),
+        ..Default::default()
+    };
+    let (mut slow, mut fail) = (exec_step("slow", &slow), exec_step("fail", &fail));
+    slow.depends_on = vec!["root".into()];
+    fail.depends_on = vec!["root".into()];
+
+    let pipeline = PipelineConfig {
+        id: "abort-siblings".into(),
+        steps: vec![root, slow, fail],
+        ..Default::default()
+    };
+
+    let started = Instant::now();
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert_eq!(report.status, RunStatus::Failed);
+    let slow = report.steps.iter().find(|s| s.id == "slow").unwrap();
+    assert_eq!(slow.status, StepStatus::Cancelled);
+    assert_eq!(
+        slow.error.as_deref(),
+        Some("其他步骤失败，运行中的步骤已中止")
+    );
+    assert_eq!(report.first_fail().map(|(id, _)| id), Some("fail"));
+    // 时间线记录单元实际启动时刻：两者同时启动
+    let fail = report.steps.iter().find(|s| s.id == "fail").unwrap();
+    let (slow_at, fail_at) = (slow.started_ms.unwrap(), fail.started_ms.unwrap());
+    assert!(slow_at.abs_diff(fail_at) < 200, "{slow_at} / {fail_at}");
+}
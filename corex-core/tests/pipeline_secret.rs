@@ -0,0 +1,72 @@
+//! `${secret.*}` 解析与报告脱敏测试
+
+use std::collections::HashMap;
+use std::fs;
+
+use cx::pipeline::config::{PipelineConfig, StepConfig};
+use cx::pipeline::context::PipelineContext;
+use cx::pipeline::history::Trigger;
+use cx::pipeline::orchestrator::run_pipeline;
+use cx::pipeline::report::{RunStatus, StepStatus};
+use cx::pipeline::state::RunState;
+use cx::secret::store::SecretStore;
+use serde_json::json;
+
+const SECRET: &str = "hunter2-token";
+
+fn copy(id: &str, from: &str, to: &str) -> StepConfig {
+    StepConfig {
+        id: id.into(),
+        module: "copy".into(),
+        params: json!({ "from": from, "to": to, "empty": false }),
+        continue_on_error: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn secret_resolves_and_is_masked_in_report() {
+    let secrets = tempfile::tempdir().unwrap();
+    let mut store = SecretStore::open(secrets.path()).unwrap();
+    store.set("SRC_NAME", SECRET.into()).unwrap();
+    store.save().unwrap();
+    // 单测试进程：首次解析前设置，无并发读取环境变量
+    unsafe { std::env::set_var("COREX_SECRETS_DIR", secrets.path()) };
+
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join(format!("{SECRET}.txt")), "data").unwrap();
+    let pipeline = PipelineConfig {
+        id: "secret".into(),
+        steps: vec![
+            copy(
+                "copy_named",
+                "${var.base}/${var.name}.txt",
+                "${var.base}/out/copied.txt",
+            ),
+            copy(
+                "copy_missing",
+                "${var.base}/missing-${secret.SRC_NAME}",
+                "${var.base}/out/missing.txt",
+            ),
+        ],
+        ..Default::default()
+    };
+    let mut ctx = PipelineContext::with_variables(HashMap::from([
+        ("base".to_string(), dir.path().display().to_string()),
+        ("name".to_string(), "${secret.SRC_NAME}".to_string()),
+    ]));
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+
+    assert_eq!(report.status, RunStatus::Success);
+    assert!(dir.path().join("out").join("copied.txt").exists());
+    assert_eq!(report.steps[1].status, StepStatus::FailedTolerated);
+    let error = report.steps[1].error.as_deref().unwrap();
+    assert!(error.contains("missing-***"), "{error}");
+
+    let json = serde_json::to_string(&report).unwrap();
+    assert!(!json.contains(SECRET), "{json}");
+
+    // 运行状态不落盘密钥明文
+    let state = RunState::new(Trigger::Manual, &ctx, &report);
+    assert_eq!(state.variables["name"], "${secret.SRC_NAME}");
+}
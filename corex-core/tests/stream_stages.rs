@@ -0,0 +1,154 @@
+//! stream 链（copy → shade → codec hash → zip）测试
+
+use std::fs;
+
+use cx::pipeline::config::{PipelineConfig, StepConfig};
+use cx::pipeline::context::PipelineContext;
+use cx::pipeline::orchestrator::run_pipeline;
+use cx::pipeline::report::{RunStatus, StepStatus};
+use cx::pipeline::stream::StageKind;
+use serde_json::json;
+
+fn stream_step(id: &str, module: &str, params: serde_json::Value) -> StepConfig {
+    StepConfig {
+        id: id.into(),
+        module: module.into(),
+        kind: StageKind::Stream,
+        params,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn stream_chain_copies_converts_hashes_and_zips() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let src = dir.path().join("src");
+    fs::create_dir_all(src.join("nested")).unwrap();
+    image::RgbImage::new(4, 4).save(src.join("a.png")).unwrap();
+    image::RgbImage::new(2, 2)
+        .save(src.join("nested").join("b.png"))
+        .unwrap();
+    fs::write(src.join("readme.txt"), "hello").unwrap();
+
+    let copies = dir.path().join("copies");
+    let images = dir.path().join("images");
+    let archive = dir.path().join("out.zip");
+
+    let mut hash = stream_step("hash", "codec", json!({}));
+    hash.action = Some("hash".into());
+    hash.algorithm = Some("md5".into());
+    let mut pack = stream_step("pack", "compression", json!({ "to": archive }));
+    pack.action = Some("compress".into());
+    pack.format = Some("zip".into());
+
+    let pipeline = PipelineConfig {
+        id: "stream".into(),
+        steps: vec![
+            stream_step(
+                "copy",
+                "copy",
+                json!({ "from": src, "to": copies, "empty": false }),
+            ),
+            stream_step(
+                "shade",
+                "shade",
+                json!({ "to": images, "format": "jpg", "quality": 90 }),
+            ),
+            hash,
+            pack,
+        ],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("pipeline should complete");
+
+    assert_eq!(report.status, RunStatus::Success);
+    assert_eq!(report.steps.len(), 4);
+    assert!(report.steps.iter().all(|s| s.status == StepStatus::Success));
+    assert!(report.steps.iter().all(|s| s.items == 3));
+
+    assert!(images.join("a.jpg").exists());
+    assert!(images.join("nested").join("b.jpg").exists());
+
+    let hashes = &ctx.step_artifacts["hash"].data["hashes"];
+    assert_eq!(hashes.as_object().unwrap().len(), 3);
+
+    let zip = zip::ZipArchive::new(fs::File::open(&archive).unwrap()).unwrap();
+    let mut names: Vec<&str> = zip.file_names().collect();
+    names.sort();
+    assert_eq!(names, vec!["a.jpg", "nested/b.jpg", "readme.txt"]);
+}
+
+#[test]
+fn stream_chain_keeps_nested_pass_through_files() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let src = dir.path().join("src");
+    fs::create_dir_all(src.join("a")).unwrap();
+    fs::create_dir_all(src.join("b")).unwrap();
+    image::RgbImage::new(2, 2).save(src.join("a").join("icon.png")).unwrap();
+    fs::write(src.join("a").join("readme.txt"), "a").unwrap();
+    fs::write(src.join("b").join("readme.txt"), "b").unwrap();
+
+    let archive = dir.path().join("out.zip");
+    let mut pack = stream_step("pack", "compression", json!({ "to": archive }));
+    pack.action = Some("compress".into());
+    pack.format = Some("zip".into());
+
+    let pipeline = PipelineConfig {
+        id: "stream-nested".into(),
+        steps: vec![
+            stream_step(
+                "copy",
+                "copy",
+                json!({ "from": src, "to": dir.path().join("copies"), "empty": false }),
+            ),
+            stream_step(
+                "shade",
+                "shade",
+                json!({ "to": dir.path().join("images"), "format": "jpg", "quality": 90 }),
+            ),
+            pack,
+        ],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("pipeline should complete");
+    assert_eq!(report.status, RunStatus::Success, "{:?}", report.first_fail());
+
+    let zip = zip::ZipArchive::new(fs::File::open(&archive).unwrap()).unwrap();
+    let mut names: Vec<&str> = zip.file_names().collect();
+    names.sort();
+    assert_eq!(names, vec!["a/icon.jpg", "a/readme.txt", "b/readme.txt"]);
+}
+
+#[test]
+fn stream_chain_reports_failing_stage() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let src = dir.path().join("src");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("broken.png"), "not an image").unwrap();
+
+    let pipeline = PipelineConfig {
+        id: "stream-fail".into(),
+        steps: vec![
+            stream_step(
+                "copy",
+                "copy",
+                json!({ "from": src, "to": dir.path().join("copies"), "empty": false }),
+            ),
+            stream_step(
+                "shade",
+                "shade",
+                json!({ "to": dir.path().join("images"), "quality": 100 }),
+            ),
+        ],
+        ..Default::default()
+    };
+
+    let mut ctx = PipelineContext::new();
+    let report = run_pipeline(&pipeline, &mut ctx).expect("should return report");
+    assert_eq!(report.status, RunStatus::Failed);
+    assert_eq!(report.first_fail().map(|(id, _)| id), Some("shade"));
+}
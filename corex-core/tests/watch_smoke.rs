@@ -14,7 +14,6 @@ fn watch_pipeline_passes_validation() {
         pipelines: vec![PipelineConfig {
             id: "watched".into(),
             description: Some("dev watch".into()),
-            schedule: None,
             watch: Some(WatchConfig {
                 paths: vec![".".into()],
                 includes: vec!["**/*.rs".into()],
@@ -29,6 +28,7 @@ fn watch_pipeline_passes_validation() {
                 params: json!({}),
                 ..Default::default()
             }],
+            ..Default::default()
         }],
     };
 